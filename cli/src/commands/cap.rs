@@ -1,4 +1,4 @@
-use account_multisig_sdk::{MultisigClient, proposals::params::ParamsArgs};
+use account_multisig_sdk::{MultisigClient, proposals::actions::IntentType};
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
 use sui_crypto::ed25519::Ed25519PrivateKey;
@@ -8,6 +8,11 @@ use crate::tx_utils;
 
 #[derive(Debug, Subcommand)]
 pub enum CapCommands {
+    #[command(
+        name = "list-depositable",
+        about = "List the Caps owned by your wallet"
+    )]
+    ListDepositable,
     #[command(name = "deposit-cap", about = "Deposit a Cap into the multisig")]
     DepositCap {
         #[arg(long, short, help = "Address of the Cap object")]
@@ -33,25 +38,40 @@ pub enum CapCommands {
 }
 
 impl CapCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
         match self {
+            CapCommands::ListDepositable => {
+                let caps = client.find_depositable_caps(tx_utils::address(pk)?).await?;
+                if caps.is_empty() {
+                    println!("No Cap found in your wallet");
+                }
+                for (id, cap_type) in caps {
+                    println!("{} - {}", id, cap_type);
+                }
+                Ok(())
+            }
             CapCommands::DepositCap { cap_id, cap_type } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
                 client.deposit_cap(&mut builder, *cap_id, cap_type).await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
             }
             CapCommands::ProposeBorrowCap { name, cap_type } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::BorrowCap)?;
                 client
                     .request_borrow_cap(&mut builder, intent_args, cap_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
         }
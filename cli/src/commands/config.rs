@@ -1,19 +1,66 @@
 use account_multisig_sdk::{
     MultisigClient,
-    proposals::params::{ConfigMultisigArgs, ParamsArgs},
+    proposals::{
+        actions::{ConfigMultisigFields, IntentType},
+        params::ConfigMultisigArgs,
+        simulation::{ConfigWarning, analyze_config_change},
+    },
 };
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
+use colored::*;
 use sui_crypto::ed25519::Ed25519PrivateKey;
 use std::str::FromStr;
 
 use crate::parsers::{Member, Role};
+use crate::settings::Settings;
 use crate::tx_utils;
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommands {
+    #[command(
+        name = "get",
+        about = "Show the cli settings (config.toml), all of them if no key is passed"
+    )]
+    Get { key: Option<String> },
+    #[command(
+        name = "set",
+        about = "Set a cli setting used at the next start, unset it if no value is passed"
+    )]
+    Set { key: String, value: Option<String> },
     #[command(name = "modify-name", about = "Modify multisig name")]
     ModifyName { name: String },
+    #[command(
+        name = "propose-metadata",
+        about = "Propose a metadata change that is applied once the proposal is approved and executed"
+    )]
+    ProposeMetadata {
+        #[arg(help = "Name of the proposal")]
+        key: String,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        image: Option<String>,
+        #[arg(long, help = "Replaces all the links, e.g. --link twitter=https://x.com/team")]
+        link: Option<Vec<String>>,
+    },
+    #[command(
+        name = "analyze-removal",
+        about = "Show the thresholds and proposals that would be blocked without a member"
+    )]
+    AnalyzeRemoval { address: String },
+    #[command(
+        name = "lint",
+        about = "Check the multisig for inconsistencies (undefined roles, unreachable thresholds, ...)"
+    )]
+    Lint,
+    #[command(
+        name = "list-invites",
+        about = "List the invites sent by the multisig that are still pending"
+    )]
+    ListInvites,
     #[command(
         name = "propose-config-multisig",
         about = "Create a proposal with a new config (overrides the current state with the new one)"
@@ -31,16 +78,101 @@ pub enum ConfigCommands {
 }
 
 impl ConfigCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
-        client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         match self {
+            // the settings don't need a multisig
+            ConfigCommands::Get { key } => get_settings(key.as_deref()),
+            ConfigCommands::Set { key, value } => set_setting(key, value.as_deref()),
+            _ if client.multisig().is_none() => Err(anyhow!("Multisig not loaded")),
             ConfigCommands::ModifyName { name } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                // the other metadata (description, links) is kept
+                let mut profile = client
+                    .multisig()
+                    .ok_or(anyhow!("Multisig not loaded"))?
+                    .profile();
+                profile.set_name(name);
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                client.replace_profile(&mut builder, &profile).await?;
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                Ok(())
+            }
+            ConfigCommands::ProposeMetadata {
+                key,
+                name,
+                description,
+                image,
+                link,
+            } => {
+                let mut profile = client
+                    .multisig()
+                    .ok_or(anyhow!("Multisig not loaded"))?
+                    .profile();
+                if let Some(name) = name {
+                    profile.set_name(name);
+                }
+                if let Some(description) = description {
+                    profile.set_description(Some(description));
+                }
+                if let Some(image) = image {
+                    profile.set_image(Some(image));
+                }
+                if let Some(links) = link {
+                    let links = links
+                        .iter()
+                        .map(|link| {
+                            link.split_once('=')
+                                .ok_or(anyhow!("Invalid link {}, expected <key>=<url>", link))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    profile.set_links(&links)?;
+                }
+
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let params = client.intent_params(key).await?;
                 client
-                    .replace_metadata(&mut builder, vec!["name".to_string()], vec![name.clone()])
+                    .request_config_metadata(&mut builder, params, &profile)
                     .await?;
-                tx_utils::execute(client.sui(), builder, &pk).await?;
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, key);
+                Ok(())
+            }
+            ConfigCommands::AnalyzeRemoval { address } => {
+                let address = client.resolve_address(address).await?;
+                let warnings = client
+                    .multisig()
+                    .ok_or(anyhow!("Multisig not loaded"))?
+                    .analyze_member_removal(address)?;
+                print_warnings(&warnings);
+                Ok(())
+            }
+            ConfigCommands::Lint => {
+                let warnings = client
+                    .multisig_mut()
+                    .ok_or(anyhow!("Multisig not loaded"))?
+                    .lint()
+                    .await?;
+                if warnings.is_empty() {
+                    println!("{}", "No issue found".green());
+                }
+                for warning in warnings {
+                    println!("{} {}", "Warning:".yellow(), warning);
+                }
+                Ok(())
+            }
+            ConfigCommands::ListInvites => {
+                let invites = client
+                    .multisig()
+                    .ok_or(anyhow!("Multisig not loaded"))?
+                    .pending_invites()
+                    .await?;
+                println!("\n=== PENDING INVITES ===\n");
+                for invite in invites {
+                    println!("{} - {}", invite.recipient, invite.id);
+                }
                 Ok(())
             }
             ConfigCommands::ProposeConfigMultisig {
@@ -49,21 +181,24 @@ impl ConfigCommands {
                 role,
                 global_threshold,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
 
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::ConfigMultisig)?;
 
                 // Convert Member and Role structs to the format expected by ConfigMultisigArgs
-                let addresses = member
-                    .as_ref()
-                    .map(|m| {
-                        m.iter()
-                            .map(|member| member.address.parse().unwrap())
-                            .collect()
-                    })
-                    .unwrap_or_default();
+                let addresses = match member {
+                    Some(m) => {
+                        client
+                            .resolve_addresses(
+                                &m.iter().map(|member| &member.address).collect::<Vec<_>>(),
+                            )
+                            .await?
+                    }
+                    None => Vec::new(),
+                };
 
                 let weights = member
                     .as_ref()
@@ -85,6 +220,25 @@ impl ConfigCommands {
                     .map(|r| r.iter().map(|role| role.threshold).collect())
                     .unwrap_or_default();
 
+                let proposed = ConfigMultisigFields {
+                    global: *global_threshold,
+                    members: addresses
+                        .iter()
+                        .zip(&weights)
+                        .zip(&roles)
+                        .map(|((address, weight), roles)| (*address, *weight, roles.clone()))
+                        .collect(),
+                    roles: role_names
+                        .iter()
+                        .cloned()
+                        .zip(role_thresholds.iter().copied())
+                        .collect(),
+                };
+                print_warnings(&analyze_config_change(
+                    client.multisig().ok_or(anyhow!("Multisig not loaded"))?,
+                    &proposed,
+                ));
+
                 let actions_args = ConfigMultisigArgs::new(
                     &mut builder,
                     addresses,
@@ -100,8 +254,45 @@ impl ConfigCommands {
                     .await?;
 
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
         }
     }
 }
+
+fn get_settings(key: Option<&str>) -> Result<()> {
+    let path = Settings::path()?;
+    let settings = Settings::load(&path)?;
+    match key {
+        Some(key) => println!(
+            "{}",
+            settings.get(key)?.unwrap_or_else(|| "not set".to_string())
+        ),
+        None => {
+            println!("{}", path.display().to_string().underline());
+            for (key, value) in settings.entries() {
+                println!("{} = {}", key, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set_setting(key: &str, value: Option<&str>) -> Result<()> {
+    let path = Settings::path()?;
+    let mut settings = Settings::load(&path)?;
+    settings.set(key, value)?;
+    settings.save(&path)?;
+    println!("{}", "Saved, restart the cli to apply it".green());
+    Ok(())
+}
+
+fn print_warnings(warnings: &[ConfigWarning]) {
+    if warnings.is_empty() {
+        println!("{}", "No threshold or pending proposal is affected".green());
+    }
+    for warning in warnings {
+        println!("{} {}", "Warning:".yellow(), warning);
+    }
+}
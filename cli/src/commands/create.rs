@@ -1,22 +1,24 @@
 use super::super::tx_utils;
 use account_multisig_sdk::{MultisigBuilder, MultisigClient};
 use anyhow::Result;
+use colored::*;
 use sui_crypto::ed25519::Ed25519PrivateKey;
 use crate::parsers::{Member, Role};
 
 #[allow(clippy::too_many_arguments)]
 pub async fn create_multisig(
     client: &MultisigClient,
-    pk: &Ed25519PrivateKey,
+    pk: Option<&Ed25519PrivateKey>,
     name: Option<String>,
     global_threshold: Option<u64>,
     members: Option<Vec<Member>>,
     roles: Option<Vec<Role>>,
+    auto_execute: bool,
 ) -> Result<()> {
-    let address = pk.public_key().derive_address();
-    let mut builder = tx_utils::init(client.sui(), address).await?;
+    let address = tx_utils::address(pk)?;
+    let mut builder = tx_utils::init(client, address).await?;
 
-    let mut multisig = MultisigBuilder::new(client, &mut builder);
+    let mut multisig = MultisigBuilder::new(client, &mut builder).auto_execute(auto_execute);
     if let Some(name) = name {
         multisig = multisig.set_name(name.as_str());
     }
@@ -26,8 +28,9 @@ pub async fn create_multisig(
 
     if let Some(members) = members {
         for member in members {
+            let address = client.resolve_address(&member.address).await?.to_string();
             multisig = multisig.add_member(
-                member.address.as_str(),
+                address.as_str(),
                 member.weight,
                 member.roles.iter().map(|r| r.as_str()).collect(),
             );
@@ -40,6 +43,18 @@ pub async fn create_multisig(
         }
     }
 
+    for warning in multisig.warnings() {
+        println!("{} {}", "Warning:".yellow(), warning);
+    }
+    let estimate = multisig.estimate().await?;
+    println!(
+        "Creating the multisig costs {} MIST: {} protocol fee (paid to {}) and about {} gas",
+        estimate.total(),
+        estimate.fee,
+        client.fees().await?.recipient,
+        estimate.net_gas()
+    );
+
     multisig.build().await?;
     tx_utils::execute(client.sui(), builder, pk).await?;
 
@@ -1,12 +1,16 @@
 use account_multisig_sdk::{
     MultisigClient,
-    proposals::params::{
-        DisableRulesArgs, MintAndTransferArgs, MintAndVestArgs, ParamsArgs, UpdateMetadataArgs,
-        WithdrawAndBurnArgs,
+    proposals::{
+        actions::IntentType,
+        params::{
+            DisableRulesArgs, MintAndTransferArgs, MintAndVestArgs, UpdateMetadataArgs,
+            WithdrawAndBurnArgs, parse_transfers_csv, total_amount,
+        },
     },
 };
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
+use std::{fs::File, path::PathBuf};
 use sui_crypto::ed25519::Ed25519PrivateKey;
 use sui_sdk_types::{Address, ObjectId};
 
@@ -77,8 +81,15 @@ pub enum CurrencyCommands {
         coin_type: String,
         #[arg(long, help = "Amounts to mint")]
         amounts: Vec<u64>,
-        #[arg(long, help = "Recipients")]
-        recipients: Vec<Address>,
+        #[arg(long, help = "Recipient addresses or SuiNS names")]
+        recipients: Vec<String>,
+        #[arg(
+            long,
+            help = "CSV file of recipient,amount lines with amounts in coin units (replaces --amounts and --recipients)"
+        )]
+        csv: Option<PathBuf>,
+        #[arg(long, help = "Propose even if the local policy forbids it")]
+        force: bool,
     },
     #[command(
         name = "propose-mint-and-vest",
@@ -95,8 +106,10 @@ pub enum CurrencyCommands {
         start_timestamp: u64,
         #[arg(long, help = "Vesting end timestamp (ms since epoch)")]
         end_timestamp: u64,
-        #[arg(long, help = "Recipient address")]
-        recipient: Address,
+        #[arg(long, help = "Recipient address or SuiNS name")]
+        recipient: String,
+        #[arg(long, help = "Propose even if the local policy forbids it")]
+        force: bool,
     },
     #[command(
         name = "propose-withdraw-and-burn",
@@ -115,7 +128,11 @@ pub enum CurrencyCommands {
 }
 
 impl CurrencyCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
         match self {
             CurrencyCommands::DepositTreasuryCap {
@@ -123,8 +140,8 @@ impl CurrencyCommands {
                 cap_id,
                 coin_type,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
                 client
                     .deposit_treasury_cap(&mut builder, *max_supply, *cap_id, coin_type)
                     .await?;
@@ -141,10 +158,12 @@ impl CurrencyCommands {
                 update_description,
                 update_icon,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::DisableRules)?;
                 let actions_args = DisableRulesArgs::new(
                     &mut builder,
                     *mint,
@@ -158,6 +177,7 @@ impl CurrencyCommands {
                     .request_disable_rules(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             CurrencyCommands::ProposeUpdateMetadata {
@@ -168,10 +188,12 @@ impl CurrencyCommands {
                 description,
                 icon_url,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::UpdateMetadata)?;
                 let actions_args = UpdateMetadataArgs::new(
                     &mut builder,
                     symbol.clone(),
@@ -183,6 +205,7 @@ impl CurrencyCommands {
                     .request_update_metadata(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             CurrencyCommands::ProposeMintAndTransfer {
@@ -190,17 +213,32 @@ impl CurrencyCommands {
                 coin_type,
                 amounts,
                 recipients,
+                csv,
+                force,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
-                let actions_args =
-                    MintAndTransferArgs::new(&mut builder, amounts.clone(), recipients.clone());
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let (amounts, recipients) = match csv {
+                    Some(path) => {
+                        let decimals = client.coin_metadata(coin_type).await?.decimals;
+                        parse_transfers_csv(File::open(path)?, decimals)?
+                    }
+                    None => (amounts.clone(), client.resolve_addresses(recipients).await?),
+                };
+                client.validate_mint(coin_type, total_amount(&amounts)?)?;
+
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::MintAndTransfer)?;
+                let actions_args = MintAndTransferArgs::new(&mut builder, amounts, recipients);
+                // the request is checked against the policy, unless forced
                 client
+                    .skip_policy(*force)
                     .request_mint_and_transfer(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             CurrencyCommands::ProposeMintAndVest {
@@ -210,11 +248,17 @@ impl CurrencyCommands {
                 start_timestamp,
                 end_timestamp,
                 recipient,
+                force,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let recipient = &client.resolve_address(recipient).await?;
+                client.validate_mint(coin_type, *total_amount)?;
+
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::MintAndVest)?;
                 let actions_args = MintAndVestArgs::new(
                     &mut builder,
                     *total_amount,
@@ -222,10 +266,13 @@ impl CurrencyCommands {
                     *end_timestamp,
                     *recipient,
                 );
+                // the request is checked against the policy, unless forced
                 client
+                    .skip_policy(*force)
                     .request_mint_and_vest(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             CurrencyCommands::ProposeWithdrawAndBurn {
@@ -234,15 +281,18 @@ impl CurrencyCommands {
                 coin_id,
                 amount,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::WithdrawAndBurn)?;
                 let actions_args = WithdrawAndBurnArgs::new(&mut builder, *coin_id, *amount);
                 client
                     .request_withdraw_and_burn(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
         }
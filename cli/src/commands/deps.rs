@@ -1,6 +1,6 @@
 use account_multisig_sdk::{
     MultisigClient,
-    proposals::params::{ConfigDepsArgs, ParamsArgs},
+    proposals::{actions::IntentType, params::ConfigDepsArgs},
 };
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
@@ -38,12 +38,15 @@ pub enum DepsCommands {
 }
 
 impl DepsCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
         match self {
             DepsCommands::UpdateToLatest => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
                 client.update_verified_deps_to_latest(&mut builder).await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
@@ -54,11 +57,12 @@ impl DepsCommands {
                 addresses,
                 versions,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
 
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::ConfigDeps)?;
                 let actions_args = ConfigDepsArgs::new(
                     &mut builder,
                     names.clone(),
@@ -71,20 +75,29 @@ impl DepsCommands {
                     .await?;
 
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             DepsCommands::ProposeToggleUnverifiedAllowed { name } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
 
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::ToggleUnverifiedAllowed)?;
 
                 client
                     .request_toggle_unverified_allowed(&mut builder, intent_args)
                     .await?;
 
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                if let Some(multisig) = client.multisig() {
+                    println!(
+                        "Unverified deps allowed will become: {}",
+                        !multisig.unverified_deps_allowed
+                    );
+                }
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
         }
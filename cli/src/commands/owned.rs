@@ -1,6 +1,11 @@
 use account_multisig_sdk::{
     MultisigClient,
-    proposals::params::{ParamsArgs, WithdrawAndTransferArgs, WithdrawAndVestArgs},
+    proposals::{
+        actions::IntentType,
+        batch::ProposalBatch,
+        params::{WithdrawAndTransferArgs, WithdrawAndVestArgs},
+        scaffold::Scaffold,
+    },
 };
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
@@ -11,6 +16,11 @@ use crate::tx_utils;
 
 #[derive(Debug, Subcommand)]
 pub enum OwnedCommands {
+    #[command(name = "deposit", about = "Deposit an object from wallet into the multisig")]
+    Deposit {
+        #[arg(long, help = "Object id to deposit")]
+        object_id: Address,
+    },
     #[command(
         name = "propose-withdraw-and-transfer",
         about = "Propose to withdraw and transfer owned objects"
@@ -20,8 +30,8 @@ pub enum OwnedCommands {
         name: String,
         #[arg(long, help = "Object IDs to withdraw")]
         object_ids: Vec<ObjectId>,
-        #[arg(long, help = "Recipient addresses")]
-        recipients: Vec<Address>,
+        #[arg(long, help = "Recipient addresses or SuiNS names")]
+        recipients: Vec<String>,
     },
     #[command(
         name = "propose-withdraw-and-vest",
@@ -36,24 +46,51 @@ pub enum OwnedCommands {
         start_timestamp: u64,
         #[arg(long, help = "Vesting end timestamp in ms")]
         end_timestamp: u64,
-        #[arg(long, help = "Recipient address")]
-        recipient: Address,
+        #[arg(long, help = "Recipient address or SuiNS name")]
+        recipient: String,
+    },
+    #[command(
+        name = "propose-from-tx",
+        about = "Propose the withdrawal of the objects needed to run a transaction built outside of the multisig"
+    )]
+    ProposeFromTx {
+        #[arg(long, help = "Name of the proposal (suffixed with -1)")]
+        name: String,
+        #[arg(
+            long,
+            help = "Base64 of the transaction, e.g. from --serialize-unsigned-transaction"
+        )]
+        tx_bytes: String,
+        #[arg(long, help = "Only show the objects to withdraw")]
+        dry_run: bool,
     },
 }
 
 impl OwnedCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
         match self {
+            OwnedCommands::Deposit { object_id } => {
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                client.deposit_object(&mut builder, *object_id).await?;
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                Ok(())
+            }
             OwnedCommands::ProposeWithdrawAndTransfer {
                 name,
                 object_ids,
                 recipients,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let recipients = &client.resolve_addresses(recipients).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::WithdrawAndTransfer)?;
                 let actions_args = WithdrawAndTransferArgs::new(
                     &mut builder,
                     object_ids.clone(),
@@ -63,6 +100,7 @@ impl OwnedCommands {
                     .request_withdraw_and_transfer(&mut builder, intent_args, actions_args)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             OwnedCommands::ProposeWithdrawAndVest {
@@ -72,10 +110,12 @@ impl OwnedCommands {
                 end_timestamp,
                 recipient,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let recipient = &client.resolve_address(recipient).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::WithdrawAndVest)?;
                 let actions_args = WithdrawAndVestArgs::new(
                     &mut builder,
                     *coin_id,
@@ -87,6 +127,45 @@ impl OwnedCommands {
                     .request_withdraw_and_vest(&mut builder, intent_args, actions_args)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
+                Ok(())
+            }
+            OwnedCommands::ProposeFromTx {
+                name,
+                tx_bytes,
+                dry_run,
+            } => {
+                let scaffold = Scaffold::analyze_base64(client, tx_bytes)?;
+                for id in &scaffold.withdrawals {
+                    println!("Withdraw {} to the executor", id);
+                }
+                for id in &scaffold.locked {
+                    println!("{} is locked by a pending proposal", id);
+                }
+                for id in &scaffold.external {
+                    println!("{} is provided by the executor", id);
+                }
+                if *dry_run {
+                    return Ok(());
+                }
+
+                // the proposer runs the transaction once the withdrawal is executed
+                let executor = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, executor).await?;
+                let key = {
+                    let mut batch = ProposalBatch::new(client, &mut builder, name).await?;
+                    scaffold
+                        .propose(
+                            &mut batch,
+                            "Run a transaction built outside of the multisig",
+                            executor,
+                        )
+                        .await?
+                };
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, &key);
+                // the withdrawn objects get new versions and a new owner
+                println!("Rebuild the transaction once the objects are withdrawn to run it");
                 Ok(())
             }
         }
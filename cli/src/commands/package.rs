@@ -1,6 +1,9 @@
 use account_multisig_sdk::{
     MultisigClient,
-    proposals::params::{ParamsArgs, RestrictPolicyArgs, UpgradePackageArgs},
+    proposals::{
+        actions::IntentType,
+        params::{RestrictPolicyArgs, UpgradePackageArgs},
+    },
 };
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
@@ -50,7 +53,11 @@ pub enum PackageCommands {
 }
 
 impl PackageCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
         match self {
             PackageCommands::DepositUpgradeCap {
@@ -58,8 +65,7 @@ impl PackageCommands {
                 package_name,
                 timelock_duration,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
                 client
                     .deposit_upgrade_cap(&mut builder, *cap_id, package_name, *timelock_duration)
                     .await?;
@@ -71,16 +77,18 @@ impl PackageCommands {
                 package_name,
                 digest,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::UpgradePackage)?;
                 let actions_args =
                     UpgradePackageArgs::new(&mut builder, package_name.clone(), digest.clone());
                 client
                     .request_upgrade_package(&mut builder, intent_args, actions_args)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             PackageCommands::ProposeRestrictPolicy {
@@ -88,16 +96,18 @@ impl PackageCommands {
                 package_name,
                 policy,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::RestrictPolicy)?;
                 let actions_args =
                     RestrictPolicyArgs::new(&mut builder, package_name.clone(), *policy);
                 client
                     .request_restrict_policy(&mut builder, intent_args, actions_args)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
         }
@@ -1,17 +1,30 @@
 use std::str::FromStr;
 
-use account_multisig_sdk::{MultisigClient, proposals::actions::IntentType};
+use account_multisig_sdk::{
+    MultisigClient,
+    multisig::ApprovalStatus,
+    policy,
+    proposals::{
+        actions::IntentType,
+        coordinator::{ApprovalRequest, Preapproval},
+    },
+};
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
+use colored::*;
 use sui_crypto::ed25519::Ed25519PrivateKey;
 use sui_sdk_types::ObjectId;
+use sui_transaction_builder::TransactionBuilder;
 
 use crate::tx_utils;
 
 #[derive(Debug, Subcommand)]
 pub enum ProposalCommands {
     #[command(name = "approve", about = "Approve a proposal")]
-    Approve,
+    Approve {
+        #[arg(long, help = "Approval request shared by the proposer, checked before approving")]
+        request: Option<String>,
+    },
     #[command(name = "disapprove", about = "Remove approval from a proposal")]
     Disapprove,
     #[command(name = "execute", about = "Execute a proposal")]
@@ -22,27 +35,79 @@ pub enum ProposalCommands {
         modules: Option<String>,
         #[arg(short, long)]
         dependencies: Option<String>,
+        #[arg(long, help = "Invite the members added by a config multisig proposal")]
+        invite_new_members: bool,
+        #[arg(long, help = "Execute even if the local policy forbids it")]
+        force: bool,
+        #[arg(long, help = "Write the approval record to this file once executed")]
+        export: Option<String>,
     },
     #[command(name = "delete", about = "Delete a proposal")]
     Delete,
+    #[command(
+        name = "withdraw",
+        about = "Remove your approval from your proposal and delete it if possible"
+    )]
+    Withdraw,
+    #[command(name = "export", about = "Export the approval record of a proposal")]
+    Export {
+        #[arg(short, long, help = "File to write, printed if not set")]
+        output: Option<String>,
+    },
+    #[command(
+        name = "share",
+        about = "Print an approval request for the other members and who approved so far"
+    )]
+    Share,
+    #[command(
+        name = "preapprove",
+        about = "Sign an off-chain preapproval to send to the coordinator, nothing is submitted"
+    )]
+    Preapprove {
+        #[arg(
+            long,
+            help = "Approval request shared by the proposer, checked before signing"
+        )]
+        request: Option<String>,
+    },
+    #[command(
+        name = "tally",
+        about = "Check whether the approvals and the collected preapprovals reach the threshold"
+    )]
+    Tally {
+        #[arg(long, help = "Preapprovals sent by the members")]
+        preapproval: Vec<String>,
+    },
 }
 
 impl ProposalCommands {
     pub async fn run(
         &self,
         client: &mut MultisigClient,
-        pk: &Ed25519PrivateKey,
+        pk: Option<&Ed25519PrivateKey>,
         key: &str,
     ) -> Result<()> {
         match self {
-            ProposalCommands::Approve => self.approve(client, pk, key).await,
+            ProposalCommands::Approve { request } => {
+                self.approve(client, pk, key, request.as_deref()).await
+            }
             ProposalCommands::Disapprove => self.disapprove(client, pk, key).await,
             ProposalCommands::Execute {
                 package_id,
                 modules,
                 dependencies,
+                invite_new_members,
+                force,
+                export,
             } => match (package_id, modules, dependencies) {
-                (None, None, None) => self.execute(client, pk, key).await,
+                (None, None, None) => {
+                    self.execute(client, pk, key, *invite_new_members, *force)
+                        .await?;
+                    match export {
+                        Some(path) => export_record(client, key, Some(path)).await,
+                        None => Ok(()),
+                    }
+                }
                 (Some(package_id), Some(modules), Some(dependencies)) => {
                     self.execute_upgrade_package(client, pk, key, package_id, modules, dependencies)
                         .await
@@ -50,101 +115,105 @@ impl ProposalCommands {
                 _ => Err(anyhow!("Invalid arguments")),
             },
             ProposalCommands::Delete => self.delete(client, pk, key).await,
+            ProposalCommands::Withdraw => self.withdraw(client, pk, key).await,
+            ProposalCommands::Export { output } => {
+                export_record(client, key, output.as_deref()).await
+            }
+            ProposalCommands::Share => share(client, key).await,
+            ProposalCommands::Preapprove { request } => {
+                preapprove(client, pk, key, request.as_deref()).await
+            }
+            ProposalCommands::Tally { preapproval } => tally(client, key, preapproval).await,
         }
     }
 
     async fn approve(
         &self,
-        client: &MultisigClient,
-        pk: &Ed25519PrivateKey,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
         key: &str,
+        request: Option<&str>,
     ) -> Result<()> {
-        let addr = pk.public_key().derive_address();
-        let mut builder = tx_utils::init(client.sui(), addr).await?;
-        client.approve_intent(&mut builder, key).await?;
+        let request = request.map(ApprovalRequest::decode).transpose()?;
+        if request.as_ref().is_some_and(|request| request.key != key) {
+            return Err(anyhow!("The approval request is for another proposal"));
+        }
+        let addr = tx_utils::address(pk)?;
+        let status = client
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?
+            .permissions(&addr)
+            .approval_status(client.intent(key)?);
+        if !matches!(status, ApprovalStatus::CanApprove { .. }) {
+            return Err(anyhow!("You cannot approve this proposal: {}", status));
+        }
+
+        let mut builder = tx_utils::init(client, addr).await?;
+        match &request {
+            Some(request) => client.approve_request(&mut builder, request, addr).await?,
+            None => client.approve_intent(&mut builder, key, addr).await?,
+        }
         tx_utils::execute(client.sui(), builder, pk).await?;
-        Ok(())
+        print_outcome(client, key).await
     }
 
     async fn disapprove(
         &self,
-        client: &MultisigClient,
-        pk: &Ed25519PrivateKey,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
         key: &str,
     ) -> Result<()> {
-        let addr = pk.public_key().derive_address();
-        let mut builder = tx_utils::init(client.sui(), addr).await?;
-        client.disapprove_intent(&mut builder, key).await?;
+        let addr = tx_utils::address(pk)?;
+        let mut builder = tx_utils::init(client, addr).await?;
+        client.disapprove_intent(&mut builder, key, addr).await?;
         tx_utils::execute(client.sui(), builder, pk).await?;
-        Ok(())
+        print_outcome(client, key).await
     }
 
     pub async fn execute(
         &self,
         client: &mut MultisigClient,
-        pk: &Ed25519PrivateKey,
+        pk: Option<&Ed25519PrivateKey>,
         key: &str,
+        invite_new_members: bool,
+        force: bool,
     ) -> Result<()> {
-        let addr = pk.public_key().derive_address();
-        let mut builder = tx_utils::init(client.sui(), addr).await?;
-
-        let intent_type: IntentType = client.intent(key)?.type_.as_str().try_into()?;
-        match intent_type {
-            IntentType::ConfigMultisig => client.execute_config_multisig(&mut builder, key).await?,
-            IntentType::ConfigDeps => client.execute_config_deps(&mut builder, key).await?,
-            IntentType::ToggleUnverifiedAllowed => {
-                client
-                    .execute_toggle_unverified_allowed(&mut builder, key)
-                    .await?
-            }
-            IntentType::BorrowCap => return Err(anyhow!("Cannot be used via the CLI")),
-            IntentType::DisableRules => client.execute_disable_rules(&mut builder, key).await?,
-            IntentType::UpdateMetadata => client.execute_update_metadata(&mut builder, key).await?,
-            IntentType::MintAndTransfer => {
-                client.execute_mint_and_transfer(&mut builder, key).await?
-            }
-            IntentType::MintAndVest => client.execute_mint_and_vest(&mut builder, key).await?,
-            IntentType::WithdrawAndBurn => {
-                client.execute_withdraw_and_burn(&mut builder, key).await?
-            }
-            IntentType::TakeNfts => return Err(anyhow!("Not implemented")),
-            IntentType::ListNfts => return Err(anyhow!("Not implemented")),
-            IntentType::WithdrawAndTransferToVault => {
-                client
-                    .execute_withdraw_and_transfer_to_vault(&mut builder, key)
-                    .await?
-            }
-            IntentType::WithdrawAndTransfer => {
-                client
-                    .execute_withdraw_and_transfer(&mut builder, key)
-                    .await?
-            }
-            IntentType::WithdrawAndVest => {
-                client.execute_withdraw_and_vest(&mut builder, key).await?
-            }
-            IntentType::SpendAndTransfer => {
-                client.execute_spend_and_transfer(&mut builder, key).await?
-            }
-            IntentType::SpendAndVest => client.execute_spend_and_vest(&mut builder, key).await?,
-            IntentType::UpgradePackage => return Err(anyhow!("Not implemented")),
-            IntentType::RestrictPolicy => client.execute_restrict_policy(&mut builder, key).await?,
-        }
+        let addr = tx_utils::address(pk)?;
+        let mut builder = tx_utils::init(client, addr).await?;
+
+        // spends are recorded against the policy limits once executed, for each due execution
+        let outflows = match client.policy() {
+            Some(_) => policy::outflows(client.intent_mut(key)?.get_actions_args().await?)
+                .repeat(client.due_executions(key).await?),
+            None => vec![],
+        };
+        build_execution(
+            &mut client.skip_policy(force),
+            &mut builder,
+            key,
+            invite_new_members,
+        )
+        .await?;
 
         tx_utils::execute(client.sui(), builder, pk).await?;
+        // executed anyway, the spend is only missing from the limits
+        if let Err(e) = client.record_spends(&outflows).await {
+            eprintln!("{} {}", "Failed to save the spend:".yellow(), e);
+        }
         Ok(())
     }
 
     pub async fn execute_upgrade_package(
         &self,
         client: &mut MultisigClient,
-        pk: &Ed25519PrivateKey,
+        pk: Option<&Ed25519PrivateKey>,
         key: &str,
         package_id: &str,
         modules: &str,
         dependencies: &str,
     ) -> Result<()> {
-        let addr = pk.public_key().derive_address();
-        let mut builder = tx_utils::init(client.sui(), addr).await?;
+        let addr = tx_utils::address(pk)?;
+        let mut builder = tx_utils::init(client, addr).await?;
 
         let package_id = ObjectId::from_str(package_id)?;
         let mut modules_parsed = Vec::new();
@@ -187,55 +256,182 @@ impl ProposalCommands {
     pub async fn delete(
         &self,
         client: &mut MultisigClient,
-        pk: &Ed25519PrivateKey,
+        pk: Option<&Ed25519PrivateKey>,
         key: &str,
     ) -> Result<()> {
-        let addr = pk.public_key().derive_address();
-        let mut builder = tx_utils::init(client.sui(), addr).await?;
-
-        let intent_type: IntentType = client.intent(key)?.type_.as_str().try_into()?;
-        match intent_type {
-            IntentType::ConfigMultisig => client.delete_config_multisig(&mut builder, key).await?,
-            IntentType::ConfigDeps => client.delete_config_deps(&mut builder, key).await?,
-            IntentType::ToggleUnverifiedAllowed => {
-                client
-                    .delete_toggle_unverified_allowed(&mut builder, key)
-                    .await?
-            }
-            IntentType::BorrowCap => client.delete_borrow_cap(&mut builder, key).await?,
-            IntentType::DisableRules => client.delete_disable_rules(&mut builder, key).await?,
-            IntentType::UpdateMetadata => client.delete_update_metadata(&mut builder, key).await?,
-            IntentType::MintAndTransfer => {
-                client.delete_mint_and_transfer(&mut builder, key).await?
-            }
-            IntentType::MintAndVest => client.delete_mint_and_vest(&mut builder, key).await?,
-            IntentType::WithdrawAndBurn => {
-                client.delete_withdraw_and_burn(&mut builder, key).await?
-            }
-            IntentType::TakeNfts => return Err(anyhow!("Not implemented")),
-            IntentType::ListNfts => return Err(anyhow!("Not implemented")),
-            IntentType::WithdrawAndTransferToVault => {
-                client
-                    .delete_withdraw_and_transfer_to_vault(&mut builder, key)
-                    .await?
-            }
-            IntentType::WithdrawAndTransfer => {
-                client
-                    .delete_withdraw_and_transfer(&mut builder, key)
-                    .await?
-            }
-            IntentType::WithdrawAndVest => {
-                client.delete_withdraw_and_vest(&mut builder, key).await?
-            }
-            IntentType::SpendAndTransfer => {
-                client.delete_spend_and_transfer(&mut builder, key).await?
-            }
-            IntentType::SpendAndVest => client.delete_spend_and_vest(&mut builder, key).await?,
-            IntentType::UpgradePackage => client.delete_upgrade_package(&mut builder, key).await?,
-            IntentType::RestrictPolicy => client.delete_restrict_policy(&mut builder, key).await?,
+        let addr = tx_utils::address(pk)?;
+        let mut builder = tx_utils::init(client, addr).await?;
+
+        client.delete_intent(&mut builder, key).await?;
+
+        tx_utils::execute(client.sui(), builder, pk).await?;
+        Ok(())
+    }
+
+    async fn withdraw(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+        key: &str,
+    ) -> Result<()> {
+        let addr = tx_utils::address(pk)?;
+        let mut builder = tx_utils::init(client, addr).await?;
+        let deleted = client.withdraw_proposal(&mut builder, key).await?;
+        if !deleted && client.has_approved(key)? != Some(true) {
+            return Err(anyhow!(
+                "Nothing to do, the proposal can only be deleted once expired"
+            ));
         }
 
         tx_utils::execute(client.sui(), builder, pk).await?;
+        if deleted {
+            println!("Proposal {} deleted", key);
+        } else {
+            println!("Approval removed, the proposal can be deleted once expired");
+        }
         Ok(())
     }
 }
+
+// the intent is still cached after its execution, until the next refresh
+async fn export_record(client: &mut MultisigClient, key: &str, output: Option<&str>) -> Result<()> {
+    let record = serde_json::to_string_pretty(&client.export_approval_record(key).await?)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, record)?;
+            println!("Approval record written to {}", path);
+        }
+        None => println!("{}", record),
+    }
+    Ok(())
+}
+
+// to paste in the team chat, members approve with `proposals <key> approve --request <request>`
+async fn share(client: &mut MultisigClient, key: &str) -> Result<()> {
+    let request = client.approval_request(key).await?;
+    let multisig = client.multisig_mut().ok_or(anyhow!("Multisig not loaded"))?;
+    println!("Approval request:\n{}\n", request.encode()?);
+    if let Some(progress) = request.progress(multisig).await? {
+        println!(
+            "Approved by {} member(s), total weight {}{}",
+            progress.approved.len(),
+            progress.total_weight,
+            if progress.threshold_reached {
+                ", threshold reached"
+            } else {
+                ""
+            }
+        );
+        for address in progress.pending {
+            println!("Pending: {}", address);
+        }
+    }
+    Ok(())
+}
+
+async fn preapprove(
+    client: &mut MultisigClient,
+    pk: Option<&Ed25519PrivateKey>,
+    key: &str,
+    request: Option<&str>,
+) -> Result<()> {
+    let request = match request {
+        Some(request) => ApprovalRequest::decode(request)?,
+        None => client.approval_request(key).await?,
+    };
+    if request.key != key {
+        return Err(anyhow!("The approval request is for another proposal"));
+    }
+    let multisig = client.multisig_mut().ok_or(anyhow!("Multisig not loaded"))?;
+    request.verify(multisig).await?;
+    let preapproval = request.preapprove(tx_utils::signer(pk)?)?;
+    println!("Preapproval:\n{}", preapproval.encode()?);
+    Ok(())
+}
+
+async fn tally(client: &mut MultisigClient, key: &str, preapprovals: &[String]) -> Result<()> {
+    let preapprovals = preapprovals
+        .iter()
+        .map(|preapproval| Preapproval::decode(preapproval))
+        .collect::<Result<Vec<_>>>()?;
+    let request = client.approval_request(key).await?;
+    let multisig = client.multisig_mut().ok_or(anyhow!("Multisig not loaded"))?;
+    let tally = request.tally(multisig, &preapprovals).await?;
+    for (signer, reason) in &tally.rejected {
+        println!("Not counted {}: {}", signer, reason);
+    }
+    println!(
+        "Approved or preapproved by {} member(s), total weight {}, role weight {}{}",
+        tally.approvers.len(),
+        tally.total_weight,
+        tally.role_weight,
+        if tally.threshold_reached {
+            ", threshold reached"
+        } else {
+            ""
+        }
+    );
+    Ok(())
+}
+
+// without waiting for the full refresh
+async fn print_outcome(client: &mut MultisigClient, key: &str) -> Result<()> {
+    client.refresh_intent(key).await?;
+    let outcome = &client.intent(key)?.outcome;
+    println!(
+        "Total weight: {}, role weight: {}, approved by {} member(s)",
+        outcome.total_weight,
+        outcome.role_weight,
+        outcome.approved.len()
+    );
+    Ok(())
+}
+
+// builds the execution of any intent type (except UpgradePackage)
+async fn build_execution(
+    client: &mut MultisigClient,
+    builder: &mut TransactionBuilder,
+    key: &str,
+    invite_new_members: bool,
+) -> Result<()> {
+    let intent_type: IntentType = client.intent(key)?.type_.as_str().try_into()?;
+    match intent_type {
+        // also writes the profile proposed by config metadata intents
+        IntentType::ConfigMultisig => {
+            let invited = client
+                .execute_config_multisig_with_invites(builder, key, invite_new_members)
+                .await?;
+            for address in invited {
+                println!("Inviting {}", address);
+            }
+        }
+        IntentType::ConfigDeps => client.execute_config_deps(builder, key).await?,
+        IntentType::ToggleUnverifiedAllowed => {
+            client
+                .execute_toggle_unverified_allowed(builder, key)
+                .await?
+        }
+        IntentType::BorrowCap => return Err(anyhow!("Cannot be used via the CLI")),
+        IntentType::DisableRules => client.execute_disable_rules(builder, key).await?,
+        IntentType::UpdateMetadata => client.execute_update_metadata(builder, key).await?,
+        IntentType::MintAndTransfer => client.execute_mint_and_transfer(builder, key).await?,
+        IntentType::MintAndVest => client.execute_mint_and_vest(builder, key).await?,
+        IntentType::WithdrawAndBurn => client.execute_withdraw_and_burn(builder, key).await?,
+        IntentType::TakeNfts => return Err(anyhow!("Not implemented")),
+        IntentType::ListNfts => return Err(anyhow!("Not implemented")),
+        IntentType::WithdrawAndTransferToVault => {
+            client
+                .execute_withdraw_and_transfer_to_vault(builder, key)
+                .await?
+        }
+        IntentType::WithdrawAndTransfer => {
+            client.execute_withdraw_and_transfer(builder, key).await?
+        }
+        IntentType::WithdrawAndVest => client.execute_withdraw_and_vest(builder, key).await?,
+        IntentType::SpendAndTransfer => client.execute_spend_and_transfer(builder, key).await?,
+        IntentType::SpendAndVest => client.execute_spend_and_vest(builder, key).await?,
+        IntentType::UpgradePackage => return Err(anyhow!("Not implemented")),
+        IntentType::RestrictPolicy => client.execute_restrict_policy(builder, key).await?,
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use account_multisig_sdk::MultisigClient;
+use account_multisig_sdk::{MultisigClient, user::sort_by_urgency};
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
 use sui_crypto::ed25519::Ed25519PrivateKey;
@@ -12,6 +12,16 @@ pub enum UserCommands {
         about = "List all multisigs the user is a member of"
     )]
     ListMultisigs,
+    #[command(
+        name = "overview",
+        about = "Members, proposals awaiting your approval and SUI balance of each multisig"
+    )]
+    Overview,
+    #[command(
+        name = "pending",
+        about = "Proposals awaiting your approval across all your multisigs, the most urgent first"
+    )]
+    Pending,
     #[command(
         name = "join-multisig",
         about = "Insert a multisig id to the user object"
@@ -22,6 +32,11 @@ pub enum UserCommands {
         about = "Remove a multisig from the user object"
     )]
     LeaveMultisig { multisig_id: String },
+    #[command(
+        name = "reorder-multisigs",
+        about = "Reorder the multisigs in the user object (all ids in the new order)"
+    )]
+    ReorderMultisigs { multisig_ids: Vec<String> },
     #[command(
         name = "list-invites",
         about = "List all invites the user has received"
@@ -34,7 +49,11 @@ pub enum UserCommands {
 }
 
 impl UserCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         let user = client.user().ok_or(anyhow!("User not found"))?;
 
         match self {
@@ -45,22 +64,78 @@ impl UserCommands {
                 }
                 Ok(())
             },
+            UserCommands::Overview => {
+                println!("\n=== MULTISIGS ===\n");
+                let overviews = user.aggregate_overview().await;
+                for (preview, overview) in user.multisigs.iter().zip(overviews) {
+                    match overview {
+                        Ok(overview) => println!(
+                            "{} - {}: {} members, {} to approve, {} MIST",
+                            overview.id,
+                            overview.name,
+                            overview.members,
+                            overview.awaiting_approval,
+                            overview.sui_balance
+                        ),
+                        Err(e) => {
+                            println!("{} - {}: failed to load ({})", preview.id, preview.name, e)
+                        }
+                    }
+                }
+                Ok(())
+            }
+            UserCommands::Pending => {
+                println!("\n=== AWAITING YOUR APPROVAL ===\n");
+                let mut all = Vec::new();
+                let results = user.pending_approvals().await?;
+                for (preview, pending) in user.multisigs.iter().zip(results) {
+                    match pending {
+                        Ok(pending) => all.extend(pending),
+                        Err(e) => {
+                            println!("{} - {}: failed to load ({})", preview.id, preview.name, e)
+                        }
+                    }
+                }
+                sort_by_urgency(&mut all);
+                for pending in all {
+                    println!(
+                        "[{:?}] {} - {} ({}) in {}",
+                        pending.deadlines.urgency,
+                        pending.key,
+                        pending.intent_type,
+                        pending.description,
+                        pending.multisig_name
+                    );
+                }
+                Ok(())
+            }
             UserCommands::JoinMultisig { multisig_id } => {
-                let addr = pk.public_key().derive_address();
-                let mut builder = tx_utils::init(client.sui(), addr).await?;
+                let addr = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, addr).await?;
                 user.join_multisig(&mut builder, multisig_id.parse()?)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
             },
             UserCommands::LeaveMultisig { multisig_id } => {
-                let addr = pk.public_key().derive_address();
-                let mut builder = tx_utils::init(client.sui(), addr).await?;
+                let addr = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, addr).await?;
                 user.leave_multisig(&mut builder, multisig_id.parse()?)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
-            },
+            }
+            UserCommands::ReorderMultisigs { multisig_ids } => {
+                let addr = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, addr).await?;
+                let ids = multisig_ids
+                    .iter()
+                    .map(|id| id.parse())
+                    .collect::<Result<Vec<_>, _>>()?;
+                user.reorder_multisigs(&mut builder, ids).await?;
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                Ok(())
+            }
             UserCommands::ListInvites => {
                 println!("\n=== INVITES ===");
                 for invite in &user.invites {
@@ -70,16 +145,16 @@ impl UserCommands {
                 Ok(())
             },
             UserCommands::AcceptInvite { invite_id } => {
-                let addr = pk.public_key().derive_address();
-                let mut builder = tx_utils::init(client.sui(), addr).await?;
+                let addr = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, addr).await?;
                 user.accept_invite(&mut builder, invite_id.parse()?)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
             },
             UserCommands::RefuseInvite { invite_id } => {
-                let addr = pk.public_key().derive_address();
-                let mut builder = tx_utils::init(client.sui(), addr).await?;
+                let addr = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, addr).await?;
                 user.refuse_invite(&mut builder, invite_id.parse()?)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
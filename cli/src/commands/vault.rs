@@ -1,15 +1,23 @@
 use account_multisig_sdk::{
     MultisigClient,
-    proposals::params::{
-        ParamsArgs, SpendAndTransferArgs, SpendAndVestArgs, WithdrawAndTransferToVaultArgs,
+    assets::dynamic_fields::normalize_type,
+    proposals::{
+        actions::IntentType,
+        params::{
+            SpendAndTransferArgs, SpendAndVestArgs, WithdrawAndTransferToVaultArgs,
+            parse_transfers_csv, total_amount,
+        },
     },
-    utils::get_owned_coins,
+    utils::{InputKind, get_coins_for_amount, get_object_as_input},
 };
 use anyhow::{Result, anyhow};
 use clap::Subcommand;
+use std::{fs::File, path::PathBuf, str::FromStr};
 use sui_crypto::ed25519::Ed25519PrivateKey;
-use sui_sdk_types::{Address, ObjectId};
+use sui_sdk_types::ObjectId;
+use sui_transaction_builder::Serialized;
 
+use crate::parsers::Payout;
 use crate::tx_utils;
 
 #[derive(Debug, Subcommand)]
@@ -28,13 +36,35 @@ pub enum VaultCommands {
         vault_name: String,
         #[arg(long, help = "Coin amount in the smallest unit")]
         amount: u64,
-        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>)")]
+        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>) or ticker (e.g. USDC)")]
         coin_type: String,
     },
+    #[command(
+        name = "deposit-from-multisig",
+        about = "Propose to deposit coins owned by the multisig into a vault (one proposal per coin)"
+    )]
+    DepositFromMultisig {
+        #[arg(long, help = "Name of the proposals (suffixed with -1, -2, ...)")]
+        name: String,
+        #[arg(long, help = "Vault name")]
+        vault_name: String,
+        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>) or ticker (e.g. USDC)")]
+        coin_type: String,
+        #[arg(
+            long,
+            help = "Coin amount in the smallest unit, all the coins of the type if omitted"
+        )]
+        amount: Option<u64>,
+    },
     #[command(name = "close-vault", about = "Close a vault")]
     CloseVault {
         #[arg(long, help = "Vault name")]
         vault_name: String,
+        #[arg(
+            long,
+            help = "Propose to transfer what's left in the vault to this address first (proposals named drain-<vault>-1, -2, ...), run again once executed to close it"
+        )]
+        drain_to: Option<String>,
     },
     #[command(
         name = "propose-withdraw-and-transfer-to-vault",
@@ -43,7 +73,7 @@ pub enum VaultCommands {
     ProposeWithdrawAndTransferToVault {
         #[arg(long, help = "Name of the proposal")]
         name: String,
-        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>)")]
+        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>) or ticker (e.g. USDC)")]
         coin_type: String,
         #[arg(long, help = "Coin object id")]
         coin_id: ObjectId,
@@ -59,14 +89,24 @@ pub enum VaultCommands {
     ProposeSpendAndTransfer {
         #[arg(long, help = "Name of the proposal")]
         name: String,
-        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>)")]
+        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>) or ticker (e.g. USDC)")]
         coin_type: String,
         #[arg(long, help = "Vault name")]
         vault_name: String,
         #[arg(long, help = "Amounts to transfer")]
         amounts: Vec<u64>,
-        #[arg(long, help = "Recipients")]
-        recipients: Vec<Address>,
+        #[arg(long, help = "Recipient addresses or SuiNS names")]
+        recipients: Vec<String>,
+        #[arg(
+            long,
+            help = "CSV file of recipient,amount lines with amounts in coin units (replaces --amounts and --recipients)"
+        )]
+        csv: Option<PathBuf>,
+        #[arg(
+            long,
+            help = "Propose even if the vault doesn't hold enough coins yet or the local policy forbids it"
+        )]
+        force: bool,
     },
     #[command(
         name = "propose-spend-and-vest",
@@ -75,7 +115,7 @@ pub enum VaultCommands {
     ProposeSpendAndVest {
         #[arg(long, help = "Name of the proposal")]
         name: String,
-        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>)")]
+        #[arg(long, help = "Coin type (e.g. <addr>::<module>::<COIN_TYPE>) or ticker (e.g. USDC)")]
         coin_type: String,
         #[arg(long, help = "Vault name")]
         vault_name: String,
@@ -85,18 +125,38 @@ pub enum VaultCommands {
         start_timestamp: u64,
         #[arg(long, help = "Vesting end timestamp in ms")]
         end_timestamp: u64,
-        #[arg(long, help = "Recipient address")]
-        recipient: Address,
+        #[arg(long, help = "Recipient address or SuiNS name")]
+        recipient: String,
+        #[arg(
+            long,
+            help = "Propose even if the vault doesn't hold enough coins yet or the local policy forbids it"
+        )]
+        force: bool,
+    },
+    #[command(
+        name = "propose-payout",
+        about = "Propose to pay several recipients in different coins from a vault (one proposal per coin type)"
+    )]
+    ProposePayout {
+        #[arg(long, help = "Name of the proposals (suffixed with -1, -2, ...)")]
+        name: String,
+        #[arg(long, help = "Vault name")]
+        vault_name: String,
+        #[arg(long, value_parser = clap::builder::ValueParser::new(Payout::from_str))]
+        payout: Vec<Payout>,
     },
 }
 
 impl VaultCommands {
-    pub async fn run(&self, client: &mut MultisigClient, pk: &Ed25519PrivateKey) -> Result<()> {
+    pub async fn run(
+        &self,
+        client: &mut MultisigClient,
+        pk: Option<&Ed25519PrivateKey>,
+    ) -> Result<()> {
         client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
         match self {
             VaultCommands::OpenVault { vault_name } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
                 client.open_vault(&mut builder, vault_name).await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
@@ -106,23 +166,85 @@ impl VaultCommands {
                 amount,
                 coin_type,
             } => {
-                let owner = pk.public_key().derive_address();
-                let mut builder = tx_utils::init(client.sui(), owner).await?;
-                
-                let coins = get_owned_coins(client.sui(), owner, Some(coin_type)).await?;
-                let to_merge = coins.iter().map(|coin| *coin.id().as_address()).collect();
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let owner = tx_utils::address(pk)?;
+                let mut builder = tx_utils::init(client, owner).await?;
 
-                let coin = client.merge_and_split(&mut builder, to_merge, vec![*amount], coin_type).await?;
-                client.deposit_from_wallet(&mut builder, vault_name.clone(), coin, coin_type).await?;
+                // coins of the wallet, SUI is split from the gas coin
+                let primary = if normalize_type(coin_type) == normalize_type("0x2::sui::SUI") {
+                    builder.gas()
+                } else {
+                    let coins =
+                        get_coins_for_amount(client.sui(), owner, coin_type, *amount).await?;
+                    let mut inputs = Vec::new();
+                    for coin in &coins {
+                        let id = *coin.id().as_address();
+                        inputs.push(builder.input(
+                            get_object_as_input(client.sui(), id, InputKind::Owned).await?,
+                        ));
+                    }
+                    let primary = inputs.remove(0);
+                    if !inputs.is_empty() {
+                        builder.merge_coins(primary, inputs);
+                    }
+                    primary
+                };
+                let amount_arg = builder.input(Serialized(amount));
+                let coin = builder.split_coins(primary, vec![amount_arg]);
+                client
+                    .deposit_from_wallet(&mut builder, vault_name.clone(), coin, coin_type)
+                    .await?;
 
                 tx_utils::execute(client.sui(), builder, pk).await?;
                 Ok(())
             }
-            VaultCommands::CloseVault { vault_name } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                client.close_vault(&mut builder, vault_name).await?;
+            VaultCommands::DepositFromMultisig {
+                name,
+                vault_name,
+                coin_type,
+                amount,
+            } => {
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let keys = client
+                    .request_deposit_owned_to_vault(
+                        &mut builder,
+                        name,
+                        "",
+                        vault_name,
+                        coin_type,
+                        *amount,
+                    )
+                    .await?;
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                for key in &keys {
+                    tx_utils::print_share_link(client, key);
+                }
+                Ok(())
+            }
+            VaultCommands::CloseVault {
+                vault_name,
+                drain_to,
+            } => {
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let Some(recipient) = drain_to else {
+                    client.close_vault(&mut builder, vault_name).await?;
+                    tx_utils::execute(client.sui(), builder, pk).await?;
+                    return Ok(());
+                };
+
+                let recipient = client.resolve_address(recipient).await?;
+                let key_prefix = format!("drain-{}", vault_name);
+                let keys = client
+                    .drain_and_close_vault(&mut builder, vault_name, &key_prefix, recipient)
+                    .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                if keys.is_empty() {
+                    println!("Vault {} closed", vault_name);
+                }
+                for key in &keys {
+                    tx_utils::print_share_link(client, key);
+                }
                 Ok(())
             }
             VaultCommands::ProposeWithdrawAndTransferToVault {
@@ -132,10 +254,12 @@ impl VaultCommands {
                 coin_amount,
                 vault_name,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::WithdrawAndTransferToVault)?;
                 let actions_args = WithdrawAndTransferToVaultArgs::new(
                     &mut builder,
                     *coin_id,
@@ -151,6 +275,7 @@ impl VaultCommands {
                     )
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
             VaultCommands::ProposeSpendAndTransfer {
@@ -159,21 +284,62 @@ impl VaultCommands {
                 vault_name,
                 amounts,
                 recipients,
+                csv,
+                force,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let (amounts, recipients) = match csv {
+                    Some(path) => {
+                        let decimals = client.coin_metadata(coin_type).await?.decimals;
+                        parse_transfers_csv(File::open(path)?, decimals)?
+                    }
+                    None => (amounts.clone(), client.resolve_addresses(recipients).await?),
+                };
+                if !force {
+                    client.validate_spend(vault_name, coin_type, total_amount(&amounts)?)?;
+                }
+
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::SpendAndTransfer)?;
                 let actions_args = SpendAndTransferArgs::new(
                     &mut builder,
                     vault_name.clone(),
-                    amounts.clone(),
-                    recipients.clone(),
+                    amounts,
+                    recipients,
                 );
+                // the request is checked against the policy, unless forced
                 client
+                    .skip_policy(*force)
                     .request_spend_and_transfer(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
+                Ok(())
+            }
+            VaultCommands::ProposePayout {
+                name,
+                vault_name,
+                payout,
+            } => {
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let mut payouts = Vec::new();
+                for p in payout {
+                    payouts.push((
+                        client.resolve_coin(&p.coin_type)?,
+                        p.amount,
+                        client.resolve_address(&p.recipient).await?,
+                    ));
+                }
+                let keys = client
+                    .request_vault_payout(&mut builder, name, name, vault_name, payouts)
+                    .await?;
+                tx_utils::execute(client.sui(), builder, pk).await?;
+                for key in &keys {
+                    tx_utils::print_share_link(client, key);
+                }
                 Ok(())
             }
             VaultCommands::ProposeSpendAndVest {
@@ -184,11 +350,19 @@ impl VaultCommands {
                 start_timestamp,
                 end_timestamp,
                 recipient,
+                force,
             } => {
-                let mut builder =
-                    tx_utils::init(client.sui(), pk.public_key().derive_address()).await?;
-                let intent_args =
-                    ParamsArgs::new(&mut builder, name.clone(), "".to_string(), vec![0], 0);
+                let coin_type = &client.resolve_coin(coin_type)?;
+                let recipient = &client.resolve_address(recipient).await?;
+                if !force {
+                    client.validate_spend(vault_name, coin_type, *coin_amount)?;
+                }
+
+                let mut builder = tx_utils::init(client, tx_utils::address(pk)?).await?;
+                let intent_args = client
+                    .intent_params(name)
+                    .await?
+                    .build_checked(&mut builder, &IntentType::SpendAndVest)?;
                 let actions_args = SpendAndVestArgs::new(
                     &mut builder,
                     vault_name.clone(),
@@ -197,10 +371,13 @@ impl VaultCommands {
                     *end_timestamp,
                     *recipient,
                 );
+                // the request is checked against the policy, unless forced
                 client
+                    .skip_policy(*force)
                     .request_spend_and_vest(&mut builder, intent_args, actions_args, coin_type)
                     .await?;
                 tx_utils::execute(client.sui(), builder, pk).await?;
+                tx_utils::print_share_link(client, name);
                 Ok(())
             }
         }
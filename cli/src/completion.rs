@@ -0,0 +1,137 @@
+use account_multisig_sdk::MultisigClient;
+use clap::Command;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+// tab completion of the repl: subcommands and flags from the clap definition, values from the
+// state of the client (proposal keys, vault names, coin types) updated before each prompt
+
+pub struct CliHelper {
+    command: Command,
+    intent_keys: Vec<String>,
+    vault_names: Vec<String>,
+    coin_types: Vec<String>,
+}
+
+impl CliHelper {
+    pub fn new(command: Command) -> Self {
+        Self {
+            command,
+            intent_keys: Vec::new(),
+            vault_names: Vec::new(),
+            coin_types: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, client: &MultisigClient) {
+        self.intent_keys = client
+            .intents()
+            .map(|intents| intents.intents.keys().cloned().collect())
+            .unwrap_or_default();
+        self.vault_names = client
+            .dynamic_fields()
+            .map(|df| df.vaults.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let mut coin_types = client
+            .coin_registry()
+            .tickers()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if let Some(df) = client.dynamic_fields() {
+            coin_types.extend(df.currencies.keys().cloned());
+            coin_types.extend(
+                df.vaults
+                    .values()
+                    .flat_map(|vault| vault.coins.keys().cloned()),
+            );
+        }
+        coin_types.sort();
+        coin_types.dedup();
+        self.coin_types = coin_types;
+    }
+
+    // values of the word following the previous ones
+    fn candidates(&self, previous: &[&str]) -> Vec<String> {
+        match previous.last().copied() {
+            Some("proposals") => return self.intent_keys.clone(),
+            Some("--vault-name") => return self.vault_names.clone(),
+            Some("--coin-type") => return self.coin_types.clone(),
+            _ => {}
+        }
+        // the deepest subcommand typed, args like the proposal key are skipped
+        let mut command = &self.command;
+        for word in previous {
+            if let Some(subcommand) = command.find_subcommand(word) {
+                command = subcommand;
+            }
+        }
+        command
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .chain(
+                command
+                    .get_arguments()
+                    .filter_map(|arg| arg.get_long())
+                    .map(|long| format!("--{}", long)),
+            )
+            .collect()
+    }
+}
+
+// candidates starting with the word, or containing its chars in order if none does
+// (e.g. "pst" for "propose-spend-and-transfer")
+pub fn fuzzy_matches(word: &str, candidates: Vec<String>) -> Vec<String> {
+    let (mut prefixed, mut others): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .filter(|candidate| is_subsequence(word, candidate))
+        .partition(|candidate| candidate.starts_with(word));
+    if prefixed.is_empty() {
+        others.sort();
+        return others;
+    }
+    prefixed.sort();
+    prefixed
+}
+
+fn is_subsequence(word: &str, candidate: &str) -> bool {
+    let mut chars = candidate.chars();
+    word.chars()
+        .all(|c| chars.any(|other| other.eq_ignore_ascii_case(&c)))
+}
+
+impl Completer for CliHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(' ').map_or(0, |i| i + 1);
+        let previous = line[..start].split_whitespace().collect::<Vec<_>>();
+        let pairs = fuzzy_matches(&line[start..pos], self.candidates(&previous))
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for CliHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CliHelper {}
+
+impl Validator for CliHelper {}
+
+impl Helper for CliHelper {}
@@ -0,0 +1,189 @@
+use account_multisig_sdk::{MultisigClient, errors::MultisigError};
+use colored::*;
+use sui_sdk_types::Address;
+
+// failed commands are printed with a hint when there is an obvious next step, the class of the
+// last failure is the exit code of the cli so that scripts piping commands can check it
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    // anything not classified, e.g. network or io failures
+    Other,
+    // the command couldn't be parsed
+    Usage,
+    // the multisig, an object or an asset doesn't exist
+    NotFound,
+    // refused before submitting: approvals, balances, local policy
+    Rejected,
+    // the transaction aborted on chain
+    Aborted,
+    // the endpoint is on another network or returns data the bindings can't decode
+    Endpoint,
+}
+
+impl ErrorClass {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorClass::Other => 1,
+            ErrorClass::Usage => 2,
+            ErrorClass::NotFound => 3,
+            ErrorClass::Rejected => 4,
+            ErrorClass::Aborted => 5,
+            ErrorClass::Endpoint => 6,
+        }
+    }
+}
+
+// prints the error and its hint, key is the proposal the command operated on and signer the
+// address that signed it (the --key one or the default one)
+pub fn report(
+    e: &anyhow::Error,
+    client: &MultisigClient,
+    key: Option<&str>,
+    signer: Option<Address>,
+) -> ErrorClass {
+    eprintln!("{} {e:#}", "Error:".red());
+    if let Some(hint) = multisig_error(e).and_then(|error| hint(error, client, key, signer)) {
+        eprintln!("{} {hint}", "Hint:".yellow());
+    }
+    classify(e)
+}
+
+pub fn classify(e: &anyhow::Error) -> ErrorClass {
+    let Some(error) = multisig_error(e) else {
+        return ErrorClass::Other;
+    };
+    match error {
+        MultisigError::CurrencyNotFound { .. }
+        | MultisigError::VaultNotFound { .. }
+        | MultisigError::MissingObjects { .. }
+        | MultisigError::NotAMultisigAccount { .. }
+        | MultisigError::UnresolvedName { .. } => ErrorClass::NotFound,
+        MultisigError::MoveAbort { .. } => ErrorClass::Aborted,
+        MultisigError::WrongNetwork { .. }
+        | MultisigError::SchemaMismatch { .. }
+        | MultisigError::MalformedActions { .. } => ErrorClass::Endpoint,
+        MultisigError::AlreadyApproved { .. }
+        | MultisigError::NotApproved { .. }
+        | MultisigError::ApprovalRequestMismatch { .. }
+        | MultisigError::InvalidPreapproval { .. }
+        | MultisigError::MintDisabled { .. }
+        | MultisigError::MaxSupplyExceeded { .. }
+        | MultisigError::InsufficientVaultBalance { .. }
+        | MultisigError::VaultNotEmpty { .. }
+        | MultisigError::RecipientsMismatch { .. }
+        | MultisigError::InvalidWithdrawals { .. }
+        | MultisigError::NotOwnedByAccount { .. }
+        | MultisigError::IntentBlocked { .. }
+        | MultisigError::RecipientNotAllowed { .. }
+        | MultisigError::SpendingLimitExceeded { .. } => ErrorClass::Rejected,
+    }
+}
+
+// the error may be wrapped in contexts
+fn multisig_error(e: &anyhow::Error) -> Option<&MultisigError> {
+    e.chain()
+        .find_map(|cause| cause.downcast_ref::<MultisigError>())
+}
+
+fn hint(
+    error: &MultisigError,
+    client: &MultisigClient,
+    key: Option<&str>,
+    signer: Option<Address>,
+) -> Option<String> {
+    let key = key.unwrap_or("<key>");
+    match error {
+        MultisigError::AlreadyApproved { .. } => Some(format!(
+            "run `proposals {key}` to see the approvals still missing"
+        )),
+        MultisigError::NotApproved { .. } => {
+            Some(format!("approve it first with `proposals {key} approve`"))
+        }
+        MultisigError::ApprovalRequestMismatch { .. } => Some(format!(
+            "the proposal changed, ask for a new request (`proposals {key} share`)"
+        )),
+        MultisigError::CurrencyNotFound { .. } => {
+            Some("deposit the TreasuryCap with `currencies deposit-treasury-cap`".to_string())
+        }
+        MultisigError::MintDisabled { .. } => Some(
+            "minting was disabled by a DisableRules proposal and can't be enabled again"
+                .to_string(),
+        ),
+        MultisigError::VaultNotFound { .. } => {
+            Some("list the vaults with `vaults` or open one with `vaults open-vault`".to_string())
+        }
+        MultisigError::InsufficientVaultBalance { .. } => Some(
+            "deposit with `vaults deposit-from-wallet` or `vaults deposit-from-multisig`"
+                .to_string(),
+        ),
+        MultisigError::VaultNotEmpty { .. } => Some(
+            "spend the remaining coins with `vaults propose-spend-and-transfer` first".to_string(),
+        ),
+        MultisigError::InvalidWithdrawals { .. } => {
+            Some("list the objects owned by the multisig with `owned`".to_string())
+        }
+        MultisigError::IntentBlocked { .. }
+        | MultisigError::RecipientNotAllowed { .. }
+        | MultisigError::SpendingLimitExceeded { .. } => Some(
+            "edit account-multisig-policy.toml in the Sui config directory or add --force"
+                .to_string(),
+        ),
+        MultisigError::UnresolvedName { .. } => Some(
+            "check the spelling and the network, or pass the address instead".to_string(),
+        ),
+        MultisigError::WrongNetwork { .. } => {
+            Some("restart the cli with the network of the multisig".to_string())
+        }
+        MultisigError::SchemaMismatch { .. } => {
+            Some("update the cli, the packages were upgraded".to_string())
+        }
+        MultisigError::MoveAbort {
+            name: Some(name), ..
+        } => abort_hint(name, client, key, signer),
+        _ => None,
+    }
+}
+
+// by error constant, only known for clever errors
+fn abort_hint(
+    name: &str,
+    client: &MultisigClient,
+    key: &str,
+    signer: Option<Address>,
+) -> Option<String> {
+    match name {
+        "EKeyAlreadyExists" => Some(format!("a proposal {key} exists, choose another key")),
+        "EKeyNotFound" => Some(format!("no proposal {key}, list them with `proposals`")),
+        "EThresholdNotReached" => Some(missing_weight(client, key).unwrap_or_else(|| {
+            format!("threshold not met, see the approvals with `proposals {key}`")
+        })),
+        "ECallerIsNotMember" | "ENotMember" => Some(match signer {
+            Some(signer) => format!("the signer {signer} isn't a member, pick another with --key"),
+            None => "the signer isn't a member, pick another with --key".to_string(),
+        }),
+        "ECantBeExecutedYet" => Some(format!(
+            "the execution time isn't reached, see `proposals {key}`"
+        )),
+        "EHasntExpired" => Some(format!(
+            "the proposal can only be deleted once expired, see `proposals {key}`"
+        )),
+        _ => None,
+    }
+}
+
+fn missing_weight(client: &MultisigClient, key: &str) -> Option<String> {
+    let multisig = client.multisig()?;
+    let intent = client.intent(key).ok()?;
+    let global = multisig
+        .config
+        .global
+        .threshold
+        .saturating_sub(intent.outcome.total_weight);
+    let mut hint = format!("threshold not met, {global} more weight needed");
+    if let Some(role) = multisig.config.roles.get(&intent.role) {
+        let missing = role.threshold.saturating_sub(intent.outcome.role_weight);
+        hint.push_str(&format!(" or {missing} from role {}", intent.role));
+    }
+    Some(hint)
+}
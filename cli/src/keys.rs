@@ -0,0 +1,47 @@
+use anyhow::{Result, anyhow};
+use colored::*;
+use std::str::FromStr;
+use sui_crypto::ed25519::Ed25519PrivateKey;
+use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_sdk::types::{
+    base_types::SuiAddress,
+    crypto::{SuiKeyPair, ToFromBytes},
+};
+
+// signer keys from the keystore of the sui client, selected by alias or address
+// (--key on any command, `key` in the config file)
+
+pub fn resolve(keystore: &Keystore, key: &str) -> Result<SuiAddress> {
+    match SuiAddress::from_str(key) {
+        Ok(address) => Ok(address),
+        Err(_) => keystore
+            .get_address_by_alias(key.to_string())
+            .copied()
+            .map_err(|_| anyhow!("No key with alias {} in the keystore, see `keys list`", key)),
+    }
+}
+
+pub fn ed25519_key(keystore: &Keystore, address: &SuiAddress) -> Result<Ed25519PrivateKey> {
+    match keystore.get_key(address)? {
+        SuiKeyPair::Ed25519(kp) => Ok(Ed25519PrivateKey::new(kp.as_bytes().try_into()?)),
+        _ => Err(anyhow!("Only ed25519 keys are supported")),
+    }
+}
+
+pub fn signer(keystore: &Keystore, key: &str) -> Result<Ed25519PrivateKey> {
+    ed25519_key(keystore, &resolve(keystore, key)?)
+}
+
+// default is the address used when --key isn't passed
+pub fn list(keystore: &Keystore, default: &SuiAddress) {
+    let mut keys = keystore.addresses_with_alias();
+    keys.sort_by(|a, b| a.1.alias.cmp(&b.1.alias));
+    for (address, alias) in keys {
+        let scheme = match keystore.get_key(address) {
+            Ok(SuiKeyPair::Ed25519(_)) => "ed25519".normal(),
+            _ => "not supported".red(),
+        };
+        let marker = if address == default { "*" } else { " " };
+        println!("{} {:<20} {} ({})", marker, alias.alias, address, scheme);
+    }
+}
@@ -1,3 +1,9 @@
 pub mod commands;
+pub mod completion;
+pub mod errors;
+pub mod keys;
+pub mod offline;
 pub mod tx_utils;
-pub mod parsers;
\ No newline at end of file
+pub mod parsers;
+pub mod settings;
+pub mod table;
\ No newline at end of file
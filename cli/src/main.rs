@@ -10,24 +10,42 @@ use account_multisig_cli::commands::{
     user::UserCommands,
     vault::VaultCommands,
 };
+use account_multisig_cli::completion::CliHelper;
+use account_multisig_cli::errors::{self, ErrorClass};
+use account_multisig_cli::keys;
+use account_multisig_cli::offline::Snapshots;
 use account_multisig_cli::parsers::{Member, Role};
-use account_multisig_sdk::MultisigClient;
+use account_multisig_cli::settings::Settings;
+use account_multisig_cli::table::{Table, View};
+use account_multisig_sdk::{
+    MultisigClient,
+    policy::Policy,
+    proposals::{
+        actions::IntentActions,
+        intents::{Deadlines, Urgency},
+        simulation::simulate_config,
+    },
+    roles,
+};
 use anyhow::{Result, anyhow};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use colored::*;
-use std::io::{self, Write};
+use rustyline::{Editor, error::ReadlineError, history::DefaultHistory};
 use std::str::FromStr;
+use std::time::Duration;
 use sui_config::{SUI_CLIENT_CONFIG, sui_config_dir};
-use sui_crypto::ed25519::Ed25519PrivateKey;
-use sui_keys::keystore::AccountKeystore;
-use sui_sdk::{
-    types::crypto::{SuiKeyPair, ToFromBytes},
-    wallet_context::WalletContext,
-};
+use sui_keys::keystore::Keystore;
+use sui_sdk::wallet_context::WalletContext;
 
 #[derive(Debug, Parser)]
 #[command(name = "account-multisig", version, about, long_about = None)]
 struct App {
+    #[arg(
+        long,
+        global = true,
+        help = "Keystore alias or address of the signer for this command"
+    )]
+    key: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -41,8 +59,18 @@ enum Commands {
         #[command(subcommand)]
         command: UserCommands,
     },
+    #[command(name = "keys", about = "Manage the signer keys")]
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommands,
+    },
     #[command(name = "load", about = "Load a specific multisig or reload current")]
     Load { id: Option<String> },
+    #[command(
+        name = "gas-budget",
+        about = "Show the gas budget or set it in MIST, 'default' for the network default"
+    )]
+    GasBudget { budget: Option<String> },
     #[command(name = "create", about = "Create a new multisig")]
     Create {
         #[arg(long)]
@@ -53,6 +81,8 @@ enum Commands {
         member: Option<Vec<Member>>,
         #[arg(long, value_parser = clap::builder::ValueParser::new(Role::from_str))]
         role: Option<Vec<Role>>,
+        #[arg(long, help = "Leave the config intent pending instead of executing it")]
+        pending: bool,
     },
     #[command(
         name = "proposals",
@@ -62,6 +92,10 @@ enum Commands {
         /// Proposal key to operate on. If not provided, lists all proposals.
         /// If provided without a subcommand, shows proposal details.
         key: Option<String>,
+        #[arg(long, help = "Show the proposals saved locally without fetching them")]
+        offline: bool,
+        #[command(flatten)]
+        view: View,
         #[command(subcommand)]
         proposal_command: Option<ProposalCommands>,
     },
@@ -89,6 +123,8 @@ enum Commands {
     Owned {
         #[command(subcommand)]
         command: Option<OwnedCommands>,
+        #[command(flatten)]
+        view: View,
     },
     #[command(name = "packages", about = "Manage packages")]
     Packages {
@@ -99,9 +135,20 @@ enum Commands {
     Vaults {
         #[command(subcommand)]
         command: Option<VaultCommands>,
+        #[command(flatten)]
+        view: View,
     },
 }
 
+#[derive(Debug, Subcommand)]
+enum KeysCommands {
+    #[command(
+        name = "list",
+        about = "List the keys of the sui keystore, * marks the default signer"
+    )]
+    List,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::fmt()
@@ -111,62 +158,152 @@ async fn main() -> Result<()> {
     println!("Multisig CLI - Interactive Mode");
     println!("Type 'help' for commands, 'exit' to quit");
 
-    // get keypair from sui_config
-    let mut wallet_context =
-        WalletContext::new(&sui_config_dir()?.join(SUI_CLIENT_CONFIG), None, None)?;
-    let active_addr = wallet_context.active_address()?;
-    let signer = wallet_context.config.keystore.get_key(&active_addr)?;
+    // defaults for what isn't passed as args, see `config set`
+    let settings = Settings::load(&Settings::path()?)?;
 
-    let bytes = match signer {
-        SuiKeyPair::Ed25519(kp) => Ok(kp.as_bytes()),
-        _ => Err(anyhow!("Only ed25519 keys are supported")),
+    // --read-only doesn't load the keystore, for browsing on machines without keys
+    let read_only = std::env::args().any(|arg| arg == "--read-only");
+    let mut args = std::env::args().skip(1).filter(|arg| arg != "--read-only");
+
+    // get keypair from sui_config
+    let (wallet_context, active_addr, ed25519_pk) = if read_only {
+        println!(
+            "{}",
+            "Read-only mode, transactions can't be signed".yellow()
+        );
+        (None, None, None)
+    } else {
+        let mut wallet_context =
+            WalletContext::new(&sui_config_dir()?.join(SUI_CLIENT_CONFIG), None, None)?;
+        let active_addr = match &settings.key {
+            Some(key) => keys::resolve(&wallet_context.config.keystore, key)?,
+            None => wallet_context.active_address()?,
+        };
+        let ed25519_pk = keys::ed25519_key(&wallet_context.config.keystore, &active_addr)?;
+        (Some(wallet_context), Some(active_addr), Some(ed25519_pk))
     };
-    let ed25519_pk = Ed25519PrivateKey::new(bytes?.try_into()?);
 
     // init cli with network and multisig id
-    let network = std::env::args().nth(1).ok_or(anyhow!(
-        "Network not specified: 'mainnet' 'testnet' or '<url>'"
+    let network = args.next().or(settings.network.clone()).ok_or(anyhow!(
+        "Network not specified: 'mainnet' 'testnet' '<url>' or a network from the config"
     ))?;
-    let mut client = match network.as_str() {
+    let mut client = match settings.resolve_network(&network).as_str() {
         "testnet" => MultisigClient::new_testnet(),
         "mainnet" => MultisigClient::new_mainnet(),
+        // comma separated urls fail over to the next one
+        urls if urls.contains(',') => {
+            MultisigClient::new_with_urls(&urls.split(',').collect::<Vec<_>>())?
+        }
         url => MultisigClient::new_with_url(url)?,
     };
+    client.set_gas_budget(settings.gas_budget);
+
+    // local rules for the proposals and executions, ignored with --force
+    // the executed spends are saved next to it, counted against the limits across sessions
+    let policy_path = sui_config_dir()?.join("account-multisig-policy.toml");
+    if policy_path.exists() {
+        let spent_path = sui_config_dir()?.join("account-multisig-spent.json");
+        client.set_policy(Some(Policy::load(&policy_path)?.with_spent_file(spent_path)?));
+        println!("Policy loaded from {}", policy_path.display());
+    }
+
+    // extra coin tickers (TICKER = "<coin type>"), on top of the well-known ones
+    let coins_path = sui_config_dir()?.join("account-multisig-coins.toml");
+    if coins_path.exists() {
+        client.coin_registry_mut().load(&coins_path)?;
+    }
 
-    println!("{}", "Loading user...".yellow().italic());
-    client.load_user(active_addr.to_inner().into()).await?;
+    // snapshots of the loaded multisigs for offline browsing
+    let mut snapshots = Snapshots::open()?;
 
-    if let Some(id) = std::env::args().nth(2) {
+    // failures are reported but don't prevent browsing offline
+    if let Some(active_addr) = active_addr {
+        println!("{}", "Loading user...".yellow().italic());
+        if let Err(e) = client.load_user(active_addr.to_inner().into()).await {
+            eprintln!("{} {}", "Failed to load user:".red(), e);
+        }
+    }
+
+    if let Some(id) = args
+        .next()
+        .or_else(|| settings.multisigs.get(&network).cloned())
+    {
         println!("{}", "Loading multisig...".yellow().italic());
-        client
-            .load_multisig(id.parse().map_err(|_| anyhow!("Invalid multisig id"))?)
-            .await?;
+        let id = id.parse().map_err(|_| anyhow!("Invalid multisig id"))?;
+        if let Err(e) = client.load_multisig(id).await {
+            eprintln!("{} {}", "Failed to load multisig:".red(), e);
+        }
     }
 
+    // exit code of the cli, for scripts piping commands
+    let mut last_failure: Option<ErrorClass> = None;
+    // history of the commands across sessions, completion from the loaded state
+    let mut editor = Editor::<CliHelper, DefaultHistory>::new()?;
+    editor.set_helper(Some(CliHelper::new(App::command())));
+    let history_path = sui_config_dir()?.join("account-multisig-history");
+    let _ = editor.load_history(&history_path); // no history before the first session
     loop {
-        print!("{}", "\nmultisig> ".cyan());
-        io::stdout().flush()?;
-        client.refresh().await?; // update the state between commands/txs
+        // update the state between commands/txs, only refetches what changed
+        match client.refresh_incremental().await {
+            Ok(()) => snapshots.save(&client),
+            Err(e) => eprintln!("{} {}", "Failed to refresh:".red(), e),
+        }
+
+        if let Some(helper) = editor.helper_mut() {
+            helper.update(&client);
+        }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        println!();
+        let input = match editor.readline(&"multisig> ".cyan().to_string()) {
+            Ok(input) => input,
+            Err(ReadlineError::Interrupted) => continue, // ctrl-c clears the line
+            Err(ReadlineError::Eof) => break,            // ctrl-d or end of the piped commands
+            Err(e) => return Err(e.into()),
+        };
 
         let input = input.trim();
         if input.is_empty() {
             continue;
         }
+        editor.add_history_entry(input)?;
 
         let args: Vec<&str> = input.split_whitespace().collect();
         let mut clap_args = vec!["interactive"];
         clap_args.extend(args);
         match App::try_parse_from(clap_args) {
             Ok(app) => {
+                let key = match &app.command {
+                    Commands::Proposals { key, .. } => key.clone(),
+                    _ => None,
+                };
+                // signer of this command only
+                let command_pk = match app
+                    .key
+                    .as_deref()
+                    .map(|key| keys::signer(keystore(&wallet_context)?, key))
+                    .transpose()
+                {
+                    Ok(command_pk) => command_pk,
+                    Err(e) => {
+                        last_failure = Some(errors::report(&e, &client, None, None));
+                        continue;
+                    }
+                };
+                let pk = command_pk.as_ref().or(ed25519_pk.as_ref());
                 // Wrap command execution in error handling
                 let result = match app.command {
                     Commands::Exit => {
                         break;
                     }
-                    Commands::User { command } => command.run(&mut client, &ed25519_pk).await,
+                    // the user loaded at start is the one of the default key
+                    Commands::User { .. } if command_pk.is_some() => Err(anyhow!(
+                        "--key is not supported with user commands, switch the default key instead"
+                    )),
+                    Commands::User { command } => command.run(&mut client, pk).await,
+                    Commands::Keys { command } => match command {
+                        KeysCommands::List => keystore(&wallet_context)
+                            .map(|keystore| keys::list(keystore, active_addr.as_ref().unwrap())),
+                    },
                     Commands::Load { id } => {
                         if let Some(id) = id {
                             client.load_multisig(id.parse()?).await
@@ -174,25 +311,55 @@ async fn main() -> Result<()> {
                             client.refresh().await
                         }
                     }
+                    Commands::GasBudget { budget } => {
+                        let parsed = match budget.as_deref() {
+                            Some("default") | None => Ok(None),
+                            Some(budget) => budget
+                                .parse()
+                                .map(Some)
+                                .map_err(|_| anyhow!("Invalid gas budget")),
+                        };
+                        parsed.map(|parsed| {
+                            if budget.is_some() {
+                                client.set_gas_budget(parsed);
+                            }
+                            println!("Gas budget: {} MIST", client.gas_budget());
+                        })
+                    }
                     Commands::Create {
                         name,
                         global_threshold,
                         member,
                         role,
+                        pending,
                     } => {
-                        create_multisig(&client, &ed25519_pk, name, global_threshold, member, role)
+                        create_multisig(&client, pk, name, global_threshold, member, role, !pending)
                             .await
                     }
+                    Commands::Proposals {
+                        key,
+                        offline: true,
+                        ..
+                    } => snapshots.print_proposals(&client, key),
                     Commands::Proposals {
                         key,
                         proposal_command,
+                        view,
+                        ..
                     } => match (key, proposal_command) {
                         (Some(key), Some(proposal_command)) => {
-                            proposal_command
-                                .run(&mut client, &ed25519_pk, key.as_str())
-                                .await
+                            proposal_command.run(&mut client, pk, key.as_str()).await
                         }
                         (Some(key), None) => {
+                            let approval_status = client
+                                .multisig()
+                                .zip(client.intent(key.as_str()).ok())
+                                .zip(pk)
+                                .map(|((multisig, intent), pk)| {
+                                    multisig
+                                        .permissions(&pk.public_key().derive_address())
+                                        .approval_status(intent)
+                                });
                             let intent = client.intent_mut(key.as_str());
                             match intent {
                                 Ok(intent) => {
@@ -218,11 +385,28 @@ async fn main() -> Result<()> {
                                     for address in &intent.outcome.approved {
                                         print!("{}", address);
                                     }
-                                    let actions = intent.get_actions_args().await;
-                                    match actions {
+                                    if let Some(status) = approval_status {
+                                        println!("\nYour approval: {}", status);
+                                    }
+                                    match client.intent_actions(key.as_str()).await {
                                         Ok(actions) => {
                                             println!("\n\n{}", "Actions:".underline());
                                             println!("{:#?}", actions);
+                                            match &actions {
+                                                IntentActions::ConfigMultisig(fields) => {
+                                                    println!("\n{}", "New config:".underline());
+                                                    print!("{}", simulate_config(fields));
+                                                }
+                                                IntentActions::ToggleUnverifiedAllowed(fields) => {
+                                                    if let Some(allowed) = fields.will_become {
+                                                        println!(
+                                                            "\nUnverified deps allowed will become: {}",
+                                                            allowed
+                                                        );
+                                                    }
+                                                }
+                                                _ => {}
+                                            }
                                             Ok(())
                                         }
                                         Err(e) => Err(e),
@@ -232,13 +416,29 @@ async fn main() -> Result<()> {
                             }
                         }
                         (None, None) => {
-                            let intents = client.intents().ok_or(anyhow!("Intents not loaded"));
+                            let intents = client.clock_timestamp().await.and_then(|clock| {
+                                Ok((clock, client.intents().ok_or(anyhow!("Intents not loaded"))?))
+                            });
                             match intents {
-                                Ok(intents) => {
+                                Ok((clock, intents)) => {
                                     println!("\n{}\n", "=== PROPOSALS ===".bold());
-                                    for (key, intent) in &intents.intents {
-                                        println!("{} - {}", key, intent.type_);
+                                    let mut intents = intents
+                                        .intents
+                                        .values()
+                                        .map(|intent| (intent, intent.deadlines(clock)))
+                                        .collect::<Vec<_>>();
+                                    intents.sort_by_key(|(intent, deadlines)| {
+                                        (deadlines.urgency, intent.key.clone())
+                                    });
+                                    let mut table = Table::new(&["KEY", "TYPE", "DEADLINES"]);
+                                    for (intent, deadlines) in intents {
+                                        table.push(vec![
+                                            intent.key.clone(),
+                                            intent.type_.clone(),
+                                            format_deadlines(&deadlines),
+                                        ]);
                                     }
+                                    table.print(&view);
                                     Ok(())
                                 }
                                 Err(e) => Err(e),
@@ -250,17 +450,14 @@ async fn main() -> Result<()> {
                         }
                     },
                     Commands::Config { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
                                 Ok(multisig) => {
                                     println!("\n{}", "=== MULTISIG CONFIG ===".bold());
                                     println!("\n{} ", "Name:".underline());
-                                    println!(
-                                        "{}",
-                                        multisig.metadata.get("name").unwrap_or(&"".to_string())
-                                    );
+                                    println!("{}", multisig.name());
                                     println!("\n{}", "Members:".underline());
                                     for member in &multisig.config.members {
                                         println!(
@@ -272,8 +469,23 @@ async fn main() -> Result<()> {
                                     }
                                     println!("\n{}", "Thresholds:".underline());
                                     println!("Global: {}", multisig.config.global.threshold);
-                                    for (name, role) in &multisig.config.roles {
-                                        println!("{}: {}", name, role.threshold);
+                                    for role in multisig.roles_overview() {
+                                        let name = role
+                                            .name
+                                            .parse::<roles::Role>()
+                                            .map(|r| r.short_name())
+                                            .unwrap_or(role.name.clone());
+                                        println!(
+                                            "{}: {}/{} - [{}]",
+                                            name,
+                                            role.threshold,
+                                            role.total_weight,
+                                            role.members
+                                                .iter()
+                                                .map(|(address, _)| address.as_str())
+                                                .collect::<Vec<_>>()
+                                                .join(", ")
+                                        );
                                     }
                                     Ok(())
                                 }
@@ -282,7 +494,7 @@ async fn main() -> Result<()> {
                         }
                     },
                     Commands::Deps { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
@@ -298,7 +510,7 @@ async fn main() -> Result<()> {
                         }
                     },
                     Commands::Caps { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
@@ -314,7 +526,7 @@ async fn main() -> Result<()> {
                         }
                     },
                     Commands::Currencies { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
@@ -324,6 +536,13 @@ async fn main() -> Result<()> {
                                         &multisig.dynamic_fields.as_ref().unwrap().currencies
                                     {
                                         println!("\n{}:", currency.0.underline());
+                                        if let Ok(metadata) = client.coin_metadata(currency.0).await
+                                        {
+                                            println!(
+                                                "Symbol: {} ({} decimals)",
+                                                metadata.symbol, metadata.decimals
+                                            );
+                                        }
                                         println!(
                                             "Max supply: {}",
                                             currency
@@ -372,8 +591,8 @@ async fn main() -> Result<()> {
                             }
                         }
                     },
-                    Commands::Owned { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                    Commands::Owned { command, view } => match command {
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
@@ -383,16 +602,29 @@ async fn main() -> Result<()> {
                                     let mut coins =
                                         multisig.owned_objects.as_ref().unwrap().coins.clone();
                                     coins.sort_by(|a, b| a.type_.cmp(&b.type_));
+                                    let mut table = Table::new(&["TYPE", "BALANCE", "ID"]);
                                     for coin in coins {
-                                        println!("{} - {} - {}", coin.type_, coin.balance, coin.id);
+                                        table.push(vec![
+                                            coin.type_.clone(),
+                                            client
+                                                .format_amount(coin.coin_type(), coin.balance)
+                                                .await,
+                                            coin.id.to_string(),
+                                        ]);
                                     }
+                                    table.print(&view);
                                     println!("\n{}", "Objects:".underline());
                                     let mut objects =
                                         multisig.owned_objects.as_ref().unwrap().objects.clone();
                                     objects.sort_by(|a, b| a.type_.cmp(&b.type_));
+                                    let mut table = Table::new(&["TYPE", "ID"]);
                                     for object in objects {
-                                        println!("{} - {}", object.type_, object.id);
+                                        table.push(vec![
+                                            object.type_.clone(),
+                                            object.id.to_string(),
+                                        ]);
                                     }
+                                    table.print(&view);
                                     Ok(())
                                 }
                                 Err(e) => Err(e),
@@ -400,7 +632,7 @@ async fn main() -> Result<()> {
                         }
                     },
                     Commands::Packages { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
@@ -421,20 +653,32 @@ async fn main() -> Result<()> {
                             }
                         }
                     },
-                    Commands::Vaults { command } => match command {
-                        Some(command) => command.run(&mut client, &ed25519_pk).await,
+                    Commands::Vaults { command, view } => match command {
+                        Some(command) => command.run(&mut client, pk).await,
                         None => {
                             let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"));
                             match multisig {
                                 Ok(multisig) => {
                                     println!("\n{}", "=== VAULTS ===".bold());
                                     if let Some(dynamic_fields) = multisig.dynamic_fields.as_ref() {
-                                        for (vault_name, vault) in &dynamic_fields.vaults {
-                                            println!("\n{}:", vault_name.underline());
-                                            for (coin_type, amount) in &vault.coins {
-                                                println!("{} - {}", coin_type, amount);
+                                        let mut vaults =
+                                            dynamic_fields.vaults.iter().collect::<Vec<_>>();
+                                        vaults.sort_by_key(|(vault_name, _)| *vault_name);
+                                        let mut table =
+                                            Table::new(&["VAULT", "COIN TYPE", "AMOUNT"]);
+                                        for (vault_name, vault) in vaults {
+                                            let mut coins = vault.coins.iter().collect::<Vec<_>>();
+                                            coins.sort();
+                                            for (coin_type, amount) in coins {
+                                                table.push(vec![
+                                                    vault_name.clone(),
+                                                    coin_type.clone(),
+                                                    client.format_amount(coin_type, *amount).await,
+                                                ]);
                                             }
                                         }
+                                        println!();
+                                        table.print(&view);
                                     }
                                     Ok(())
                                 }
@@ -443,15 +687,64 @@ async fn main() -> Result<()> {
                         }
                     },
                 };
+                // unreachable endpoints are replaced by the next ones of --urls
+                if let Err(e) = client.record_result(&result) {
+                    eprintln!("{} {}", "Failed to switch endpoint:".red(), e);
+                }
                 if let Err(e) = result {
-                    eprintln!("Error: {e}");
+                    let signer = pk.map(|pk| pk.public_key().derive_address());
+                    last_failure = Some(errors::report(&e, &client, key.as_deref(), signer));
                 }
             }
             Err(e) => {
                 eprintln!("Parse error: {e}");
+                // help and version are printed through errors too
+                if e.use_stderr() {
+                    last_failure = Some(ErrorClass::Usage);
+                }
             }
         }
     }
 
+    editor.save_history(&history_path)?;
+    if let Some(class) = last_failure {
+        std::process::exit(class.exit_code());
+    }
     Ok(())
 }
+
+// None in read-only mode
+fn keystore(wallet_context: &Option<WalletContext>) -> Result<&Keystore> {
+    wallet_context
+        .as_ref()
+        .map(|wallet_context| &wallet_context.config.keystore)
+        .ok_or(anyhow!("Read-only mode, no keystore loaded"))
+}
+
+// red when expired or expiring within a day, yellow within a week
+fn format_deadlines(deadlines: &Deadlines) -> String {
+    let executable = match deadlines.until_executable {
+        Some(left) => format!("executable in {}", format_duration(left)),
+        None => "executable".to_string(),
+    };
+    let expiry = match deadlines.until_expiry {
+        Some(left) => format!("expires in {}", format_duration(left)),
+        None => "expired".to_string(),
+    };
+    let text = format!("{}, {}", executable, expiry);
+    match deadlines.urgency {
+        Urgency::Expired => text.red().to_string(),
+        Urgency::ExpiresToday => text.red().bold().to_string(),
+        Urgency::ExpiresThisWeek => text.yellow().to_string(),
+        Urgency::Normal => text.green().to_string(),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, minutes) => format!("{}m", minutes),
+        (0, hours, minutes) => format!("{}h {}m", hours, minutes),
+        (days, hours, _) => format!("{}d {}h", days, hours),
+    }
+}
@@ -0,0 +1,90 @@
+use account_multisig_sdk::MultisigClient;
+#[cfg(feature = "offline")]
+use account_multisig_sdk::store::Store;
+use anyhow::{Result, anyhow};
+use colored::*;
+
+// snapshots of the loaded multisigs for `proposals --offline`, saved after each refresh in the
+// sui config dir, a no-op when the cli is built without the offline feature (no sqlite)
+pub struct Snapshots {
+    #[cfg(feature = "offline")]
+    store: Store,
+}
+
+impl Snapshots {
+    #[cfg(feature = "offline")]
+    pub fn open() -> Result<Self> {
+        let path = sui_config::sui_config_dir()?.join("account-multisig.db");
+        Ok(Self {
+            store: Store::open(path)?,
+        })
+    }
+
+    #[cfg(not(feature = "offline"))]
+    pub fn open() -> Result<Self> {
+        Ok(Self {})
+    }
+
+    // failures are only a warning, the cli keeps working online
+    pub fn save(&mut self, client: &MultisigClient) {
+        #[cfg(feature = "offline")]
+        if let Some(multisig) = client.multisig() {
+            if let Err(e) = self.store.save_snapshot(multisig) {
+                eprintln!("{} {}", "Failed to save the snapshot:".yellow(), e);
+            }
+        }
+        #[cfg(not(feature = "offline"))]
+        let _ = client;
+    }
+
+    #[cfg(feature = "offline")]
+    pub fn print_proposals(&self, client: &MultisigClient, key: Option<String>) -> Result<()> {
+        let id = match client.multisig_id() {
+            Ok(id) => id,
+            Err(_) => self
+                .store
+                .last_multisig()?
+                .ok_or(anyhow!("No multisig saved locally"))?,
+        };
+        let snapshot = self
+            .store
+            .load_snapshot(id)?
+            .ok_or(anyhow!("Multisig {} not saved locally", id))?;
+        println!(
+            "{}",
+            format!("Offline snapshot of {} (version {})", id, snapshot.version)
+                .yellow()
+                .italic()
+        );
+
+        match key {
+            Some(key) => {
+                let intent = snapshot
+                    .intents
+                    .iter()
+                    .find(|intent| intent["key"] == key.as_str())
+                    .ok_or(anyhow!("Proposal {} not saved locally", key))?;
+                println!("\n{}", "=== PROPOSAL ===".bold());
+                println!("{}", serde_json::to_string_pretty(intent)?);
+            }
+            None => {
+                println!("\n{}\n", "=== PROPOSALS ===".bold());
+                for intent in &snapshot.intents {
+                    println!(
+                        "{} - {}",
+                        intent["key"].as_str().unwrap_or_default(),
+                        intent["type"].as_str().unwrap_or_default()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "offline"))]
+    pub fn print_proposals(&self, _client: &MultisigClient, _key: Option<String>) -> Result<()> {
+        Err(anyhow!(
+            "Offline snapshots are not available, build the cli with the offline feature"
+        ))
+    }
+}
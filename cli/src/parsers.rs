@@ -1,3 +1,4 @@
+use account_multisig_sdk::roles;
 
 #[derive(Debug, Clone)]
 pub struct Member {
@@ -19,7 +20,8 @@ impl std::str::FromStr for Member {
             .map_err(|_| "Invalid weight")?;
         let roles = parts
             .next()
-            .map(|r| r.split(',').map(|s| s.to_string()).collect())
+            .map(|r| r.split(',').map(parse_role).collect::<Result<Vec<_>, _>>())
+            .transpose()?
             .unwrap_or_else(Vec::new);
         Ok(Member {
             address,
@@ -38,14 +40,43 @@ pub struct Role {
 impl std::str::FromStr for Role {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Format: name:threshold
-        let mut parts = s.splitn(2, ':');
-        let name = parts.next().ok_or("Missing name")?.to_string();
-        let threshold = parts
+        // Format: name:threshold (name can contain "::")
+        let (name, threshold) = s.rsplit_once(':').ok_or("Missing threshold")?;
+        let name = parse_role(name)?;
+        let threshold = threshold.parse().map_err(|_| "Invalid threshold")?;
+        Ok(Role { name, threshold })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Payout {
+    pub coin_type: String,
+    pub amount: u64,
+    // address or SuiNS name, resolved by the command
+    pub recipient: String,
+}
+
+impl std::str::FromStr for Payout {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Format: coin_type:amount:recipient (coin type contains "::")
+        let mut parts = s.rsplitn(3, ':');
+        let recipient = parts.next().ok_or("Missing recipient")?.to_string();
+        let amount = parts
             .next()
-            .ok_or("Missing threshold")?
+            .ok_or("Missing amount")?
             .parse()
-            .map_err(|_| "Invalid threshold")?;
-        Ok(Role { name, threshold })
+            .map_err(|_| "Invalid amount")?;
+        let coin_type = parts.next().ok_or("Missing coin type")?.to_string();
+        Ok(Payout {
+            coin_type,
+            amount,
+            recipient,
+        })
     }
-}
\ No newline at end of file
+}
+
+// any non-empty name, aliases like "actions::currency_intents" are expanded
+fn parse_role(s: &str) -> Result<String, String> {
+    roles::normalize_role_name(s).map_err(|e| e.to_string())
+}
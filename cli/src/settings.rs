@@ -0,0 +1,174 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// defaults of the cli so that the network and the multisig don't have to be passed every run,
+// in $XDG_CONFIG_HOME/account-multisig/config.toml (~/.config/account-multisig/config.toml)
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // used when no network is passed
+    pub network: Option<String>,
+    // name -> url, or comma separated urls to fail over
+    pub networks: BTreeMap<String, String>,
+    // network -> multisig loaded when no id is passed
+    pub multisigs: BTreeMap<String, String>,
+    // keystore alias or address of the signer, the active address of the sui client if None
+    pub key: Option<String>,
+    // in MIST, the network default if None
+    pub gas_budget: Option<u64>,
+}
+
+// for `config get` and `config set`
+pub const KEYS: &[&str] = &[
+    "network",
+    "networks.<name>",
+    "multisigs.<network>",
+    "key",
+    "gas_budget",
+];
+
+impl Settings {
+    pub fn path() -> Result<PathBuf> {
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME").ok_or(anyhow!("HOME is not set"))?)
+                .join(".config"),
+        };
+        Ok(config_dir.join("account-multisig").join("config.toml"))
+    }
+
+    // empty settings if the file doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        toml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| anyhow!("Invalid {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key.split_once('.') {
+            Some(("networks", name)) => self.networks.get(name).cloned(),
+            Some(("multisigs", network)) => self.multisigs.get(network).cloned(),
+            _ => match key {
+                "network" => self.network.clone(),
+                "key" => self.key.clone(),
+                "gas_budget" => self.gas_budget.map(|budget| budget.to_string()),
+                _ => return Err(unknown(key)),
+            },
+        })
+    }
+
+    // unsets the key if value is None
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> Result<()> {
+        let value = value.map(str::to_string);
+        match key.split_once('.') {
+            Some(("networks", name)) => set_entry(&mut self.networks, name, value),
+            Some(("multisigs", network)) => set_entry(&mut self.multisigs, network, value),
+            _ => match key {
+                "network" => self.network = value,
+                "key" => self.key = value,
+                "gas_budget" => {
+                    self.gas_budget = value
+                        .map(|budget| budget.parse())
+                        .transpose()
+                        .map_err(|_| anyhow!("Invalid gas budget"))?
+                }
+                _ => return Err(unknown(key)),
+            },
+        }
+        Ok(())
+    }
+
+    // (key, value) of everything set
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        if let Some(network) = &self.network {
+            entries.push(("network".to_string(), network.clone()));
+        }
+        for (name, urls) in &self.networks {
+            entries.push((format!("networks.{}", name), urls.clone()));
+        }
+        for (network, id) in &self.multisigs {
+            entries.push((format!("multisigs.{}", network), id.clone()));
+        }
+        if let Some(key) = &self.key {
+            entries.push(("key".to_string(), key.clone()));
+        }
+        if let Some(budget) = self.gas_budget {
+            entries.push(("gas_budget".to_string(), budget.to_string()));
+        }
+        entries
+    }
+
+    // urls of a named network, the network as is otherwise (testnet, mainnet or urls)
+    pub fn resolve_network(&self, network: &str) -> String {
+        self.networks
+            .get(network)
+            .cloned()
+            .unwrap_or_else(|| network.to_string())
+    }
+}
+
+fn set_entry(map: &mut BTreeMap<String, String>, key: &str, value: Option<String>) {
+    match value {
+        Some(value) => map.insert(key.to_string(), value),
+        None => map.remove(key),
+    };
+}
+
+fn unknown(key: &str) -> anyhow::Error {
+    anyhow!(
+        "Unknown setting {}, expected one of: {}",
+        key,
+        KEYS.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_and_set_keys() {
+        let mut settings = Settings::default();
+        settings.set("network", Some("mainnet")).unwrap();
+        settings
+            .set("networks.local", Some("http://127.0.0.1:9000"))
+            .unwrap();
+        settings.set("multisigs.testnet", Some("0x1")).unwrap();
+        settings.set("gas_budget", Some("50000000")).unwrap();
+
+        assert_eq!(settings.get("network").unwrap().as_deref(), Some("mainnet"));
+        assert_eq!(
+            settings.get("networks.local").unwrap().as_deref(),
+            Some("http://127.0.0.1:9000")
+        );
+        assert_eq!(settings.get("multisigs.testnet").unwrap().as_deref(), Some("0x1"));
+        assert_eq!(settings.get("multisigs.mainnet").unwrap(), None);
+        assert_eq!(settings.gas_budget, Some(50_000_000));
+        assert_eq!(settings.entries().len(), 4);
+
+        // unset
+        settings.set("networks.local", None).unwrap();
+        assert_eq!(settings.get("networks.local").unwrap(), None);
+        settings.set("gas_budget", None).unwrap();
+        assert_eq!(settings.get("gas_budget").unwrap(), None);
+
+        assert!(settings.set("gas_budget", Some("a lot")).is_err());
+        assert!(settings.set("unknown", Some("value")).is_err());
+        assert!(settings.get("unknown.key").is_err());
+        assert!(settings.get("key").unwrap().is_none());
+    }
+}
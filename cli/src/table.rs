@@ -0,0 +1,110 @@
+use clap::Args;
+use colored::*;
+
+// aligned columns for the list views, paginated with --limit/--page since multisigs can hold
+// hundreds of coins, long types are shortened unless --full
+
+// longer cells are shortened in the middle to keep the package address and the type name
+const MAX_WIDTH: usize = 48;
+
+#[derive(Debug, Clone, Args)]
+pub struct View {
+    #[arg(long, help = "Max rows per page")]
+    pub limit: Option<usize>,
+    #[arg(long, default_value_t = 1, help = "Page to show, starting at 1")]
+    pub page: usize,
+    #[arg(long, help = "Show the types in full")]
+    pub full: bool,
+}
+
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn print(&self, view: &View) {
+        if self.rows.is_empty() {
+            println!("None");
+            return;
+        }
+        let page = view.page.max(1);
+        let (start, end) = match view.limit {
+            Some(limit) => (
+                (page - 1).saturating_mul(limit).min(self.rows.len()),
+                page.saturating_mul(limit).min(self.rows.len()),
+            ),
+            None => (0, self.rows.len()),
+        };
+        let cell = |value: &String| {
+            if view.full {
+                value.clone()
+            } else {
+                shorten(value)
+            }
+        };
+        let rows = self.rows[start..end]
+            .iter()
+            .map(|row| row.iter().map(cell).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let widths = (0..self.headers.len())
+            .map(|i| {
+                rows.iter()
+                    .filter_map(|row| row.get(i))
+                    .chain(std::iter::once(&self.headers[i]))
+                    .map(|value| value.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        println!("{}", format_row(&self.headers, &widths).bold());
+        for row in &rows {
+            println!("{}", format_row(row, &widths));
+        }
+
+        if let Some(limit) = view.limit {
+            let pages = self.rows.len().div_ceil(limit.max(1));
+            print!("Page {}/{} ({} rows)", page, pages, self.rows.len());
+            if page < pages {
+                print!(", next with --page {}", page + 1);
+            }
+            println!();
+        }
+    }
+}
+
+fn format_row(row: &[String], widths: &[usize]) -> String {
+    row.iter()
+        .zip(widths)
+        .map(|(value, width)| format!("{:<width$}", value, width = width))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_string()
+}
+
+fn shorten(value: &str) -> String {
+    let chars = value.chars().collect::<Vec<_>>();
+    if chars.len() <= MAX_WIDTH {
+        return value.to_string();
+    }
+    let head = MAX_WIDTH / 2 - 1;
+    let tail = MAX_WIDTH - head - 1;
+    format!(
+        "{}…{}",
+        chars[..head].iter().collect::<String>(),
+        chars[chars.len() - tail..].iter().collect::<String>()
+    )
+}
@@ -1,64 +1,67 @@
+use account_multisig_sdk::{MultisigClient, errors, proposals::intents};
 use anyhow::{Result, anyhow};
 use colored::*;
 use sui_crypto::{SuiSigner, ed25519::Ed25519PrivateKey};
-use sui_graphql_client::{Client, PaginationFilter};
+use sui_graphql_client::Client;
 use sui_sdk_types::{Address, ExecutionStatus};
-use sui_transaction_builder::{TransactionBuilder, unresolved::Input};
+use sui_transaction_builder::TransactionBuilder;
 
-pub async fn init(sui_client: &Client, address: Address) -> Result<TransactionBuilder> {
-    let mut builder = TransactionBuilder::new();
-
-    let gas_coin = sui_client
-        .coins(
-            address,
-            Some("0x2::coin::Coin<0x2::sui::SUI>"),
-            PaginationFilter::default(),
-        )
-        .await?
-        .data()
-        .first()
-        .ok_or(anyhow!("No SUI coin found"))?
-        .to_owned();
-    let gas_input: Input = (&sui_client
-        .object(gas_coin.id().to_owned().into(), None)
-        .await?
-        .ok_or(anyhow!("Gas coin not found"))?)
-        .into();
+// gas budget of the client and reference gas price of the network
+pub async fn init(client: &MultisigClient, address: Address) -> Result<TransactionBuilder> {
+    client.init_tx(address).await
+}
 
-    builder.add_gas_objects(vec![gas_input.with_owned_kind()]);
-    builder.set_gas_budget(100000000);
-    builder.set_gas_price(1000);
-    builder.set_sender(address);
+// the signer is None in read-only mode (--read-only)
+pub fn signer(pk: Option<&Ed25519PrivateKey>) -> Result<&Ed25519PrivateKey> {
+    pk.ok_or(anyhow!(
+        "Read-only mode, restart the cli without --read-only to sign transactions"
+    ))
+}
 
-    Ok(builder)
+pub fn address(pk: Option<&Ed25519PrivateKey>) -> Result<Address> {
+    Ok(signer(pk)?.public_key().derive_address())
 }
 
 pub async fn execute(
     sui_client: &Client,
     builder: TransactionBuilder,
-    pk: &Ed25519PrivateKey,
+    pk: Option<&Ed25519PrivateKey>,
 ) -> Result<()> {
     let tx = builder.finish()?;
-    let sig = pk.sign_transaction(&tx)?;
+    let sig = signer(pk)?.sign_transaction(&tx)?;
 
     println!("{}", "Executing transaction...".yellow().italic());
-    let effects = sui_client.execute_tx(vec![sig], &tx).await;
+    let effects = sui_client
+        .execute_tx(vec![sig], &tx)
+        .await?
+        .ok_or(anyhow!("Transaction returned no effects"))?;
     // wait for the transaction to be finalized
     while sui_client.transaction(tx.digest()).await?.is_none() {
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
     // display effects
     println!("Effects: {:#?}", &effects);
-    let status = effects.as_ref().unwrap().as_ref().unwrap().status();
+    let status = effects.status();
     if status == &ExecutionStatus::Success {
         println!("\n{}", "Transaction executed successfully".green());
     } else {
         println!("\n{}", "Transaction failed".red());
         if let ExecutionStatus::Failure { error, command } = status.clone() {
-            println!("Error: {:?}", error);
+            match errors::decode_abort(sui_client, &error).await {
+                Some(abort) => println!("Error: {}", abort),
+                None => println!("Error: {:?}", error),
+            }
             println!("Command: {:?}", command);
         }
     }
 
     Ok(())
 }
+
+// link to the proposal in the web app, to share with the other members
+pub fn print_share_link(client: &MultisigClient, key: &str) {
+    if let Some(multisig) = client.multisig() {
+        let link = intents::share_link(intents::DEFAULT_FRONTEND, multisig.id, key);
+        println!("Proposal {}: {}", key, link.cyan());
+    }
+}
@@ -1,11 +1,11 @@
 use anyhow::Result;
 use base64ct::{Base64, Encoding};
 use sui_crypto::{ed25519::Ed25519PrivateKey, SuiSigner};
-use sui_graphql_client::{Client, PaginationFilter};
+use sui_graphql_client::Client;
 use sui_sdk_types::{Address, ExecutionStatus};
-use sui_transaction_builder::{unresolved::Input, TransactionBuilder};
+use sui_transaction_builder::TransactionBuilder;
 
-use account_multisig_sdk::MultisigClient;
+use account_multisig_sdk::{utils, MultisigClient};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -14,17 +14,19 @@ async fn main() -> Result<()> {
         Address::from_hex("0xbd4128161c82c7b58e320c2cf7ed10a0bffc3de1859593879c15875800bda672")
             .unwrap(),
     ).await?;
-    let mut builder = init_tx(client.sui()).await;
+    let (mut builder, sender) = init_tx(client.sui()).await;
 
     // approve intent
-    client.approve_intent(&mut builder, "borrow_cap_again").await?;
+    client
+        .approve_intent(&mut builder, "borrow_cap_again", sender)
+        .await?;
 
     execute_tx(client.sui(), builder).await;
 
     Ok(())
 }
 
-async fn init_tx(sui_client: &Client) -> TransactionBuilder {
+async fn init_tx(sui_client: &Client) -> (TransactionBuilder, Address) {
     let pk = Ed25519PrivateKey::new(
         (&Base64::decode_vec("AM06bExREdFceWiExfSacTJ+64AQtFl7SRkSiTmAqh6F").unwrap()[1..])
             .try_into()
@@ -32,33 +34,9 @@ async fn init_tx(sui_client: &Client) -> TransactionBuilder {
     );
     let address = pk.public_key().derive_address();
 
-    let mut builder = TransactionBuilder::new();
+    let builder = utils::init_tx(sui_client, address).await.unwrap();
 
-    let gas_coin = sui_client
-        .coins(
-            address,
-            Some("0x2::coin::Coin<0x2::sui::SUI>"),
-            PaginationFilter::default(),
-        )
-        .await
-        .unwrap()
-        .data()
-        .first()
-        .unwrap()
-        .to_owned();
-    let gas_input: Input = (&sui_client
-        .object(gas_coin.id().to_owned().into(), None)
-        .await
-        .unwrap()
-        .unwrap())
-        .into();
-
-    builder.add_gas_objects(vec![gas_input.with_owned_kind()]);
-    builder.set_gas_budget(100000000);
-    builder.set_gas_price(1000);
-    builder.set_sender(address);
-
-    builder
+    (builder, address)
 }
 
 async fn execute_tx(sui_client: &Client, builder: TransactionBuilder) {
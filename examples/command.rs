@@ -1,11 +1,11 @@
 use anyhow::Result;
 use base64ct::{Base64, Encoding};
 use sui_crypto::{ed25519::Ed25519PrivateKey, SuiSigner};
-use sui_graphql_client::{Client, PaginationFilter};
+use sui_graphql_client::Client;
 use sui_sdk_types::{Address, ExecutionStatus};
-use sui_transaction_builder::{unresolved::Input, TransactionBuilder};
+use sui_transaction_builder::TransactionBuilder;
 
-use account_multisig_sdk::MultisigClient;
+use account_multisig_sdk::{utils, MultisigClient};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -41,33 +41,7 @@ async fn init_tx(sui_client: &Client) -> TransactionBuilder {
     );
     let address = pk.public_key().derive_address();
 
-    let mut builder = TransactionBuilder::new();
-
-    let gas_coin = sui_client
-        .coins(
-            address,
-            Some("0x2::coin::Coin<0x2::sui::SUI>"),
-            PaginationFilter::default(),
-        )
-        .await
-        .unwrap()
-        .data()
-        .first()
-        .unwrap()
-        .to_owned();
-    let gas_input: Input = (&sui_client
-        .object(gas_coin.id().to_owned().into(), None)
-        .await
-        .unwrap()
-        .unwrap())
-        .into();
-
-    builder.add_gas_objects(vec![gas_input.with_owned_kind()]);
-    builder.set_gas_budget(100000000);
-    builder.set_gas_price(1000);
-    builder.set_sender(address);
-
-    builder
+    utils::init_tx(sui_client, address).await.unwrap()
 }
 
 async fn execute_tx(sui_client: &Client, builder: TransactionBuilder) {
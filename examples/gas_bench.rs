@@ -0,0 +1,269 @@
+// dry runs the request of each intent type the multisig can build without extra inputs, then the
+// execution of the intents that are executable, and reports the gas and the size of each PTB.
+// nothing is submitted. run it before and after a change of the builders and compare the reports:
+//
+// cargo run --example gas_bench -- <multisig id> <member address> [report.json]
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use sui_sdk_types::{Address, Transaction, TransactionKind};
+use sui_transaction_builder::TransactionBuilder;
+
+use account_multisig_sdk::{
+    proposals::params::{ConfigDepsArgs, SpendAndTransferArgs},
+    utils, IntentType, MultisigClient,
+};
+
+#[derive(Debug, Serialize)]
+struct Report {
+    flow: String,
+    commands: usize,
+    inputs: usize,
+    // same input passed more than once, each should be added once and reused
+    duplicate_inputs: usize,
+    tx_bytes: usize,
+    computation_cost: u64,
+    storage_cost: u64,
+    storage_rebate: u64,
+    // None if the dry run succeeded
+    error: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let usage = "usage: gas_bench <multisig id> <member address> [report.json]";
+    let multisig_id: Address = args.next().ok_or(anyhow!(usage))?.parse()?;
+    let sender: Address = args.next().ok_or(anyhow!(usage))?.parse()?;
+    let output = args.next();
+
+    let mut client = MultisigClient::new_testnet();
+    client.load_multisig(multisig_id).await?;
+
+    let mut reports = Vec::new();
+    for flow in [
+        "config_metadata",
+        "config_deps",
+        "toggle_unverified_allowed",
+        "borrow_cap",
+        "spend_and_transfer",
+    ] {
+        let mut builder = client.init_tx(sender).await?;
+        let report = match build_request(&client, &mut builder, flow, sender).await {
+            Ok(true) => measure(&client, flow, builder).await,
+            Ok(false) => continue, // nothing to request in this multisig
+            Err(e) => failed(flow, e),
+        };
+        reports.push(report);
+    }
+
+    let executable = client
+        .intents()
+        .map(|intents| {
+            intents
+                .intents
+                .values()
+                .filter(|intent| client.multisig().unwrap().is_approved(intent))
+                .map(|intent| (intent.key.clone(), intent.type_.clone()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for (key, type_) in executable {
+        let flow = format!("execute {} ({})", key, type_);
+        let mut builder = client.init_tx(sender).await?;
+        let report = match build_execution(&mut client, &mut builder, &key).await {
+            Ok(()) => measure(&client, &flow, builder).await,
+            Err(e) => failed(&flow, e),
+        };
+        reports.push(report);
+    }
+
+    println!(
+        "{:<50} {:>8} {:>6} {:>5} {:>8} {:>12} {:>12} {:>12}",
+        "flow", "commands", "inputs", "dup", "bytes", "computation", "storage", "rebate"
+    );
+    for report in &reports {
+        match &report.error {
+            Some(error) => println!("{:<50} failed: {}", report.flow, error),
+            None => println!(
+                "{:<50} {:>8} {:>6} {:>5} {:>8} {:>12} {:>12} {:>12}",
+                report.flow,
+                report.commands,
+                report.inputs,
+                report.duplicate_inputs,
+                report.tx_bytes,
+                report.computation_cost,
+                report.storage_cost,
+                report.storage_rebate
+            ),
+        }
+    }
+    if let Some(output) = output {
+        std::fs::write(&output, serde_json::to_string_pretty(&reports)?)?;
+        println!("Report written to {}", output);
+    }
+
+    Ok(())
+}
+
+// false if the multisig has nothing to build the flow with (no cap, no vault)
+async fn build_request(
+    client: &MultisigClient,
+    builder: &mut TransactionBuilder,
+    flow: &str,
+    sender: Address,
+) -> Result<bool> {
+    let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+    let params = client
+        .intent_params(&format!("bench_{}", flow))
+        .await?
+        .immediate();
+    match flow {
+        "config_metadata" => {
+            client
+                .request_config_metadata(builder, params, &multisig.profile())
+                .await?
+        }
+        "config_deps" => {
+            let (names, (addresses, versions)) = multisig
+                .deps
+                .iter()
+                .map(|dep| (dep.name.clone(), (dep.addr, dep.version)))
+                .unzip();
+            let actions_args = ConfigDepsArgs::new(builder, names, addresses, versions);
+            let params = params.build(builder);
+            client
+                .request_config_deps(builder, params, actions_args)
+                .await?
+        }
+        "toggle_unverified_allowed" => {
+            let params = params.build(builder);
+            client
+                .request_toggle_unverified_allowed(builder, params)
+                .await?
+        }
+        "borrow_cap" => {
+            let Some(cap) = client.dynamic_fields().and_then(|df| df.caps.first()) else {
+                return Ok(false);
+            };
+            let params = params.build(builder);
+            client
+                .request_borrow_cap(builder, params, cap.type_.as_str())
+                .await?
+        }
+        "spend_and_transfer" => {
+            let Some((vault_name, coin_type)) = client.dynamic_fields().and_then(|df| {
+                df.vaults.iter().find_map(|(name, vault)| {
+                    let coin_type = vault.coins.iter().find(|(_, balance)| **balance > 0)?.0;
+                    Some((name.clone(), coin_type.clone()))
+                })
+            }) else {
+                return Ok(false);
+            };
+            let actions_args =
+                SpendAndTransferArgs::new(builder, vault_name, vec![1], vec![sender]);
+            let params = params.build(builder);
+            client
+                .request_spend_and_transfer(builder, params, actions_args, coin_type.as_str())
+                .await?
+        }
+        _ => return Err(anyhow!("Unknown flow {}", flow)),
+    }
+    Ok(true)
+}
+
+async fn build_execution(
+    client: &mut MultisigClient,
+    builder: &mut TransactionBuilder,
+    key: &str,
+) -> Result<()> {
+    let intent_type = IntentType::try_from(client.intent(key)?.type_.as_str())?;
+    match intent_type {
+        IntentType::ConfigMultisig => client.execute_config_multisig(builder, key).await,
+        IntentType::ConfigDeps => client.execute_config_deps(builder, key).await,
+        IntentType::ToggleUnverifiedAllowed => {
+            client.execute_toggle_unverified_allowed(builder, key).await
+        }
+        IntentType::BorrowCap => {
+            client
+                .execute_with_borrowed_cap(builder, key, |_, _| Ok(()))
+                .await
+        }
+        IntentType::DisableRules => client.execute_disable_rules(builder, key).await,
+        IntentType::UpdateMetadata => client.execute_update_metadata(builder, key).await,
+        IntentType::MintAndTransfer => client.execute_mint_and_transfer(builder, key).await,
+        IntentType::MintAndVest => client.execute_mint_and_vest(builder, key).await,
+        IntentType::WithdrawAndBurn => client.execute_withdraw_and_burn(builder, key).await,
+        IntentType::WithdrawAndTransferToVault => {
+            client
+                .execute_withdraw_and_transfer_to_vault(builder, key)
+                .await
+        }
+        IntentType::WithdrawAndTransfer => client.execute_withdraw_and_transfer(builder, key).await,
+        IntentType::WithdrawAndVest => client.execute_withdraw_and_vest(builder, key).await,
+        IntentType::SpendAndTransfer => client.execute_spend_and_transfer(builder, key).await,
+        IntentType::SpendAndVest => client.execute_spend_and_vest(builder, key).await,
+        IntentType::RestrictPolicy => client.execute_restrict_policy(builder, key).await,
+        _ => Err(anyhow!("{:?} is not benchmarked", intent_type)),
+    }
+}
+
+async fn measure(client: &MultisigClient, flow: &str, builder: TransactionBuilder) -> Report {
+    let tx = match builder.finish() {
+        Ok(tx) => tx,
+        Err(e) => return failed(flow, anyhow!("{}", e)),
+    };
+    let mut report = ptb_size(flow, &tx);
+    match utils::dry_run_tx(client.sui(), &tx).await {
+        Ok(effects) => {
+            let gas = effects.gas_summary();
+            report.computation_cost = gas.computation_cost;
+            report.storage_cost = gas.storage_cost;
+            report.storage_rebate = gas.storage_rebate;
+        }
+        Err(e) => report.error = Some(e.to_string()),
+    }
+    report
+}
+
+fn ptb_size(flow: &str, tx: &Transaction) -> Report {
+    let (commands, inputs, duplicate_inputs) = match &tx.kind {
+        TransactionKind::ProgrammableTransaction(ptb) => {
+            let duplicates = ptb
+                .inputs
+                .iter()
+                .enumerate()
+                .filter(|(i, input)| ptb.inputs[..*i].contains(input))
+                .count();
+            (ptb.commands.len(), ptb.inputs.len(), duplicates)
+        }
+        _ => (0, 0, 0),
+    };
+    Report {
+        flow: flow.to_string(),
+        commands,
+        inputs,
+        duplicate_inputs,
+        tx_bytes: bcs::to_bytes(tx)
+            .map(|bytes| bytes.len())
+            .unwrap_or_default(),
+        computation_cost: 0,
+        storage_cost: 0,
+        storage_rebate: 0,
+        error: None,
+    }
+}
+
+fn failed(flow: &str, e: anyhow::Error) -> Report {
+    Report {
+        flow: flow.to_string(),
+        commands: 0,
+        inputs: 0,
+        duplicate_inputs: 0,
+        tx_bytes: 0,
+        computation_cost: 0,
+        storage_cost: 0,
+        storage_rebate: 0,
+        error: Some(e.to_string()),
+    }
+}
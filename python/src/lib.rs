@@ -0,0 +1,7 @@
+use pyo3::prelude::*;
+
+// extension module built with maturin, the bindings live in the sdk behind the python feature
+#[pymodule]
+fn account_multisig(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    account_multisig_sdk::python::register(m)
+}
@@ -0,0 +1,215 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use sui_crypto::SuiSigner;
+use sui_sdk_types::TransactionDigest;
+
+use crate::assets::dynamic_fields::normalize_type;
+use crate::errors::MultisigError;
+use crate::policy::{self, Outflow, Policy};
+use crate::proposals::actions::IntentType;
+use crate::utils;
+use crate::MultisigClient;
+
+// approves the new intents allowed by the rules and never touches the others,
+// for setups where one of the members is a service (see MultisigClient::run_auto_approver)
+// denies by default: only the types in allowed_intents are approved, and only if the coins they
+// send have a spending limit in the rules
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalDecision {
+    pub key: String,
+    pub intent_type: String,
+    pub approved: bool,
+    // why the intent was left untouched
+    pub reason: Option<String>,
+    pub digest: Option<TransactionDigest>,
+}
+
+pub struct AutoApprover {
+    pub rules: Policy,
+    // intents already decided, each intent is evaluated once
+    decided: HashSet<String>,
+}
+
+impl ApprovalDecision {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "key": self.key,
+            "intent_type": self.intent_type,
+            "approved": self.approved,
+            "reason": self.reason,
+            "digest": self.digest.map(|digest| digest.to_string()),
+        })
+    }
+}
+
+impl AutoApprover {
+    pub fn new(rules: Policy) -> Self {
+        Self {
+            rules,
+            decided: HashSet::new(),
+        }
+    }
+
+    // refreshes the multisig and decides on the intents seen for the first time,
+    // the approved amounts are recorded against the limits of the rules
+    pub async fn poll<S: SuiSigner>(
+        &mut self,
+        client: &mut MultisigClient,
+        signer: &S,
+    ) -> Result<Vec<ApprovalDecision>> {
+        client.refresh_incremental().await?;
+        let mut keys = client
+            .intents()
+            .ok_or(anyhow!("Intents not loaded"))?
+            .intents
+            .keys()
+            .filter(|key| !self.decided.contains(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let mut decisions = Vec::new();
+        // an intent is only decided once evaluated, a failed fetch retries it at the next poll
+        for key in keys {
+            if client.has_approved(&key)? == Some(true) {
+                self.decided.insert(key);
+                continue;
+            }
+
+            let type_ = client.intent(&key)?.type_.clone();
+            let mut decision = ApprovalDecision {
+                key: key.clone(),
+                intent_type: type_.clone(),
+                approved: false,
+                reason: None,
+                digest: None,
+            };
+            let intent_type = match IntentType::try_from(type_.as_str()) {
+                Ok(intent_type) => intent_type,
+                Err(e) => {
+                    self.decided.insert(key);
+                    decision.reason = Some(e.to_string());
+                    decisions.push(decision);
+                    continue;
+                }
+            };
+
+            // every execution sends the outflows again
+            let executions = client.intent(&key)?.execution_times.len();
+            let actions = client.intent_mut(&key)?.get_actions_args().await?.clone();
+            let outflows = policy::outflows(&actions).repeat(executions);
+            let now = client.clock_timestamp().await?;
+            if let Err(e) = self.allows(&intent_type, &outflows, now) {
+                self.decided.insert(key);
+                decision.reason = Some(e.to_string());
+                decisions.push(decision);
+                continue;
+            }
+
+            // retried at the next poll if the transaction fails, the other intents are decided
+            let digest = match approve(client, signer, &key).await {
+                Ok(digest) => digest,
+                Err(e) => {
+                    decision.reason = Some(format!("Approval failed: {}", e));
+                    decisions.push(decision);
+                    continue;
+                }
+            };
+            // the approval is already submitted, the decision is kept
+            self.decided.insert(key);
+            if let Err(e) = self.rules.record(&outflows, now) {
+                decision.reason = Some(format!("Spend not saved: {}", e));
+            }
+            decision.approved = true;
+            decision.digest = Some(digest);
+            decisions.push(decision);
+        }
+
+        Ok(decisions)
+    }
+
+    // the rules with nothing allowed unless listed, see the module comment
+    pub fn allows(&self, intent_type: &IntentType, outflows: &[Outflow], now: u64) -> Result<()> {
+        let name = format!("{:?}", intent_type);
+        if !self.rules.allowed_intents.contains(&name) {
+            return Err(MultisigError::IntentBlocked { intent_type: name }.into());
+        }
+        if let Some(coin_type) = outflows
+            .iter()
+            .filter_map(|outflow| outflow.coin_type.as_ref())
+            .find(|coin_type| {
+                !self
+                    .rules
+                    .limits
+                    .iter()
+                    .any(|limit| normalize_type(&limit.coin_type) == normalize_type(coin_type))
+            })
+        {
+            return Err(anyhow!("No spending limit for {} in the rules", coin_type));
+        }
+        self.rules.check(intent_type, outflows, now)
+    }
+}
+
+async fn approve<S: SuiSigner>(
+    client: &MultisigClient,
+    signer: &S,
+    key: &str,
+) -> Result<TransactionDigest> {
+    let sender = client.user().ok_or(anyhow!("User not loaded"))?.address;
+    let mut builder = client.init_tx(sender).await?;
+    client.approve_intent(&mut builder, key, sender).await?;
+    let tx = builder.finish()?;
+    let signature = signer.sign_transaction(&tx)?;
+    utils::submit_tx(client.sui(), &tx, vec![signature]).await?;
+    Ok(tx.digest())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::SpendingLimit;
+    use sui_sdk_types::Address;
+
+    fn sui(amount: u64) -> Outflow {
+        Outflow {
+            coin_type: Some("0x2::sui::SUI".to_string()),
+            amount,
+            recipient: Address::ZERO,
+        }
+    }
+
+    #[test]
+    fn deny_by_default() {
+        let mut approver = AutoApprover::new(Policy::default());
+        assert!(approver
+            .allows(&IntentType::SpendAndTransfer, &[], 0)
+            .is_err());
+
+        approver.rules.allowed_intents = vec!["SpendAndTransfer".to_string()];
+        assert!(approver
+            .allows(&IntentType::SpendAndTransfer, &[], 0)
+            .is_ok());
+        assert!(approver
+            .allows(&IntentType::MintAndTransfer, &[], 0)
+            .is_err());
+        // coins without a limit are denied
+        assert!(approver
+            .allows(&IntentType::SpendAndTransfer, &[sui(10)], 0)
+            .is_err());
+
+        approver.rules.limits.push(SpendingLimit {
+            coin_type: "0x2::sui::SUI".to_string(),
+            max_amount: 100,
+            period_ms: 1_000,
+        });
+        assert!(approver
+            .allows(&IntentType::SpendAndTransfer, &[sui(10)], 0)
+            .is_ok());
+        assert!(approver
+            .allows(&IntentType::SpendAndTransfer, &[sui(200)], 0)
+            .is_err());
+    }
+}
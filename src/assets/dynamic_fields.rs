@@ -4,12 +4,13 @@ use std::fmt;
 use std::sync::Arc;
 
 use move_types::TypeTag;
-use sui_graphql_client::{Client, Direction, PaginationFilter};
+use sui_graphql_client::{Client, Direction, DynamicFieldOutput, PaginationFilter};
 use sui_sdk_types::Address;
 
-use crate::utils;
-use crate::move_binding::sui;
 use crate::move_binding::account_actions as aa;
+use crate::move_binding::sui;
+use crate::utils;
+use crate::ACCOUNT_ACTIONS_PACKAGE;
 
 pub struct DynamicFields {
     pub sui_client: Arc<Client>,
@@ -19,6 +20,16 @@ pub struct DynamicFields {
     pub kiosks: HashMap<String, Kiosk>,
     pub packages: HashMap<String, Package>,
     pub vaults: HashMap<String, Vault>,
+    // fields not handled by the sdk, e.g. from newer versions of the packages
+    pub other: Vec<RawDynamicField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawDynamicField {
+    pub key_type: String,
+    pub key_bcs: Vec<u8>,
+    pub value_type: String,
+    pub value_bcs: Vec<u8>,
 }
 
 #[derive(Debug)]
@@ -71,6 +82,7 @@ impl DynamicFields {
             kiosks: HashMap::new(),
             packages: HashMap::new(),
             vaults: HashMap::new(),
+            other: Vec::new(),
         };
         dynamic_fields.refresh().await?;
         Ok(dynamic_fields)
@@ -78,157 +90,265 @@ impl DynamicFields {
 
     pub async fn refresh(&mut self) -> Result<()> {
         let df_outputs = utils::get_dynamic_fields(&self.sui_client, self.multisig_id).await?;
-        for df_output in df_outputs {
-            if let TypeTag::Struct(struct_tag) = &df_output.name.type_ {
-                let type_name = format!("{}::{}::{}", struct_tag.address, struct_tag.module, struct_tag.name);
-                let generic = struct_tag
-                    .type_params
-                    .first()
-                    .and_then(|type_tag| match type_tag {
-                        TypeTag::Struct(struct_tag) => {
-                            Some(format!("{}::{}::{}", struct_tag.address, struct_tag.module, struct_tag.name))
-                        }
-                        _ => None,
-                    })
-                    .unwrap_or_default();
-                let key_bcs = df_output.name.bcs.as_ref();
-                let value_bcs = df_output.value.as_ref().ok_or(anyhow!("Couldn't get dynamic field bcs"))?.1.as_ref();
-
-                match type_name.as_str() {
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::access_control::CapKey" => {
-                        self.caps.push(Cap { type_: generic });
-                    },
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::currency::TreasuryCapKey" => {
-                        let treasury_cap: sui::coin::TreasuryCap<()> = bcs::from_bytes(value_bcs)?;
-                        self
-                            .currencies
-                            .entry(generic)
-                            .and_modify(|currency| currency.current_supply = treasury_cap.total_supply.value)
-                            .or_insert_with(|| Currency {
-                                current_supply: treasury_cap.total_supply.value,
-                                max_supply: None,
-                                total_minted: 0,
-                                total_burned: 0,
-                                can_mint: false,
-                                can_burn: false,
-                                can_update_symbol: false,
-                                can_update_name: false,
-                                can_update_description: false,
-                                can_update_icon: false,
-                            });
-                    },
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::currency::CurrencyRulesKey" => {
-                        let currency_rules: aa::currency::CurrencyRules<()> = bcs::from_bytes(value_bcs)?;
-                        self
-                            .currencies
-                            .entry(generic) 
-                            .and_modify(|currency| {
-                                currency.max_supply = currency_rules.max_supply;
-                                currency.total_minted = currency_rules.total_minted;
-                                currency.total_burned = currency_rules.total_burned;
-                                currency.can_mint = currency_rules.can_mint;
-                                currency.can_burn = currency_rules.can_burn;
-                                currency.can_update_symbol = currency_rules.can_update_symbol;
-                                currency.can_update_name = currency_rules.can_update_name;
-                                currency.can_update_description = currency_rules.can_update_description;
-                                currency.can_update_icon = currency_rules.can_update_icon;
-                            })
-                            .or_insert_with(|| Currency {
-                                current_supply: 0,
-                                max_supply: currency_rules.max_supply,
-                                total_minted: currency_rules.total_minted,
-                                total_burned: currency_rules.total_burned,
-                                can_mint: currency_rules.can_mint,
-                                can_burn: currency_rules.can_burn,
-                                can_update_symbol: currency_rules.can_update_symbol,
-                                can_update_name: currency_rules.can_update_name,
-                                can_update_description: currency_rules.can_update_description,
-                                can_update_icon: currency_rules.can_update_icon,
-                            });
-                    },
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::kiosk::KioskOwnerKey" => {
-                        let kiosk_owner_key: aa::kiosk::KioskOwnerKey = bcs::from_bytes(key_bcs)?;
-                        let kiosk_owner_cap: sui::kiosk::KioskOwnerCap = bcs::from_bytes(value_bcs)?;
-
-                        self.kiosks.insert(kiosk_owner_key.pos0, Kiosk {
-                            id: kiosk_owner_cap.id.into(),
-                            cap: kiosk_owner_cap.for_.into(),
-                        });
-                    },
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::package_upgrade::UpgradeCapKey" => {
-                        let upgrade_cap_key: aa::package_upgrade::UpgradeCapKey = bcs::from_bytes(key_bcs)?;
-                        let upgrade_cap: sui::package::UpgradeCap = bcs::from_bytes(value_bcs)?;
-                        
-                        self
-                            .packages
-                            .entry(upgrade_cap_key.pos0)
-                            .and_modify(|package| {
-                                package.package_id = upgrade_cap.package.into();
-                                package.cap_id = upgrade_cap.id.into();
-                                package.policy = upgrade_cap.policy;
-                            })
-                            .or_insert_with(|| Package {
-                                package_id: upgrade_cap.package.into(),
-                                cap_id: upgrade_cap.id.into(),
-                                policy: upgrade_cap.policy,
-                                delay_ms: 0,
-                            });
-                    },
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::package_upgrade::UpgradeRulesKey" => {
-                        let upgrade_rules_key: aa::package_upgrade::UpgradeRulesKey = bcs::from_bytes(key_bcs)?;
-                        let upgrade_rules: aa::package_upgrade::UpgradeRules = bcs::from_bytes(value_bcs)?;
-                        
-                        self
-                            .packages
-                            .entry(upgrade_rules_key.pos0)
-                            .and_modify(|package| package.delay_ms = upgrade_rules.delay_ms)
-                            .or_insert_with(|| Package {
-                                package_id: Address::ZERO,
-                                cap_id: Address::ZERO,
-                                policy: 0,
-                                delay_ms: upgrade_rules.delay_ms,
-                            });
-                    },
-                    "0xf477dbfad6ab1de1fdcb6042c0afeda2aa5bf12eb7ef42d280059fc8d6c36c94::vault::VaultKey" => {
-                        let vault_key: aa::vault::VaultKey = bcs::from_bytes(key_bcs)?;
-                        let vault_bag: sui::bag::Bag = bcs::from_bytes(value_bcs)?;
-
-                        let mut coins_for_vault = HashMap::new();
-
-                        let mut cursor = None;
-                        let mut has_next_page = true;
-                        while has_next_page {
-                            let filter = PaginationFilter {
-                                direction: Direction::Forward,
-                                cursor: cursor.clone(),
-                                limit: Some(50),
-                            };
-                            let resp = self.sui_client.dynamic_fields(vault_bag.id.into(), filter).await?;
-                            for df_output in resp.data() {
-                                if let Some((TypeTag::Struct(struct_tag), value_bcs)) = &df_output.value {
-                                    let coin_type = format!("{}::{}::{}", struct_tag.address, struct_tag.module, struct_tag.name);
-                                    let coin_amount: u64 = bcs::from_bytes::<sui::coin::Coin<()>>(value_bcs)?.balance.value;
-                                    coins_for_vault.insert(coin_type, coin_amount);
-                                };
-                            }
-                            cursor = resp.page_info().end_cursor.clone();
-                            has_next_page = resp.page_info().has_next_page;
-                        }
-                        
-                        self.vaults.insert(vault_key.pos0, Vault { coins: coins_for_vault });
-                    },
-                    _ => (),
-                }
+        self.caps.clear();
+        self.currencies.clear();
+        self.kiosks.clear();
+        self.packages.clear();
+        self.vaults.clear();
+        self.other.clear();
+
+        for df_output in &df_outputs {
+            self.push(df_output).await?;
+        }
+        Ok(())
+    }
+
+    // refetches a single vault, removed if it doesn't exist anymore
+    pub async fn refresh_vault(&mut self, name: &str) -> Result<()> {
+        self.vaults.remove(name);
+        let df_output = utils::get_dynamic_field(
+            &self.sui_client,
+            self.multisig_id,
+            format!("{}::vault::VaultKey", ACCOUNT_ACTIONS_PACKAGE).parse()?,
+            bcs::to_bytes(name)?,
+        )
+        .await?;
+        if let Some(df_output) = df_output {
+            self.push(&df_output).await?;
+        }
+        Ok(())
+    }
+
+    // refetches the treasury cap and the rules of a single currency
+    pub async fn refresh_currency(&mut self, coin_type: &str) -> Result<()> {
+        self.currencies.remove(&normalize_type(coin_type));
+        for key in ["TreasuryCapKey", "CurrencyRulesKey"] {
+            let df_output = utils::get_dynamic_field(
+                &self.sui_client,
+                self.multisig_id,
+                format!(
+                    "{}::currency::{}<{}>",
+                    ACCOUNT_ACTIONS_PACKAGE, key, coin_type
+                )
+                .parse()?,
+                // empty move structs have a dummy bool field
+                bcs::to_bytes(&false)?,
+            )
+            .await?;
+            if let Some(df_output) = df_output {
+                self.push(&df_output).await?;
             }
         }
         Ok(())
     }
 
+    // coin type can use the short address form
+    pub fn currency(&self, coin_type: &str) -> Option<&Currency> {
+        self.currencies.get(&normalize_type(coin_type))
+    }
+
     pub async fn switch_multisig(&mut self, multisig_id: Address) -> Result<()> {
         self.multisig_id = multisig_id;
         self.refresh().await?;
         Ok(())
     }
+
+    // === Helpers ===
+
+    // parses a dynamic field of the account into the matching collection
+    async fn push(&mut self, df_output: &DynamicFieldOutput) -> Result<()> {
+        let value = df_output
+            .value
+            .as_ref()
+            .ok_or(anyhow!("Couldn't get dynamic field bcs"))?;
+        // keys defined by the actions package, the others are kept raw
+        let key = match &df_output.name.type_ {
+            TypeTag::Struct(struct_tag)
+                if struct_tag.address.to_string() == ACCOUNT_ACTIONS_PACKAGE =>
+            {
+                Some((struct_tag.module.to_string(), struct_tag.name.to_string()))
+            }
+            _ => None,
+        };
+        let raw = RawDynamicField {
+            key_type: df_output.name.type_.to_string(),
+            key_bcs: df_output.name.bcs.clone(),
+            value_type: value.0.to_string(),
+            value_bcs: value.1.clone(),
+        };
+
+        if let (Some((module, name)), TypeTag::Struct(struct_tag)) = (key, &df_output.name.type_) {
+            let generic = struct_tag
+                .type_params
+                .first()
+                .and_then(|type_tag| match type_tag {
+                    TypeTag::Struct(struct_tag) => Some(format!(
+                        "{}::{}::{}",
+                        struct_tag.address, struct_tag.module, struct_tag.name
+                    )),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            let key_bcs = df_output.name.bcs.as_ref();
+            let value_bcs = value.1.as_ref();
+            let key_type = Some(df_output.name.type_.to_string());
+            let value_type = Some(value.0.to_string());
+
+            match (module.as_str(), name.as_str()) {
+                ("access_control", "CapKey") => {
+                    self.caps.push(Cap { type_: generic });
+                }
+                ("currency", "TreasuryCapKey") => {
+                    let treasury_cap: sui::coin::TreasuryCap<()> =
+                        utils::decode(value_bcs, value_type.as_deref())?;
+                    self.currencies
+                        .entry(generic)
+                        .and_modify(|currency| {
+                            currency.current_supply = treasury_cap.total_supply.value
+                        })
+                        .or_insert_with(|| Currency {
+                            current_supply: treasury_cap.total_supply.value,
+                            max_supply: None,
+                            total_minted: 0,
+                            total_burned: 0,
+                            can_mint: false,
+                            can_burn: false,
+                            can_update_symbol: false,
+                            can_update_name: false,
+                            can_update_description: false,
+                            can_update_icon: false,
+                        });
+                }
+                ("currency", "CurrencyRulesKey") => {
+                    let currency_rules: aa::currency::CurrencyRules<()> =
+                        utils::decode(value_bcs, value_type.as_deref())?;
+                    self.currencies
+                        .entry(generic)
+                        .and_modify(|currency| {
+                            currency.max_supply = currency_rules.max_supply;
+                            currency.total_minted = currency_rules.total_minted;
+                            currency.total_burned = currency_rules.total_burned;
+                            currency.can_mint = currency_rules.can_mint;
+                            currency.can_burn = currency_rules.can_burn;
+                            currency.can_update_symbol = currency_rules.can_update_symbol;
+                            currency.can_update_name = currency_rules.can_update_name;
+                            currency.can_update_description = currency_rules.can_update_description;
+                            currency.can_update_icon = currency_rules.can_update_icon;
+                        })
+                        .or_insert_with(|| Currency {
+                            current_supply: 0,
+                            max_supply: currency_rules.max_supply,
+                            total_minted: currency_rules.total_minted,
+                            total_burned: currency_rules.total_burned,
+                            can_mint: currency_rules.can_mint,
+                            can_burn: currency_rules.can_burn,
+                            can_update_symbol: currency_rules.can_update_symbol,
+                            can_update_name: currency_rules.can_update_name,
+                            can_update_description: currency_rules.can_update_description,
+                            can_update_icon: currency_rules.can_update_icon,
+                        });
+                }
+                ("kiosk", "KioskOwnerKey") => {
+                    let kiosk_owner_key: aa::kiosk::KioskOwnerKey =
+                        utils::decode(key_bcs, key_type.as_deref())?;
+                    let kiosk_owner_cap: sui::kiosk::KioskOwnerCap =
+                        utils::decode(value_bcs, value_type.as_deref())?;
+
+                    self.kiosks.insert(
+                        kiosk_owner_key.pos0,
+                        Kiosk {
+                            id: kiosk_owner_cap.id.into(),
+                            cap: kiosk_owner_cap.for_.into(),
+                        },
+                    );
+                }
+                ("package_upgrade", "UpgradeCapKey") => {
+                    let upgrade_cap_key: aa::package_upgrade::UpgradeCapKey =
+                        utils::decode(key_bcs, key_type.as_deref())?;
+                    let upgrade_cap: sui::package::UpgradeCap =
+                        utils::decode(value_bcs, value_type.as_deref())?;
+
+                    self.packages
+                        .entry(upgrade_cap_key.pos0)
+                        .and_modify(|package| {
+                            package.package_id = upgrade_cap.package.into();
+                            package.cap_id = upgrade_cap.id.into();
+                            package.policy = upgrade_cap.policy;
+                        })
+                        .or_insert_with(|| Package {
+                            package_id: upgrade_cap.package.into(),
+                            cap_id: upgrade_cap.id.into(),
+                            policy: upgrade_cap.policy,
+                            delay_ms: 0,
+                        });
+                }
+                ("package_upgrade", "UpgradeRulesKey") => {
+                    let upgrade_rules_key: aa::package_upgrade::UpgradeRulesKey =
+                        utils::decode(key_bcs, key_type.as_deref())?;
+                    let upgrade_rules: aa::package_upgrade::UpgradeRules =
+                        utils::decode(value_bcs, value_type.as_deref())?;
+
+                    self.packages
+                        .entry(upgrade_rules_key.pos0)
+                        .and_modify(|package| package.delay_ms = upgrade_rules.delay_ms)
+                        .or_insert_with(|| Package {
+                            package_id: Address::ZERO,
+                            cap_id: Address::ZERO,
+                            policy: 0,
+                            delay_ms: upgrade_rules.delay_ms,
+                        });
+                }
+                ("vault", "VaultKey") => {
+                    let vault_key: aa::vault::VaultKey =
+                        utils::decode(key_bcs, key_type.as_deref())?;
+                    let vault_bag: sui::bag::Bag = utils::decode(value_bcs, value_type.as_deref())?;
+
+                    let mut coins_for_vault = HashMap::new();
+
+                    let mut cursor = None;
+                    let mut has_next_page = true;
+                    while has_next_page {
+                        let filter = PaginationFilter {
+                            direction: Direction::Forward,
+                            cursor: cursor.clone(),
+                            limit: Some(50),
+                        };
+                        let resp = self
+                            .sui_client
+                            .dynamic_fields(vault_bag.id.into(), filter)
+                            .await?;
+                        for df_output in resp.data() {
+                            if let Some((TypeTag::Struct(struct_tag), value_bcs)) = &df_output.value
+                            {
+                                let coin_type = format!(
+                                    "{}::{}::{}",
+                                    struct_tag.address, struct_tag.module, struct_tag.name
+                                );
+                                let coin_amount: u64 =
+                                    utils::decode::<sui::coin::Coin<()>>(value_bcs, None)?
+                                        .balance
+                                        .value;
+                                coins_for_vault.insert(coin_type, coin_amount);
+                            };
+                        }
+                        cursor = resp.page_info().end_cursor.clone();
+                        has_next_page = resp.page_info().has_next_page;
+                    }
+
+                    self.vaults.insert(
+                        vault_key.pos0,
+                        Vault {
+                            coins: coins_for_vault,
+                        },
+                    );
+                }
+                _ => self.other.push(raw),
+            }
+        } else {
+            self.other.push(raw);
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Debug for DynamicFields {
@@ -239,6 +359,17 @@ impl fmt::Debug for DynamicFields {
             .field("kiosks", &self.kiosks)
             .field("packages", &self.packages)
             .field("vaults", &self.vaults)
+            .field("other", &self.other)
             .finish()
     }
 }
+
+// same format as the keys of the maps: <full address>::<module>::<name>
+pub fn normalize_type(type_: &str) -> String {
+    match type_.parse::<TypeTag>() {
+        Ok(TypeTag::Struct(struct_tag)) => {
+            format!("{}::{}::{}", struct_tag.address, struct_tag.module, struct_tag.name)
+        }
+        _ => type_.to_string(),
+    }
+}
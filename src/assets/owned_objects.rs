@@ -1,12 +1,19 @@
 use anyhow::{anyhow, Result};
+use base64ct::{Base64, Encoding};
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
-use sui_graphql_client::Client;
-use sui_sdk_types::Address;
+use sui_graphql_client::{query_types::MoveValue, Client};
+use sui_sdk_types::{Address, ObjectData};
 
+use crate::types::{IntoStructType, StructType};
 use crate::utils;
 
+// server-side filter matching all the coins
+const COIN_TYPE: &str = "0x2::coin::Coin";
+
 pub struct OwnedObjects {
     pub sui_client: Arc<Client>,
     pub multisig_id: Address,
@@ -26,6 +33,58 @@ pub struct Object {
     pub type_: String,
     pub id: Address,
     pub fields: Map<String, Value>,
+    // bcs contents of the move object, empty if not returned by the server
+    pub bcs: Vec<u8>,
+    // rendered Display<T> fields, None until OwnedObjects::fetch_displays is called
+    pub display: Option<BTreeMap<String, String>>,
+}
+
+// 0x2::display::Display<T>
+#[derive(Deserialize)]
+struct DisplayObject {
+    _id: Address,
+    fields: Vec<(String, String)>,
+    version: u16,
+}
+
+impl Coin {
+    // T in 0x2::coin::Coin<T>
+    pub fn coin_type(&self) -> &str {
+        self.type_
+            .split_once('<')
+            .and_then(|(_, inner)| inner.strip_suffix('>'))
+            .unwrap_or(&self.type_)
+    }
+}
+
+impl Object {
+    // from the json fields, e.g. to a struct mirroring the move one
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(Value::Object(self.fields.clone()))
+            .map_err(|e| anyhow!("Failed to decode object {}: {}", self.id, e))
+    }
+
+    // from the bcs contents, T must have the exact layout of the move struct
+    pub fn decode_bcs<T: DeserializeOwned>(&self) -> Result<T> {
+        if self.bcs.is_empty() {
+            return Err(anyhow!("No bcs contents for object {}", self.id));
+        }
+        bcs::from_bytes(&self.bcs)
+            .map_err(|e| anyhow!("Failed to decode object {}: {}", self.id, e))
+    }
+
+    // rendered Display field, e.g. "name" or "image_url"
+    pub fn display(&self, key: &str) -> Option<&str> {
+        self.display.as_ref()?.get(key).map(String::as_str)
+    }
+
+    // replaces the {path.to.field} placeholders of the Display templates with the field values
+    pub fn render_display(&self, templates: &[(String, String)]) -> BTreeMap<String, String> {
+        templates
+            .iter()
+            .map(|(key, template)| (key.clone(), render_template(template, &self.fields)))
+            .collect()
+    }
 }
 
 impl OwnedObjects {
@@ -40,45 +99,105 @@ impl OwnedObjects {
         Ok(owned_objects)
     }
 
+    // coins only, for accounts holding too many objects to fetch them all
+    pub async fn coins_only(sui_client: Arc<Client>, multisig_id: Address) -> Result<Self> {
+        let mut owned_objects = Self {
+            sui_client,
+            multisig_id,
+            coins: Vec::new(),
+            objects: Vec::new(),
+        };
+        owned_objects.refresh_coins_only().await?;
+        Ok(owned_objects)
+    }
+
     pub async fn refresh(&mut self) -> Result<()> {
         let move_values = utils::get_objects_with_fields(&self.sui_client, self.multisig_id, None).await?;
+        self.coins.clear();
+        self.objects.clear();
 
         for move_value in move_values {
-            let fields = move_value
-                .json
-                .and_then(|json| json.as_object().cloned())
-                .ok_or(anyhow!("Could not parse object"))?;
-
-            let id = fields
-                .get("id")
-                .and_then(|id| id.as_str())
-                .ok_or(anyhow!("Could not get object id"))?
-                .parse::<Address>()?;
-
-            let type_ = move_value.type_.repr;
-
-            if type_.starts_with("0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin") {
-                let balance = fields
-                    .get("balance")
-                    .and_then(|bal| bal.get("value"))
-                    .and_then(|v| v.as_str())
-                    .ok_or(anyhow!("Could not get coin balance"))?
-                    .parse::<u64>()?;
-                self.coins.push(Coin { type_, id, balance });
-            } else {
-                self.objects.push(Object { type_, id, fields });
-            }
+            self.push(move_value)?;
+        }
+
+        Ok(())
+    }
+
+    // the objects are left as they are
+    pub async fn refresh_coins_only(&mut self) -> Result<()> {
+        let move_values =
+            utils::get_objects_with_fields(&self.sui_client, self.multisig_id, Some(COIN_TYPE))
+                .await?;
+        self.coins.clear();
+
+        for move_value in move_values {
+            self.push(move_value)?;
         }
 
         Ok(())
     }
-    
-        pub async fn switch_multisig(&mut self, multisig_id: Address) -> Result<()> {
-            self.multisig_id = multisig_id;
-            self.refresh().await?;
-            Ok(())
+
+    // replaces the coins and objects matching the type (see StructType::matches),
+    // the filter is applied by the server
+    pub async fn refresh_type(&mut self, type_: impl IntoStructType) -> Result<()> {
+        let filter = type_.into_struct_type()?;
+        let move_values = utils::get_objects_with_fields(
+            &self.sui_client,
+            self.multisig_id,
+            Some(&filter.to_string()),
+        )
+        .await?;
+        let matches = |type_: &str| {
+            type_
+                .parse::<StructType>()
+                .is_ok_and(|struct_type| struct_type.matches(&filter))
+        };
+        self.coins.retain(|coin| !matches(&coin.type_));
+        self.objects.retain(|object| !matches(&object.type_));
+
+        for move_value in move_values {
+            self.push(move_value)?;
         }
 
+        Ok(())
+    }
+
+    // renders the Display<T> of the object types that have one, one query per type
+    pub async fn fetch_displays(&mut self) -> Result<()> {
+        let mut templates: BTreeMap<String, Option<Vec<(String, String)>>> = BTreeMap::new();
+        for object in &self.objects {
+            if templates.contains_key(&object.type_) {
+                continue;
+            }
+            let display_type = format!("0x2::display::Display<{}>", object.type_);
+            let latest = utils::get_objects_by_type(&self.sui_client, &display_type)
+                .await?
+                .into_iter()
+                .filter_map(|display| match display.data() {
+                    ObjectData::Struct(obj) => {
+                        bcs::from_bytes::<DisplayObject>(obj.contents()).ok()
+                    }
+                    _ => None,
+                })
+                .max_by_key(|display| display.version);
+            templates.insert(object.type_.clone(), latest.map(|display| display.fields));
+        }
+
+        for object in &mut self.objects {
+            object.display = templates
+                .get(&object.type_)
+                .and_then(|templates| templates.as_ref())
+                .map(|templates| object.render_display(templates));
+        }
+        Ok(())
+    }
+
+    pub async fn switch_multisig(&mut self, multisig_id: Address) -> Result<()> {
+        self.multisig_id = multisig_id;
+        self.refresh().await?;
+        Ok(())
+    }
+
     pub fn get_type_by_id(&self, id: Address) -> Option<String> {
         for coin in &self.coins {
             if coin.id == id {
@@ -92,6 +211,123 @@ impl OwnedObjects {
         }
         None
     }
+
+    // ids of the coins and objects whose type matches the filter (see StructType::matches)
+    pub fn ids_matching(&self, filter: &StructType) -> Vec<Address> {
+        let coins = self.coins.iter().map(|coin| (&coin.type_, coin.id));
+        let objects = self.objects.iter().map(|object| (&object.type_, object.id));
+        coins
+            .chain(objects)
+            .filter(|(type_, _)| {
+                type_
+                    .parse::<StructType>()
+                    .is_ok_and(|struct_type| struct_type.matches(filter))
+            })
+            .map(|(_, id)| id)
+            .collect()
+    }
+
+    // === Helpers ===
+
+    fn push(&mut self, move_value: MoveValue) -> Result<()> {
+        let fields = move_value
+            .json
+            .and_then(|json| json.as_object().cloned())
+            .ok_or(anyhow!("Could not parse object"))?;
+
+        let id = fields
+            .get("id")
+            .and_then(|id| id.as_str())
+            .ok_or(anyhow!("Could not get object id"))?
+            .parse::<Address>()?;
+
+        let type_ = move_value.type_.repr;
+        let bcs = Base64::decode_vec(&move_value.bcs.0).unwrap_or_default();
+
+        if type_.starts_with(
+            "0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin",
+        ) {
+            let balance = fields
+                .get("balance")
+                .and_then(|bal| bal.get("value"))
+                .and_then(|v| v.as_str())
+                .ok_or(anyhow!("Could not get coin balance"))?
+                .parse::<u64>()?;
+            self.coins.push(Coin { type_, id, balance });
+        } else {
+            self.objects.push(Object {
+                type_,
+                id,
+                fields,
+                bcs,
+                display: None,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// (coin id, amount) to withdraw from the (coin id, balance), largest coins first,
+// only the last coin is partially withdrawn. all the coins if amount is None
+pub fn select_coins(coins: &[(Address, u64)], amount: Option<u64>) -> Result<Vec<(Address, u64)>> {
+    let mut coins = coins
+        .iter()
+        .filter(|(_, balance)| *balance > 0)
+        .copied()
+        .collect::<Vec<_>>();
+    coins.sort_by(|a, b| b.1.cmp(&a.1));
+    let Some(amount) = amount else {
+        return if coins.is_empty() {
+            Err(anyhow!("No coin to withdraw"))
+        } else {
+            Ok(coins)
+        };
+    };
+
+    let mut selected = Vec::new();
+    let mut remaining = amount;
+    for (id, balance) in coins {
+        if remaining == 0 {
+            break;
+        }
+        let withdrawn = balance.min(remaining);
+        selected.push((id, withdrawn));
+        remaining -= withdrawn;
+    }
+    if remaining > 0 || selected.is_empty() {
+        return Err(anyhow!(
+            "Not enough coins: {} missing out of {}",
+            remaining,
+            amount
+        ));
+    }
+    Ok(selected)
+}
+
+// values that aren't strings are rendered as json, missing ones as empty strings
+fn render_template(template: &str, fields: &Map<String, Value>) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let path = &rest[start + 1..start + end];
+        let mut value = fields.get(path.split('.').next().unwrap_or_default());
+        for key in path.split('.').skip(1) {
+            value = value.and_then(|value| value.get(key));
+        }
+        match value {
+            Some(Value::String(s)) => rendered.push_str(s),
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
 }
 
 impl fmt::Debug for OwnedObjects {
@@ -102,3 +338,47 @@ impl fmt::Debug for OwnedObjects {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn select_largest_coins_first() {
+        let (a, b, c) = (
+            Address::new([1; 32]),
+            Address::new([2; 32]),
+            Address::new([3; 32]),
+        );
+        let coins = [(a, 10), (b, 50), (c, 0)];
+        assert_eq!(select_coins(&coins, Some(30)).unwrap(), vec![(b, 30)]);
+        assert_eq!(
+            select_coins(&coins, Some(55)).unwrap(),
+            vec![(b, 50), (a, 5)]
+        );
+        assert_eq!(select_coins(&coins, None).unwrap(), vec![(b, 50), (a, 10)]);
+        assert!(select_coins(&coins, Some(61)).is_err());
+        assert!(select_coins(&[], None).is_err());
+    }
+
+    #[test]
+    fn render_display_templates() {
+        let fields = json!({
+            "id": "0x1",
+            "name": "Capy #1",
+            "attributes": { "level": 3 },
+        });
+        let fields = fields.as_object().unwrap();
+        assert_eq!(render_template("{name}", fields), "Capy #1");
+        assert_eq!(
+            render_template("https://capy.art/{id}?level={attributes.level}", fields),
+            "https://capy.art/0x1?level=3"
+        );
+        assert_eq!(render_template("{missing} Capy", fields), " Capy");
+        assert_eq!(
+            render_template("no placeholder {", fields),
+            "no placeholder {"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+// regenerates src/move_binding.rs and checks the bindings against live objects
+//
+// cargo run --features bindgen --bin bindgen -- generate <network> <alias>=<package id>...
+// cargo run --features bindgen --bin bindgen -- check <network|url> [multisig id]
+//
+// the bindings are expanded from the on-chain bytecode at compile time, so after a package
+// upgrade, generate with the new ids, rebuild and check that the live objects still decode
+
+use account_multisig_sdk::MultisigClient;
+use anyhow::{anyhow, Result};
+use std::fmt::Write;
+use sui_sdk_types::Address;
+
+const OUT: &str = "src/move_binding.rs";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("generate") => generate(&args[1..]),
+        Some("check") => check(&args[1..]).await,
+        _ => Err(anyhow!(
+            "Usage: bindgen generate <network> <alias>=<package id>... | check <network|url> [multisig id]"
+        )),
+    }
+}
+
+fn generate(args: &[String]) -> Result<()> {
+    let (network, packages) = args.split_first().ok_or(anyhow!("Network not specified"))?;
+    if packages.is_empty() {
+        return Err(anyhow!("No package, expected <alias>=<package id>"));
+    }
+
+    let mut out = String::from("use move_binding_derive::move_contract;\n");
+    for package in packages {
+        let (alias, id) = package.split_once('=').ok_or(anyhow!(
+            "Invalid package {}, expected <alias>=<package id>",
+            package
+        ))?;
+        let id = id
+            .parse::<Address>()
+            .map_err(|_| anyhow!("Invalid package id {}", id))?;
+        write!(
+            out,
+            "\nmove_contract! {{\n    alias = \"{}\", \n    package = \"{}\", \n    base_path = crate::move_binding,\n    network = \"{}\"\n}}\n",
+            alias, id, network
+        )?;
+    }
+
+    std::fs::write(OUT, out)?;
+    println!(
+        "{} written, update the package ids in src/lib.rs and rebuild",
+        OUT
+    );
+    Ok(())
+}
+
+// decodes the objects parsed with the bindings, a failure means the layouts drifted
+async fn check(args: &[String]) -> Result<()> {
+    let network = args.first().ok_or(anyhow!("Network not specified"))?;
+    let mut client = match network.as_str() {
+        "testnet" => MultisigClient::new_testnet(),
+        "mainnet" => MultisigClient::new_mainnet(),
+        url => MultisigClient::new_with_url(url)?,
+    };
+
+    let mut failures = 0;
+    report("Fees", client.fees().await.map(|_| ()), &mut failures);
+
+    if let Some(id) = args.get(1) {
+        let id = id
+            .parse::<Address>()
+            .map_err(|_| anyhow!("Invalid multisig id {}", id))?;
+        report(
+            "Account, intents and dynamic fields",
+            client.load_multisig(id).await,
+            &mut failures,
+        );
+
+        let keys = client
+            .intents()
+            .map(|intents| intents.intents.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for key in keys {
+            let result = client.intent_actions(&key).await.map(|_| ());
+            report(&format!("Actions of {}", key), result, &mut failures);
+        }
+    }
+
+    if failures > 0 {
+        return Err(anyhow!(
+            "{} check(s) failed, regenerate the bindings",
+            failures
+        ));
+    }
+    Ok(())
+}
+
+fn report(name: &str, result: Result<()>, failures: &mut usize) {
+    match result {
+        Err(e) => {
+            *failures += 1;
+            println!("FAIL {}: {}", name, e);
+        }
+        _ => println!("ok   {}", name),
+    }
+}
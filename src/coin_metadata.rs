@@ -0,0 +1,146 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use sui_graphql_client::Client;
+use sui_sdk_types::{Address, TypeTag};
+
+#[derive(Debug, Clone)]
+pub struct CoinMetadata {
+    pub id: Address,
+    pub decimals: u8,
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+}
+
+// coin metadata by coin type, fetched once per type
+#[derive(Debug, Default)]
+pub struct CoinMetadataCache {
+    inner: RwLock<HashMap<String, CoinMetadata>>,
+}
+
+impl CoinMetadataCache {
+    pub fn get(&self, coin_type: &str) -> Option<CoinMetadata> {
+        self.inner
+            .read()
+            .ok()?
+            .get(&normalize_coin_type(coin_type))
+            .cloned()
+    }
+
+    pub async fn get_or_fetch(&self, sui_client: &Client, coin_type: &str) -> Result<CoinMetadata> {
+        if let Some(metadata) = self.get(coin_type) {
+            return Ok(metadata);
+        }
+
+        let coin_type = normalize_coin_type(coin_type);
+        let metadata = sui_client
+            .coin_metadata(&coin_type)
+            .await?
+            .ok_or(anyhow!("Coin metadata not found for {}", coin_type))?;
+        let metadata = CoinMetadata {
+            id: metadata.address,
+            decimals: metadata.decimals.unwrap_or_default() as u8,
+            name: metadata.name.unwrap_or_default(),
+            symbol: metadata.symbol.unwrap_or_default(),
+            description: metadata.description.unwrap_or_default(),
+            icon_url: metadata.icon_url,
+        };
+
+        self.inner
+            .write()
+            .map_err(|_| anyhow!("Coin metadata cache poisoned"))?
+            .insert(coin_type, metadata.clone());
+        Ok(metadata)
+    }
+
+    // "1.5 SUI", falls back to the raw amount if the metadata can't be fetched
+    pub async fn format_amount(&self, sui_client: &Client, coin_type: &str, amount: u64) -> String {
+        match self.get_or_fetch(sui_client, coin_type).await {
+            Ok(metadata) => format!(
+                "{} {}",
+                format_amount(amount, metadata.decimals),
+                metadata.symbol
+            ),
+            Err(_) => amount.to_string(),
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.clear();
+        }
+    }
+}
+
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 || decimals > 38 {
+        return amount.to_string();
+    }
+    let divisor = 10u128.pow(decimals as u32);
+    let integer = amount as u128 / divisor;
+    let fraction = amount as u128 % divisor;
+    if fraction == 0 {
+        return integer.to_string();
+    }
+    let fraction = format!("{:0>width$}", fraction, width = decimals as usize);
+    format!("{}.{}", integer, fraction.trim_end_matches('0'))
+}
+
+// "1.5" with 9 decimals -> 1_500_000_000
+pub fn parse_amount(amount: &str, decimals: u8) -> Result<u64> {
+    let amount = amount.trim();
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if fraction.len() > decimals as usize {
+        return Err(anyhow!(
+            "Too many decimals in {} (max {})",
+            amount,
+            decimals
+        ));
+    }
+    let digits = format!(
+        "{}{:0<width$}",
+        integer,
+        fraction,
+        width = decimals as usize
+    );
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(anyhow!("Invalid amount: {}", amount));
+    }
+    digits
+        .parse::<u64>()
+        .map_err(|_| anyhow!("Amount out of range: {}", amount))
+}
+
+// same key for "0x2::sui::SUI" and the long address form
+fn normalize_coin_type(coin_type: &str) -> String {
+    coin_type
+        .parse::<TypeTag>()
+        .map(|type_tag| type_tag.to_string())
+        .unwrap_or_else(|_| coin_type.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_amounts() {
+        assert_eq!(format_amount(1_500_000_000, 9), "1.5");
+        assert_eq!(format_amount(2_000_000_000, 9), "2");
+        assert_eq!(format_amount(1, 9), "0.000000001");
+        assert_eq!(format_amount(42, 0), "42");
+    }
+
+    #[test]
+    fn parse_amounts() {
+        assert_eq!(parse_amount("1.5", 9).unwrap(), 1_500_000_000);
+        assert_eq!(parse_amount("2", 9).unwrap(), 2_000_000_000);
+        assert_eq!(parse_amount(".5", 1).unwrap(), 5);
+        assert_eq!(parse_amount("42", 0).unwrap(), 42);
+        assert!(parse_amount("0.0000000001", 9).is_err());
+        assert!(parse_amount("1,5", 9).is_err());
+        assert!(parse_amount("-1", 9).is_err());
+    }
+}
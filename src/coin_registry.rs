@@ -0,0 +1,176 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+// well-known coin types by ticker, so that "USDC" can be used instead of the full type.
+// extra entries can be loaded from a toml file of TICKER = "<coin type>" lines
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    // custom rpc url, only SUI is known
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoinRegistry {
+    pub network: Network,
+    // uppercase tickers
+    coins: BTreeMap<String, String>,
+}
+
+const SUI: &str = "0x2::sui::SUI";
+
+const MAINNET_COINS: &[(&str, &str)] = &[
+    ("SUI", SUI),
+    (
+        "USDC",
+        "0xdba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7::usdc::USDC",
+    ),
+    // wormhole
+    (
+        "USDT",
+        "0xc060006111016b8a020ad5b33834984a437aaa7d3c74c18e09a95d48aceab08c::coin::COIN",
+    ),
+    (
+        "WETH",
+        "0xaf8cd5edc19c4512f4259f0bee101a40d41ebed738ade5874359610ef8eeced5::coin::COIN",
+    ),
+    (
+        "DEEP",
+        "0xdeeb7a4662eec9f2f3def03fb937a663dddaa2e215b8078a284d026b7946c270::deep::DEEP",
+    ),
+    (
+        "CETUS",
+        "0x06864a6f921804860930db6ddbe2e16acdf8504495ea7481637a1c8b9a8fe54b::cetus::CETUS",
+    ),
+];
+
+const TESTNET_COINS: &[(&str, &str)] = &[
+    ("SUI", SUI),
+    (
+        "USDC",
+        "0xa1ec7fc00a6f40db9693ad1415d0c193ad3906494428cf252621037bd7117e29::usdc::USDC",
+    ),
+];
+
+impl CoinRegistry {
+    pub fn new(network: Network) -> Self {
+        let known = match network {
+            Network::Mainnet => MAINNET_COINS,
+            Network::Testnet => TESTNET_COINS,
+            Network::Other => &[("SUI", SUI)],
+        };
+        Self {
+            network,
+            coins: known
+                .iter()
+                .map(|(ticker, coin_type)| (ticker.to_string(), coin_type.to_string()))
+                .collect(),
+        }
+    }
+
+    // overrides the known entries
+    pub fn insert(&mut self, ticker: &str, coin_type: &str) {
+        self.coins
+            .insert(ticker.to_uppercase(), coin_type.to_string());
+    }
+
+    pub fn extend_from_toml(&mut self, s: &str) -> Result<()> {
+        let coins: BTreeMap<String, String> = toml::from_str(s)?;
+        for (ticker, coin_type) in coins {
+            self.insert(&ticker, &coin_type);
+        }
+        Ok(())
+    }
+
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.extend_from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn get(&self, ticker: &str) -> Option<&str> {
+        self.coins.get(&ticker.to_uppercase()).map(String::as_str)
+    }
+
+    // full coin types are returned as they are, tickers are case insensitive
+    pub fn resolve(&self, coin: &str) -> Result<String> {
+        if coin.contains("::") {
+            return Ok(coin.to_string());
+        }
+        self.get(coin).map(str::to_string).ok_or(anyhow!(
+            "Unknown coin {} on {:?}, use the full coin type or one of: {}",
+            coin,
+            self.network,
+            self.tickers().join(", ")
+        ))
+    }
+
+    pub fn tickers(&self) -> Vec<&str> {
+        self.coins.keys().map(String::as_str).collect()
+    }
+}
+
+impl Network {
+    // identifier returned by the endpoints (digest of the genesis checkpoint)
+    pub fn chain_id(&self) -> Option<&'static str> {
+        match self {
+            Network::Mainnet => Some("35834a8a"),
+            Network::Testnet => Some("4c78adac"),
+            Network::Other => None,
+        }
+    }
+
+    pub fn from_chain_id(chain_id: &str) -> Self {
+        [Network::Mainnet, Network::Testnet]
+            .into_iter()
+            .find(|network| network.chain_id() == Some(chain_id))
+            .unwrap_or(Network::Other)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Testnet => write!(f, "testnet"),
+            Network::Other => write!(f, "custom network"),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            _ => Ok(Network::Other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_tickers() {
+        let mut registry = CoinRegistry::new(Network::Mainnet);
+        assert_eq!(registry.resolve("sui").unwrap(), SUI);
+        assert!(registry.resolve("USDC").unwrap().ends_with("::usdc::USDC"));
+        assert_eq!(registry.resolve("0x2::sui::SUI").unwrap(), SUI);
+        assert!(registry.resolve("FOO").is_err());
+
+        registry
+            .extend_from_toml(r#"foo = "0x123::foo::FOO""#)
+            .unwrap();
+        assert_eq!(registry.resolve("FOO").unwrap(), "0x123::foo::FOO");
+        assert!(CoinRegistry::new(Network::Testnet).resolve("USDT").is_err());
+        assert_eq!(Network::from_chain_id("4c78adac"), Network::Testnet);
+        assert_eq!(Network::from_chain_id("abcdef12"), Network::Other);
+    }
+}
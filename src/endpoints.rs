@@ -0,0 +1,134 @@
+use anyhow::{anyhow, Result};
+use std::time::{Duration, Instant};
+use sui_graphql_client::error::{Error as GraphQlError, Kind};
+use sui_graphql_client::Client;
+
+// ordered list of GraphQL endpoints, the next one is used after max_failures
+// consecutive transport failures of the current one (wraps around to the first)
+
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    urls: Vec<String>,
+    current: usize,
+    failures: usize,
+    pub max_failures: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthStatus {
+    pub url: String,
+    pub chain_id: String,
+    pub latest_checkpoint: u64,
+    pub latency: Duration,
+}
+
+impl Endpoints {
+    pub fn new(urls: &[&str]) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(anyhow!("No endpoint provided"));
+        }
+        Ok(Self {
+            urls: urls.iter().map(|url| url.to_string()).collect(),
+            current: 0,
+            failures: 0,
+            max_failures: 3,
+        })
+    }
+
+    pub fn current(&self) -> &str {
+        &self.urls[self.current]
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    pub fn client(&self) -> Result<Client> {
+        Ok(Client::new(self.current())?)
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+    }
+
+    // true if it switched to the next endpoint
+    pub fn record_failure(&mut self) -> bool {
+        self.failures += 1;
+        if self.failures < self.max_failures || self.urls.len() < 2 {
+            return false;
+        }
+        self.next();
+        true
+    }
+
+    // only transport errors count as failures, true if it switched to the next endpoint
+    pub fn record<T>(&mut self, result: &Result<T>) -> bool {
+        match result {
+            Ok(_) => {
+                self.record_success();
+                false
+            }
+            Err(e) if is_transport_error(e) => self.record_failure(),
+            Err(_) => false,
+        }
+    }
+
+    pub fn next(&mut self) -> &str {
+        self.current = (self.current + 1) % self.urls.len();
+        self.failures = 0;
+        self.current()
+    }
+}
+
+// the endpoint couldn't be reached or didn't answer (connection, timeout, http status),
+// the other errors (query, decoding, execution) don't depend on the endpoint
+pub fn is_transport_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<GraphQlError>()
+            .is_some_and(|e| matches!(e.kind(), Kind::Other))
+    })
+}
+
+pub async fn health_check(sui_client: &Client, url: &str) -> Result<HealthStatus> {
+    let start = Instant::now();
+    let chain_id = sui_client.chain_id().await?;
+    let latest_checkpoint = sui_client
+        .latest_checkpoint_sequence_number()
+        .await?
+        .unwrap_or_default();
+    Ok(HealthStatus {
+        url: url.to_string(),
+        chain_id,
+        latest_checkpoint,
+        latency: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failover_after_max_failures() {
+        let mut endpoints = Endpoints::new(&["https://a", "https://b"]).unwrap();
+        endpoints.max_failures = 2;
+        assert!(!endpoints.record_failure());
+        endpoints.record_success();
+        assert!(!endpoints.record_failure());
+        assert!(endpoints.record_failure());
+        assert_eq!(endpoints.current(), "https://b");
+        endpoints.next();
+        assert_eq!(endpoints.current(), "https://a");
+
+        // errors unrelated to the endpoint don't count
+        endpoints.max_failures = 1;
+        assert!(!endpoints.record::<()>(&Err(anyhow!("Intent not found"))));
+        assert_eq!(endpoints.current(), "https://a");
+
+        let mut single = Endpoints::new(&["https://a"]).unwrap();
+        single.max_failures = 1;
+        assert!(!single.record_failure());
+        assert!(Endpoints::new(&[]).is_err());
+    }
+}
@@ -0,0 +1,569 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use sui_graphql_client::Client;
+use sui_sdk_types::{Address, ExecutionError, Owner};
+
+use crate::roles::Package;
+
+// errors that can be matched by callers (returned through anyhow, use downcast_ref)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultisigError {
+    AlreadyApproved {
+        key: String,
+        address: Address,
+    },
+    NotApproved {
+        key: String,
+        address: Address,
+    },
+    // the approval request doesn't describe the intent on chain, see proposals::coordinator
+    ApprovalRequestMismatch {
+        key: String,
+        reason: String,
+    },
+    // the signed preapproval can't be counted, see coordinator::verify_preapproval
+    InvalidPreapproval {
+        key: String,
+        signer: Address,
+        reason: String,
+    },
+    CurrencyNotFound {
+        coin_type: String,
+    },
+    MintDisabled {
+        coin_type: String,
+    },
+    MaxSupplyExceeded {
+        coin_type: String,
+        max_supply: u64,
+        current_supply: u64,
+        amount: u64,
+    },
+    VaultNotFound {
+        vault_name: String,
+    },
+    InsufficientVaultBalance {
+        vault_name: String,
+        coin_type: String,
+        balance: u64,
+        amount: u64,
+    },
+    // (coin type, amount) left in the vault
+    VaultNotEmpty {
+        vault_name: String,
+        balances: Vec<(String, u64)>,
+    },
+    // object_ids and recipients of a WithdrawAndTransfer must have the same length
+    RecipientsMismatch {
+        objects: usize,
+        recipients: usize,
+    },
+    InvalidWithdrawals {
+        invalid: Vec<(Address, WithdrawalIssue)>,
+    },
+    // the actions bag doesn't have the shape of the intent type
+    MalformedActions {
+        intent_type: String,
+        reason: String,
+    },
+    // not returned by the endpoint, see utils::FetchedObjects::require_all
+    MissingObjects {
+        ids: Vec<Address>,
+    },
+    // received by the multisig but owned by someone else
+    NotOwnedByAccount {
+        id: Address,
+        owner: Owner,
+    },
+    // see policy::Policy
+    IntentBlocked {
+        intent_type: String,
+    },
+    RecipientNotAllowed {
+        recipient: Address,
+    },
+    SpendingLimitExceeded {
+        coin_type: String,
+        max_amount: u64,
+        spent: u64,
+        amount: u64,
+    },
+    // SuiNS name without a target address, see suins::NameCache
+    UnresolvedName {
+        name: String,
+    },
+    // the endpoint isn't on the network of the configured packages
+    WrongNetwork {
+        expected: String,
+        actual: String,
+    },
+    // config_type is set for account.tech accounts with another config (e.g. a DAO)
+    NotAMultisigAccount {
+        id: Address,
+        actual_type: String,
+        config_type: Option<String>,
+    },
+    // the bytes don't match the bindings, see utils::decode
+    SchemaMismatch {
+        type_: String,
+        // rust binding
+        expected: String,
+        reason: String,
+    },
+    // see decode_abort
+    MoveAbort {
+        package: Address,
+        module: String,
+        function: Option<String>,
+        code: u64,
+        // error constant name (e.g. ENotApproved) and source line, when they can be decoded
+        name: Option<String>,
+        line: Option<u16>,
+    },
+}
+
+// why an object cannot be withdrawn from the multisig
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawalIssue {
+    NotOwned,
+    // already withdrawn by another intent
+    Locked,
+    Duplicated,
+}
+
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultisigError::AlreadyApproved { key, address } => {
+                write!(f, "Intent {} already approved by {}", key, address)
+            }
+            MultisigError::NotApproved { key, address } => {
+                write!(f, "Intent {} not approved by {}", key, address)
+            }
+            MultisigError::ApprovalRequestMismatch { key, reason } => {
+                write!(f, "Approval request for {} doesn't match: {}", key, reason)
+            }
+            MultisigError::InvalidPreapproval {
+                key,
+                signer,
+                reason,
+            } => write!(
+                f,
+                "Invalid preapproval of {} by {}: {}",
+                key, signer, reason
+            ),
+            MultisigError::CurrencyNotFound { coin_type } => {
+                write!(f, "No TreasuryCap for {} in the multisig", coin_type)
+            }
+            MultisigError::MintDisabled { coin_type } => {
+                write!(f, "Minting {} is disabled", coin_type)
+            }
+            MultisigError::MaxSupplyExceeded {
+                coin_type,
+                max_supply,
+                current_supply,
+                amount,
+            } => write!(
+                f,
+                "Minting {} {} would exceed the max supply ({} / {})",
+                amount, coin_type, current_supply, max_supply
+            ),
+            MultisigError::VaultNotFound { vault_name } => {
+                write!(f, "Vault {} not found", vault_name)
+            }
+            MultisigError::InsufficientVaultBalance {
+                vault_name,
+                coin_type,
+                balance,
+                amount,
+            } => write!(
+                f,
+                "Vault {} holds {} {}, {} requested",
+                vault_name, balance, coin_type, amount
+            ),
+            MultisigError::VaultNotEmpty {
+                vault_name,
+                balances,
+            } => {
+                write!(f, "Vault {} is not empty:", vault_name)?;
+                for (coin_type, amount) in balances {
+                    write!(f, "\n  {} {}", amount, coin_type)?;
+                }
+                Ok(())
+            }
+            MultisigError::RecipientsMismatch {
+                objects,
+                recipients,
+            } => write!(
+                f,
+                "{} objects to withdraw but {} recipients",
+                objects, recipients
+            ),
+            MultisigError::InvalidWithdrawals { invalid } => {
+                write!(f, "Cannot withdraw:")?;
+                for (id, issue) in invalid {
+                    write!(f, "\n  {} ({})", id, issue)?;
+                }
+                Ok(())
+            }
+            MultisigError::MalformedActions {
+                intent_type,
+                reason,
+            } => write!(f, "Malformed {} actions: {}", intent_type, reason),
+            MultisigError::MissingObjects { ids } => {
+                write!(f, "Objects not found:")?;
+                for id in ids {
+                    write!(f, "\n  {}", id)?;
+                }
+                Ok(())
+            }
+            MultisigError::NotOwnedByAccount { id, owner } => {
+                write!(
+                    f,
+                    "Object {} is not owned by the multisig ({:?})",
+                    id, owner
+                )
+            }
+            MultisigError::IntentBlocked { intent_type } => {
+                write!(f, "{} intents are blocked by the policy", intent_type)
+            }
+            MultisigError::RecipientNotAllowed { recipient } => {
+                write!(f, "Recipient {} is not allowed by the policy", recipient)
+            }
+            MultisigError::SpendingLimitExceeded {
+                coin_type,
+                max_amount,
+                spent,
+                amount,
+            } => write!(
+                f,
+                "Spending {} {} would exceed the policy limit ({} / {})",
+                amount, coin_type, spent, max_amount
+            ),
+            MultisigError::UnresolvedName { name } => {
+                write!(f, "SuiNS name {} doesn't resolve to an address", name)
+            }
+            MultisigError::WrongNetwork { expected, actual } => write!(
+                f,
+                "{} packages configured but endpoint is {}",
+                expected, actual
+            ),
+            MultisigError::NotAMultisigAccount {
+                id,
+                actual_type,
+                config_type,
+            } => match config_type {
+                Some(config_type) => write!(
+                    f,
+                    "Object {} is an account with a {} config, not a multisig",
+                    id, config_type
+                ),
+                None => write!(
+                    f,
+                    "Object {} is a {}, not a multisig account",
+                    id, actual_type
+                ),
+            },
+            MultisigError::SchemaMismatch { type_, reason, .. } => write!(
+                f,
+                "Failed to decode {} ({}), the package may have been upgraded",
+                type_, reason
+            ),
+            MultisigError::MoveAbort {
+                module,
+                function,
+                code,
+                name,
+                line,
+                ..
+            } => {
+                write!(f, "Aborted in {}", module)?;
+                if let Some(function) = function {
+                    write!(f, "::{}", function)?;
+                }
+                match name {
+                    Some(name) => write!(f, " with {}", name)?,
+                    None => write!(f, " with code {}", code)?,
+                }
+                if let Some(line) = line {
+                    write!(f, " (line {})", line)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MultisigError {}
+
+impl fmt::Display for WithdrawalIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WithdrawalIssue::NotOwned => write!(f, "not owned by the multisig"),
+            WithdrawalIssue::Locked => write!(f, "locked by another intent"),
+            WithdrawalIssue::Duplicated => write!(f, "listed more than once"),
+        }
+    }
+}
+
+// === Abort decoding ===
+
+// maps a move abort to its error constant, None if the error isn't an abort
+// constant names only exist in the bytecode for clever errors (#[error] constants),
+// plain u64 codes are named from ABORT_CODES for the account.tech packages
+pub async fn decode_abort(sui_client: &Client, error: &ExecutionError) -> Option<MultisigError> {
+    let ExecutionError::MoveAbort { location, code } = error else {
+        return None;
+    };
+    let package = Address::from(location.package);
+    let module = location.module.as_str().to_string();
+
+    let (line, name) = match decode_clever_code(*code) {
+        Some((line, identifier_index)) => {
+            let name = match identifier_index {
+                Some(index) => module_identifiers(sui_client, package, &module)
+                    .await
+                    .ok()
+                    .and_then(|identifiers| identifiers.get(index as usize).cloned()),
+                None => None,
+            };
+            (Some(line), name)
+        }
+        None => (
+            None,
+            known_abort(package, &module, *code).map(str::to_string),
+        ),
+    };
+
+    Some(MultisigError::MoveAbort {
+        package,
+        module,
+        function: location
+            .function_name
+            .as_ref()
+            .map(|name| name.as_str().to_string()),
+        code: *code,
+        name,
+        line,
+    })
+}
+
+// error constants of the account.tech packages by package and module, indexed by code
+// mirrors the sources of the package versions in lib.rs, to update with them
+static ABORT_CODES: &[(Package, &str, &[&str])] = &[
+    (
+        Package::Protocol,
+        "account",
+        &[
+            "ECantBeRemovedYet",
+            "EHasntExpired",
+            "ECantBeExecutedYet",
+            "EWrongAccount",
+            "ENotCalledFromConfigModule",
+            "EWrongCaller",
+        ],
+    ),
+    (
+        Package::Protocol,
+        "intents",
+        &[
+            "EIntentNotFound",
+            "EObjectAlreadyLocked",
+            "EObjectNotLocked",
+            "ENoExecutionTime",
+            "EExecutionTimesNotAscending",
+            "EActionsNotEmpty",
+            "EKeyAlreadyExists",
+            "EWrongAccount",
+            "EWrongWitness",
+            "ESingleExecution",
+        ],
+    ),
+    (
+        Package::Protocol,
+        "deps",
+        &[
+            "EDepNotFound",
+            "EDepAlreadyExists",
+            "ENotDep",
+            "EDepsNotSameLength",
+            "EAccountProtocolMissing",
+            "EAccountConfigMissing",
+            "ENotExtension",
+        ],
+    ),
+    (
+        Package::Protocol,
+        "user",
+        &[
+            "ENotEmpty",
+            "EAlreadyHasUser",
+            "EAccountNotFound",
+            "EAccountTypeDoesntExist",
+            "EWrongUserId",
+            "EWrongNumberOfAccounts",
+            "EWrongAccount",
+            "EMustLeaveAllAccounts",
+        ],
+    ),
+    (
+        Package::Multisig,
+        "multisig",
+        &[
+            "EMemberNotFound",
+            "ECallerIsNotMember",
+            "ERoleNotFound",
+            "EThresholdNotReached",
+            "ENotApproved",
+            "EAlreadyApproved",
+            "EMembersNotSameLength",
+            "ERolesNotSameLength",
+            "EThresholdTooHigh",
+            "EThresholdNull",
+            "ERoleNotAdded",
+            "EThresholdTooLow",
+        ],
+    ),
+];
+
+// None for other packages or unknown codes
+pub fn known_abort(package: Address, module: &str, code: u64) -> Option<&'static str> {
+    let package = Package::from_address(package.to_string().trim_start_matches("0x"))?;
+    ABORT_CODES
+        .iter()
+        .find(|(p, m, _)| *p == package && *m == module)
+        .and_then(|(_, _, names)| names.get(usize::try_from(code).ok()?))
+        .copied()
+}
+
+// clever error layout: tag (1 bit) | reserved (15) | line (16) | identifier index (16) | constant index (16)
+// returns the line and the index of the constant name, 0xffff meaning no constant
+pub fn decode_clever_code(code: u64) -> Option<(u16, Option<u16>)> {
+    if code >> 63 == 0 {
+        return None;
+    }
+    let line = (code >> 32) as u16;
+    let identifier_index = (code >> 16) as u16;
+    Some((
+        line,
+        (identifier_index != u16::MAX).then_some(identifier_index),
+    ))
+}
+
+async fn module_identifiers(
+    sui_client: &Client,
+    package: Address,
+    module: &str,
+) -> Result<Vec<String>> {
+    let package = sui_client
+        .package(package, None)
+        .await?
+        .ok_or(anyhow!("Package {} not found", package))?;
+    let bytes = package
+        .modules
+        .iter()
+        .find(|(name, _)| name.as_str() == module)
+        .map(|(_, bytes)| bytes)
+        .ok_or(anyhow!("Module {} not found", module))?;
+    parse_identifiers(bytes)
+}
+
+// reads the identifiers table of a compiled module
+pub fn parse_identifiers(bytes: &[u8]) -> Result<Vec<String>> {
+    const MAGIC: [u8; 4] = [0xA1, 0x1C, 0xEB, 0x0B];
+    const IDENTIFIERS: u8 = 0x7;
+
+    if bytes.len() < 8 || bytes[..4] != MAGIC {
+        return Err(anyhow!("Invalid module bytecode"));
+    }
+    let mut cursor = 8; // magic + version
+    let table_count = read_uleb(bytes, &mut cursor)?;
+    let mut tables = Vec::new();
+    for _ in 0..table_count {
+        let kind = *bytes
+            .get(cursor)
+            .ok_or(anyhow!("Truncated module bytecode"))?;
+        cursor += 1;
+        let offset = read_uleb(bytes, &mut cursor)? as usize;
+        let length = read_uleb(bytes, &mut cursor)? as usize;
+        tables.push((kind, offset, length));
+    }
+
+    let (_, offset, length) = tables
+        .into_iter()
+        .find(|(kind, _, _)| *kind == IDENTIFIERS)
+        .ok_or(anyhow!("No identifiers in module bytecode"))?;
+    let start = cursor + offset;
+    let table = bytes
+        .get(start..start + length)
+        .ok_or(anyhow!("Truncated module bytecode"))?;
+
+    let mut identifiers = Vec::new();
+    let mut cursor = 0;
+    while cursor < table.len() {
+        let len = read_uleb(table, &mut cursor)? as usize;
+        let identifier = table
+            .get(cursor..cursor + len)
+            .ok_or(anyhow!("Truncated module bytecode"))?;
+        identifiers.push(String::from_utf8(identifier.to_vec())?);
+        cursor += len;
+    }
+    Ok(identifiers)
+}
+
+fn read_uleb(bytes: &[u8], cursor: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*cursor)
+            .ok_or(anyhow!("Truncated module bytecode"))?;
+        *cursor += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("Invalid uleb128 in module bytecode"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn decode_clever_codes() {
+        assert_eq!(decode_clever_code(4), None);
+        let code = (1 << 63) | (42 << 32) | (3 << 16) | 1;
+        assert_eq!(decode_clever_code(code), Some((42, Some(3))));
+        let code = (1 << 63) | (42 << 32) | (0xffff << 16) | 0xffff;
+        assert_eq!(decode_clever_code(code), Some((42, None)));
+    }
+
+    #[test]
+    fn name_plain_codes() {
+        let multisig = Address::from_str(crate::ACCOUNT_MULTISIG_PACKAGE).unwrap();
+        assert_eq!(
+            known_abort(multisig, "multisig", 3),
+            Some("EThresholdNotReached")
+        );
+        assert_eq!(known_abort(multisig, "multisig", 99), None);
+        assert_eq!(known_abort(multisig, "unknown", 3), None);
+        assert_eq!(known_abort(Address::ZERO, "coin", 3), None);
+    }
+
+    #[test]
+    fn parse_module_identifiers() {
+        // magic, version, 1 table: identifiers at offset 0, 15 bytes
+        let mut bytes = vec![0xA1, 0x1C, 0xEB, 0x0B, 7, 0, 0, 0, 1, 0x7, 0, 15];
+        bytes.extend([8]);
+        bytes.extend(b"multisig");
+        bytes.extend([5]);
+        bytes.extend(b"EOops");
+        assert_eq!(
+            parse_identifiers(&bytes).unwrap(),
+            vec!["multisig".to_string(), "EOops".to_string()]
+        );
+        assert!(parse_identifiers(&bytes[..14]).is_err());
+    }
+}
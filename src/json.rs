@@ -0,0 +1,158 @@
+use base64ct::{Base64, Encoding};
+use serde_json::{json, Value};
+use sui_sdk_types::{hash::Hasher, Address, TransactionDigest};
+
+use crate::assets::{dynamic_fields::DynamicFields, owned_objects::OwnedObjects};
+use crate::multisig::Multisig;
+use crate::proposals::intents::{Intent, Intents};
+
+// JSON views of the fetched data, shared by the HTTP API and the python bindings
+
+pub fn multisig(multisig: &Multisig) -> Value {
+    json!({
+        "id": multisig.id.to_string(),
+        "metadata": multisig.metadata,
+        "profile": multisig.profile(),
+        "deps": multisig.deps.iter().map(|dep| json!({
+            "name": dep.name,
+            "addr": dep.addr.to_string(),
+            "version": dep.version,
+        })).collect::<Vec<_>>(),
+        "unverified_deps_allowed": multisig.unverified_deps_allowed,
+        "locked_objects": multisig.locked_objects.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+        "global": {
+            "threshold": multisig.config.global.threshold,
+            "total_weight": multisig.config.global.total_weight,
+        },
+        "roles": multisig.roles_overview().iter().map(|role| json!({
+            "name": role.name,
+            "threshold": role.threshold,
+            "total_weight": role.total_weight,
+        })).collect::<Vec<_>>(),
+        "members": multisig.config.members.iter().map(|member| json!({
+            "address": member.address,
+            "weight": member.weight,
+            "roles": member.roles,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+// sorted by creation time
+pub fn intents(intents: &Intents) -> Value {
+    let mut intents = intents.intents.values().collect::<Vec<_>>();
+    intents.sort_by_key(|intent| intent.creation_time);
+    Value::Array(intents.into_iter().map(intent).collect())
+}
+
+// actions are included once fetched with Intent::get_actions_args, as the bcs of each action
+// (base64) with its type arguments, which unlike the debug view is stable across versions
+pub fn intent(intent: &Intent) -> Value {
+    json!({
+        "key": intent.key,
+        "type": intent.type_,
+        "description": intent.description,
+        "account": intent.account.to_string(),
+        "creator": intent.creator.to_string(),
+        "creation_time": intent.creation_time,
+        "execution_times": intent.execution_times,
+        "expiration_time": intent.expiration_time,
+        "role": intent.role,
+        "outcome": {
+            "total_weight": intent.outcome.total_weight,
+            "role_weight": intent.outcome.role_weight,
+            "approved": intent.outcome.approved.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+        },
+        "actions": intent.actions_args.as_ref().map(|_| actions_bcs(intent)),
+    })
+}
+
+fn actions_bcs(intent: &Intent) -> Value {
+    intent
+        .actions_types_bcs
+        .iter()
+        .map(|(types, bcs)| {
+            json!({
+                "types": types.iter().map(|type_| type_.to_string()).collect::<Vec<_>>(),
+                "bcs": Base64::encode_string(bcs),
+            })
+        })
+        .collect()
+}
+
+// vault name -> coin type -> amount
+pub fn vaults(dynamic_fields: &DynamicFields) -> Value {
+    Value::Object(
+        dynamic_fields
+            .vaults
+            .iter()
+            .map(|(name, vault)| (name.clone(), json!(vault.coins)))
+            .collect(),
+    )
+}
+
+pub fn owned(owned_objects: &OwnedObjects) -> Value {
+    json!({
+        "coins": owned_objects.coins.iter().map(|coin| json!({
+            "id": coin.id.to_string(),
+            "type": coin.type_,
+            "balance": coin.balance,
+        })).collect::<Vec<_>>(),
+        "objects": owned_objects.objects.iter().map(|object| json!({
+            "id": object.id.to_string(),
+            "type": object.type_,
+            "fields": object.fields,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+// audit record of an intent with its actions loaded, content_digest is the blake2b256 digest
+// of the serialized content (keys sorted, actions as bcs) so that the archived record can be
+// checked or signed
+pub fn approval_record(
+    multisig: &Multisig,
+    intent: &Intent,
+    transactions: &[(TransactionDigest, Address)],
+) -> Value {
+    let approvers = intent
+        .outcome
+        .approved
+        .iter()
+        .map(|address| {
+            let member = multisig
+                .config
+                .members
+                .iter()
+                .find(|member| member.address == address.to_string());
+            json!({
+                "address": address.to_string(),
+                "weight": member.map(|member| member.weight).unwrap_or_default(),
+                "roles": member.map(|member| member.roles.clone()).unwrap_or_default(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let role_threshold = multisig
+        .roles_overview()
+        .into_iter()
+        .find(|role| role.name == intent.role)
+        .map(|role| role.threshold);
+
+    let content = json!({
+        "multisig": multisig.id.to_string(),
+        "intent": self::intent(intent),
+        "thresholds": {
+            "global": multisig.config.global.threshold,
+            "role": role_threshold,
+        },
+        "approvers": approvers,
+        "transactions": transactions.iter().map(|(digest, sender)| json!({
+            "digest": digest.to_string(),
+            "sender": sender.to_string(),
+        })).collect::<Vec<_>>(),
+    });
+    let digest = Hasher::digest(serde_json::to_vec(&content).unwrap_or_default());
+
+    json!({
+        "content": content,
+        "content_digest": digest.to_string(),
+    })
+}
@@ -1,33 +1,81 @@
+pub mod approver;
 pub mod assets;
+pub mod coin_metadata;
+pub mod coin_registry;
+pub mod endpoints;
+pub mod errors;
+pub mod json;
+pub mod lint;
 pub mod move_binding;
 pub mod multisig;
 pub mod multisig_builder;
+pub mod native_multisig;
+pub mod notify;
+pub mod policy;
+pub mod prelude;
+pub mod profile;
 pub mod proposals;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod roles;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod suins;
+pub mod types;
+#[cfg(test)]
+mod test_utils;
 pub mod user;
 pub mod utils;
+pub mod workflows;
 
 use move_types::TypeTag;
-pub use multisig_builder::MultisigBuilder;
+pub use multisig_builder::{CreationEstimate, MultisigBuilder};
+pub use proposals::actions::{IntentActions, IntentType};
+pub use types::{CoinType, StructType};
 
 use anyhow::{anyhow, Ok, Result};
 use move_types::{functions::Arg, Key, MoveType};
-use std::{fmt, sync::Arc};
+use std::{
+    fmt,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+use sui_crypto::SuiSigner;
 use sui_graphql_client::Client;
-use sui_sdk_types::{Address, Argument, ObjectData, ObjectId};
+use sui_sdk_types::{Address, Argument, ObjectData, ObjectId, Owner, TransactionEffects};
 use sui_transaction_builder::{unresolved::Input, Function, Serialized, TransactionBuilder};
+use tokio::sync::OnceCell;
 
-use crate::assets::{dynamic_fields::DynamicFields, owned_objects::OwnedObjects};
+use crate::approver::{ApprovalDecision, AutoApprover};
+use crate::assets::{
+    dynamic_fields::{normalize_type, DynamicFields},
+    owned_objects::{self, OwnedObjects},
+};
+use crate::coin_metadata::{CoinMetadata, CoinMetadataCache};
+use crate::coin_registry::{CoinRegistry, Network};
+use crate::endpoints::{self, Endpoints, HealthStatus};
+use crate::errors::{MultisigError, WithdrawalIssue};
 use crate::move_binding::{
     account_actions as aa, account_extensions as ae, account_multisig as am,
     account_protocol as ap, sui,
 };
-use crate::multisig::Multisig;
+use crate::multisig::{Fees, LoadOptions, Multisig};
+use crate::policy::{self, Outflow, Policy, PolicyBypass};
+use crate::profile::AccountProfile;
 use crate::proposals::{
-    actions::IntentActions,
+    batch::{ChunkPolicy, ExecutionGroup, ProposalBatch, RequestInputs},
+    borrowed_cap::BorrowedCap,
+    coordinator::ApprovalRequest,
     intents::{Intent, Intents},
-    params::{self, ParamsArgs},
+    params::{self, IntentDefaults, IntentParamsBuilder, ParamsArgs},
+    receipt::ExecutionReceipt,
 };
+use crate::suins::NameCache;
+use crate::types::IntoStructType;
 use crate::user::User;
+use crate::utils::InputKind;
 
 static ACCOUNT_MULTISIG_PACKAGE: &str =
     "0x460632ef4e9e708658788229531b99f1f3285de06e1e50e98a22633c7e494867";
@@ -38,46 +86,86 @@ static ACCOUNT_ACTIONS_PACKAGE: &str =
 static EXTENSIONS_OBJECT: &str =
     "0x698bc414f25a7036d9a72d6861d9d268e478492dc8bfef8b5c1c2f1eae769254";
 static FEE_OBJECT: &str = "0xc27762578a0b1f37224550dcfd0442f37dc82744b802d3517822d1bd2718598f";
+// network the package ids and the move bindings belong to
+static PACKAGES_NETWORK: Network = Network::Testnet;
 static CLOCK_OBJECT: &str = "0x0000000000000000000000000000000000000000000000000000000000000006";
 
+// parsed once, checked by the constant_ids_are_valid test
+static ACCOUNT_MULTISIG_PACKAGE_ID: LazyLock<Address> =
+    LazyLock::new(|| parse_constant(ACCOUNT_MULTISIG_PACKAGE));
+static ACCOUNT_PROTOCOL_PACKAGE_ID: LazyLock<Address> =
+    LazyLock::new(|| parse_constant(ACCOUNT_PROTOCOL_PACKAGE));
+static ACCOUNT_ACTIONS_PACKAGE_ID: LazyLock<Address> =
+    LazyLock::new(|| parse_constant(ACCOUNT_ACTIONS_PACKAGE));
+static EXTENSIONS_OBJECT_ID: LazyLock<Address> =
+    LazyLock::new(|| parse_constant(EXTENSIONS_OBJECT));
+static FEE_OBJECT_ID: LazyLock<Address> = LazyLock::new(|| parse_constant(FEE_OBJECT));
+static CLOCK_OBJECT_ID: LazyLock<Address> = LazyLock::new(|| parse_constant(CLOCK_OBJECT));
+
+fn parse_constant(id: &str) -> Address {
+    Address::from_hex(id).expect("invalid constant id")
+}
+
 pub struct MultisigClient {
     sui_client: Arc<Client>,
     multisig: Option<Multisig>,
     user: Option<User>,
+    fees: OnceCell<Fees>,       // fetched once on first use
+    chain_id: OnceCell<String>, // of the endpoint, see verify_network
+    coin_metadata: CoinMetadataCache,
+    names: NameCache,                // SuiNS names resolved so far
+    consistent_reads: Option<usize>, // see Multisig::from_id_consistent
+    policy: Option<Policy>,          // checked before executing intents
+    load_options: LoadOptions,
+    coin_registry: CoinRegistry, // tickers accepted instead of coin types
+    endpoints: Option<Endpoints>, // see new_with_urls
+    intent_defaults: IntentDefaults, // see intent_params
+    gas_budget: Option<u64>,     // overrides the default budget of the network
 }
 
 impl MultisigClient {
     // === Constructors ===
 
     pub fn new_with_client(sui_client: Client) -> Self {
-        Self {
-            sui_client: Arc::new(sui_client),
-            multisig: None,
-            user: None,
-        }
+        Self::with_client(sui_client, Network::Other)
     }
 
     pub fn new_with_url(url: &str) -> Result<Self> {
-        Ok(Self {
-            sui_client: Arc::new(Client::new(url)?),
-            multisig: None,
-            user: None,
-        })
+        Ok(Self::with_client(Client::new(url)?, Network::Other))
+    }
+
+    // fails over to the next url after repeated refresh failures
+    pub fn new_with_urls(urls: &[&str]) -> Result<Self> {
+        let endpoints = Endpoints::new(urls)?;
+        let mut client = Self::new_with_client(endpoints.client()?);
+        client.endpoints = Some(endpoints);
+        Ok(client)
     }
 
     pub fn new_testnet() -> Self {
-        Self {
-            sui_client: Arc::new(Client::new_testnet()),
-            multisig: None,
-            user: None,
-        }
+        Self::with_client(Client::new_testnet(), Network::Testnet)
     }
 
     pub fn new_mainnet() -> Self {
+        Self::with_client(Client::new_mainnet(), Network::Mainnet)
+    }
+
+    fn with_client(sui_client: Client, network: Network) -> Self {
         Self {
-            sui_client: Arc::new(Client::new_mainnet()),
+            sui_client: Arc::new(sui_client),
             multisig: None,
             user: None,
+            fees: OnceCell::new(),
+            chain_id: OnceCell::new(),
+            coin_metadata: CoinMetadataCache::default(),
+            names: NameCache::default(),
+            consistent_reads: None,
+            policy: None,
+            load_options: LoadOptions::default(),
+            coin_registry: CoinRegistry::new(network),
+            endpoints: None,
+            intent_defaults: IntentDefaults::default(),
+            gas_budget: None,
         }
     }
 
@@ -87,22 +175,30 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
     ) -> Result<Arg<ap::account::Account<am::multisig::Multisig>>> {
-        let fee_obj = utils::get_object(&self.sui_client, Address::from_hex(FEE_OBJECT)?).await?;
-        let fee = if let ObjectData::Struct(obj) = fee_obj.data() {
-            bcs::from_bytes::<am::fees::Fees>(obj.contents())
-                .map_err(|e| anyhow!("Failed to parse fee object: {}", e))?
-        } else {
-            return Err(anyhow!("Fee object not a struct"));
-        };
-
-        let coin_amount = builder.input(Serialized(&fee.amount));
+        let fees = self.fees().await?;
+        let coin_amount = builder.input(Serialized(&fees.amount));
         let coin_arg = builder.split_coins(builder.gas(), vec![coin_amount]);
-        let fee_arg = builder.input(Input::from(&fee_obj).by_ref());
-        let extensions =
-            builder.input(self.obj(EXTENSIONS_OBJECT.parse()?).await?.by_ref());
+
+        self.create_multisig_with_coin(builder, coin_arg).await
+    }
+
+    // payment must be a Coin<SUI> of exactly the fee amount
+    pub async fn create_multisig_with_coin(
+        &self,
+        builder: &mut TransactionBuilder,
+        payment: Argument,
+    ) -> Result<Arg<ap::account::Account<am::multisig::Multisig>>> {
+        let fee_arg = builder.input(
+            self.obj(*FEE_OBJECT_ID, InputKind::Shared { mutable: false })
+                .await?,
+        );
+        let extensions = builder.input(
+            self.obj(*EXTENSIONS_OBJECT_ID, InputKind::Shared { mutable: false })
+                .await?,
+        );
 
         let account_obj =
-            am::multisig::new_account(builder, extensions.into(), fee_arg.into(), coin_arg.into());
+            am::multisig::new_account(builder, extensions.into(), fee_arg.into(), payment.into());
 
         Ok(account_obj)
     }
@@ -116,25 +212,164 @@ impl MultisigClient {
     }
 
     pub async fn load_multisig(&mut self, id: Address) -> Result<()> {
-        self.multisig = Some(Multisig::from_id(self.sui_client.clone(), id).await?);
+        self.verify_network().await?;
+        let mut multisig =
+            Multisig::from_id_with_options(self.sui_client.clone(), id, self.load_options).await?;
+        if let Some(max_attempts) = self.consistent_reads {
+            multisig.consistent_reads = Some(max_attempts);
+            multisig.ensure_consistent().await?;
+        }
+        self.multisig = Some(multisig);
         Ok(())
     }
 
+    // refreshes are retried until the multisig didn't change while being fetched,
+    // at most max_attempts times (None to disable)
+    pub fn set_consistent_reads(&mut self, max_attempts: Option<usize>) {
+        self.consistent_reads = max_attempts;
+        if let Some(multisig) = self.multisig.as_mut() {
+            multisig.consistent_reads = max_attempts;
+        }
+    }
+
+    // applied to the multisigs loaded next and to the current one on refresh
+    pub fn set_load_options(&mut self, load_options: LoadOptions) {
+        self.load_options = load_options;
+        if let Some(multisig) = self.multisig.as_mut() {
+            multisig.load_options = load_options;
+        }
+    }
+
+    // timing of the intents created with intent_params and batch
+    pub fn set_intent_defaults(&mut self, intent_defaults: IntentDefaults) {
+        self.intent_defaults = intent_defaults;
+    }
+
+    // returns the previous policy, None to disable the checks
+    pub fn set_policy(&mut self, policy: Option<Policy>) -> Option<Policy> {
+        std::mem::replace(&mut self.policy, policy)
+    }
+
+    // the client without its policy if skip, the policy is restored when the guard is dropped
+    // e.g. client.skip_policy(force).request_spend_and_transfer(..).await?
+    pub fn skip_policy(&mut self, skip: bool) -> PolicyBypass<'_> {
+        PolicyBypass::new(self, skip)
+    }
+
+    // None to use the default budget of the network (see utils::default_gas_budget)
+    pub fn set_gas_budget(&mut self, gas_budget: Option<u64>) {
+        self.gas_budget = gas_budget;
+    }
+
+    // the network is the one of the chain id if already fetched (see verify_network),
+    // the one of the constructor otherwise
+    pub fn gas_budget(&self) -> u64 {
+        let network = match self.chain_id.get() {
+            Some(chain_id) => Network::from_chain_id(chain_id),
+            None => self.coin_registry.network,
+        };
+        self.gas_budget
+            .unwrap_or_else(|| utils::default_gas_budget(network))
+    }
+
+    // builder with the gas budget of the client and the reference gas price
+    pub async fn init_tx(&self, sender: Address) -> Result<TransactionBuilder> {
+        utils::init_tx_with_budget(&self.sui_client, sender, self.gas_budget()).await
+    }
+
     pub async fn load_user(&mut self, address: Address) -> Result<()> {
+        self.verify_network().await?;
         self.user = Some(User::from_address(self.sui_client.clone(), address).await?);
         Ok(())
     }
 
-    pub async fn refresh(&mut self) -> Result<()> {
-        if let Some(multisig) = self.multisig.as_mut() {
-            multisig.refresh().await?;
-        }
+    // accepts the invite with the loaded user then loads the multisig it was sent from
+    pub async fn accept_invite_and_load<S: SuiSigner>(
+        &mut self,
+        invite_id: Address,
+        signer: &S,
+    ) -> Result<()> {
+        let user = self.user().ok_or(anyhow!("User not loaded"))?;
+        let multisig_id = user
+            .invites
+            .iter()
+            .find(|invite| *invite.id.as_address() == invite_id)
+            .map(|invite| *invite.multisig_id.as_address())
+            .ok_or(anyhow!("Invite {} not found", invite_id))?;
+
+        let mut builder = self.init_tx(user.address).await?;
+        user.accept_invite(&mut builder, invite_id).await?;
+        utils::execute_tx(&self.sui_client, builder, signer).await?;
+
         if let Some(user) = self.user.as_mut() {
             user.refresh().await?;
         }
+        self.load_multisig(multisig_id).await
+    }
+
+    pub async fn refresh(&mut self) -> Result<()> {
+        let result = self.refresh_all(false).await;
+        self.record_result(&result)?;
+        result
+    }
+
+    // only refetches the multisig if its Account object changed
+    pub async fn refresh_incremental(&mut self) -> Result<()> {
+        let result = self.refresh_all(true).await;
+        self.record_result(&result)?;
+        result
+    }
+
+    // checks the endpoint is on the network of the packages, fetched once per endpoint.
+    // custom networks (e.g. localnet deployments) are accepted
+    pub async fn verify_network(&self) -> Result<()> {
+        let chain_id = self
+            .chain_id
+            .get_or_try_init(|| async { Ok(self.sui_client.chain_id().await?) })
+            .await?;
+        let network = Network::from_chain_id(chain_id);
+        if network != PACKAGES_NETWORK && network != Network::Other {
+            return Err(MultisigError::WrongNetwork {
+                expected: PACKAGES_NETWORK.to_string(),
+                actual: network.to_string(),
+            }
+            .into());
+        }
         Ok(())
     }
 
+    // chain id, latest checkpoint and latency of the current endpoint
+    pub async fn health_check(&self) -> Result<HealthStatus> {
+        let url = self
+            .endpoints
+            .as_ref()
+            .ok_or(anyhow!("Client created without a list of endpoints"))?
+            .current();
+        endpoints::health_check(&self.sui_client, url).await
+    }
+
+    pub fn endpoints(&self) -> Option<&Endpoints> {
+        self.endpoints.as_ref()
+    }
+
+    // switches to the next endpoint of new_with_urls, returns its url
+    pub fn failover(&mut self) -> Result<String> {
+        let endpoints = self
+            .endpoints
+            .as_mut()
+            .ok_or(anyhow!("Client created without a list of endpoints"))?;
+        endpoints.next();
+        self.connect()
+    }
+
+    // refetches only the intent, to see the new approvals right after (dis)approving
+    pub async fn refresh_intent(&mut self, key: &str) -> Result<()> {
+        self.intents_mut()
+            .ok_or(anyhow!("Intents not loaded"))?
+            .refresh_intent(key)
+            .await
+    }
+
     pub async fn switch_multisig(&mut self, id: Address) -> Result<()> {
         if let Some(multisig) = self.multisig.as_mut() {
             multisig.switch_multisig(id).await?;
@@ -142,11 +377,21 @@ impl MultisigClient {
         Ok(())
     }
 
+    // sender: the address signing the transaction, checked against the intent on chain
     pub async fn approve_intent(
         &self,
         builder: &mut TransactionBuilder,
         intent_key: &str,
+        sender: Address,
     ) -> Result<()> {
+        if self.approved_on_chain(intent_key, sender).await? == Some(true) {
+            return Err(MultisigError::AlreadyApproved {
+                key: intent_key.to_string(),
+                address: sender,
+            }
+            .into());
+        }
+
         let mut multisig = self.multisig_arg(builder).await?;
         let key = self.key_arg(builder, intent_key)?;
 
@@ -159,7 +404,16 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
         intent_key: &str,
+        sender: Address,
     ) -> Result<()> {
+        if self.approved_on_chain(intent_key, sender).await? == Some(false) {
+            return Err(MultisigError::NotApproved {
+                key: intent_key.to_string(),
+                address: sender,
+            }
+            .into());
+        }
+
         let mut multisig = self.multisig_arg(builder).await?;
         let key = self.key_arg(builder, intent_key)?;
 
@@ -168,7 +422,139 @@ impl MultisigClient {
         Ok(())
     }
 
+    // request to circulate to the members who still have to approve the intent,
+    // the intent is refetched with its actions
+    pub async fn approval_request(&mut self, intent_key: &str) -> Result<ApprovalRequest> {
+        let multisig = self.multisig_mut().ok_or(anyhow!("Multisig not loaded"))?;
+        ApprovalRequest::new(multisig, intent_key).await
+    }
+
+    // approves the intent only if the one on chain is the one described by the request
+    pub async fn approve_request(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        request: &ApprovalRequest,
+        sender: Address,
+    ) -> Result<()> {
+        let multisig = self.multisig_mut().ok_or(anyhow!("Multisig not loaded"))?;
+        request.verify(multisig).await?;
+        self.approve_intent(builder, &request.key, sender).await
+    }
+
+    // deletes an expired intent or one without executions left, with the flow of its type
+    pub async fn delete_intent(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+    ) -> Result<()> {
+        let intent_type = IntentType::try_from(self.intent(intent_key)?.type_.as_str())?;
+        match intent_type {
+            IntentType::ConfigMultisig => self.delete_config_multisig(builder, intent_key).await,
+            IntentType::ConfigDeps => self.delete_config_deps(builder, intent_key).await,
+            IntentType::ToggleUnverifiedAllowed => {
+                self.delete_toggle_unverified_allowed(builder, intent_key)
+                    .await
+            }
+            IntentType::BorrowCap => self.delete_borrow_cap(builder, intent_key).await,
+            IntentType::DisableRules => self.delete_disable_rules(builder, intent_key).await,
+            IntentType::UpdateMetadata => self.delete_update_metadata(builder, intent_key).await,
+            IntentType::MintAndTransfer => self.delete_mint_and_transfer(builder, intent_key).await,
+            IntentType::MintAndVest => self.delete_mint_and_vest(builder, intent_key).await,
+            IntentType::WithdrawAndBurn => self.delete_withdraw_and_burn(builder, intent_key).await,
+            IntentType::TakeNfts | IntentType::ListNfts => Err(anyhow!(
+                "Deleting {:?} intents is not implemented",
+                intent_type
+            )),
+            IntentType::WithdrawAndTransferToVault => {
+                self.delete_withdraw_and_transfer_to_vault(builder, intent_key)
+                    .await
+            }
+            IntentType::WithdrawAndTransfer => {
+                self.delete_withdraw_and_transfer(builder, intent_key).await
+            }
+            IntentType::WithdrawAndVest => self.delete_withdraw_and_vest(builder, intent_key).await,
+            IntentType::UpgradePackage => self.delete_upgrade_package(builder, intent_key).await,
+            IntentType::RestrictPolicy => self.delete_restrict_policy(builder, intent_key).await,
+            IntentType::SpendAndTransfer => {
+                self.delete_spend_and_transfer(builder, intent_key).await
+            }
+            IntentType::SpendAndVest => self.delete_spend_and_vest(builder, intent_key).await,
+        }
+    }
+
+    // for the creator abandoning a proposal: removes the user approval if any and deletes
+    // the intent if it has expired or has no executions left, returns whether it was deleted
+    pub async fn withdraw_proposal(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+    ) -> Result<bool> {
+        let user = self.user().ok_or(anyhow!("User not loaded"))?.address;
+        let intent = self.intent(intent_key)?;
+        if intent.creator != user {
+            return Err(anyhow!(
+                "Only the creator {} can withdraw {}",
+                intent.creator,
+                intent_key
+            ));
+        }
+
+        if self.has_approved(intent_key)? == Some(true) {
+            self.disapprove_intent(builder, intent_key, user).await?;
+        }
+
+        // same conditions as prepare_delete
+        let now = self.clock_timestamp().await?;
+        let intent = self.intent(intent_key)?;
+        if now <= intent.expiration_time && !intent.execution_times.is_empty() {
+            return Ok(false);
+        }
+        self.delete_intent(builder, intent_key).await?;
+        Ok(true)
+    }
+
+    // whether the address approved the intent as it is on chain, None if the intent isn't cached
+    // (the check is skipped and the transaction fails on chain if needed)
+    async fn approved_on_chain(&self, intent_key: &str, address: Address) -> Result<Option<bool>> {
+        let Some(intents) = self.intents() else {
+            return Ok(None);
+        };
+        if intents.get_intent(intent_key).is_none() {
+            return Ok(None);
+        }
+        Ok(intents
+            .fetch_intent(intent_key)
+            .await?
+            .map(|intent| intent.outcome.approved.contains(&address)))
+    }
+
+    // whether the loaded user approved the intent, None if no user is loaded
+    pub fn has_approved(&self, intent_key: &str) -> Result<Option<bool>> {
+        let Some(user) = self.user() else {
+            return Ok(None);
+        };
+        let intent = self.intent(intent_key)?;
+        Ok(Some(intent.outcome.approved.contains(&user.address)))
+    }
+
+    // params of a new intent with the client defaults, override them before building:
+    // client.intent_params(key).await?.description(..).build(builder)
+    pub async fn intent_params(&self, key: &str) -> Result<IntentParamsBuilder> {
+        let now = self.clock_timestamp().await?;
+        Ok(IntentParamsBuilder::new(key, self.intent_defaults, now))
+    }
+
+    pub async fn batch<'a>(
+        &'a self,
+        builder: &'a mut TransactionBuilder,
+        key_prefix: &str,
+    ) -> Result<ProposalBatch<'a>> {
+        ProposalBatch::new(self, builder, key_prefix).await
+    }
+
     // === Commands ===
+    // commands are authenticated with am::multisig::authenticate, which only checks that the sender
+    // is a member: the protocol has no role-scoped auth, roles only weigh in intent approvals
 
     pub async fn replace_metadata(
         &self,
@@ -186,6 +572,16 @@ impl MultisigClient {
         Ok(())
     }
 
+    // writes the whole profile, keys missing from it are removed from the metadata
+    pub async fn replace_profile(
+        &self,
+        builder: &mut TransactionBuilder,
+        profile: &AccountProfile,
+    ) -> Result<()> {
+        let (keys, values) = profile.to_metadata();
+        self.replace_metadata(builder, keys, values).await
+    }
+
     pub async fn update_verified_deps_to_latest(
         &self,
         builder: &mut TransactionBuilder,
@@ -204,22 +600,87 @@ impl MultisigClient {
         Ok(())
     }
 
+    pub async fn deposit_object(
+        &self,
+        builder: &mut TransactionBuilder,
+        object_id: Address,
+    ) -> Result<()> {
+        let multisig_id = self.multisig_id()?;
+        let object = utils::get_object(&self.sui_client, object_id).await?;
+
+        // only objects owned by a wallet can be transferred to the multisig
+        match object.owner() {
+            Owner::Address(owner) if *owner == multisig_id => {
+                return Err(anyhow!(
+                    "Object {} is already owned by the multisig",
+                    object_id
+                ))
+            }
+            Owner::Address(_) => (),
+            _ => return Err(anyhow!("Object {} is not owned by an address", object_id)),
+        }
+        if let ObjectData::Package(_) = object.data() {
+            return Err(anyhow!("Object {} is a package", object_id));
+        }
+
+        let object_arg = builder.input(Input::from(&object).with_owned_kind());
+        let recipient = builder.input(Serialized(&multisig_id));
+        builder.transfer_objects(vec![object_arg], recipient);
+
+        Ok(())
+    }
+
+    // wallet-owned objects whose type name ends in Cap, sorted by type, to pick one to deposit
+    pub async fn find_depositable_caps(
+        &self,
+        owner: Address,
+    ) -> Result<Vec<(Address, StructType)>> {
+        let mut caps = utils::get_owned_objects(&self.sui_client, owner, None)
+            .await?
+            .into_iter()
+            .filter_map(|object| match object.data() {
+                ObjectData::Struct(obj) if obj.object_type().name.as_str().ends_with("Cap") => {
+                    Some((object.object_id().into(), obj.object_type().clone().into()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<(Address, StructType)>>();
+        caps.sort_by_key(|(_, type_)| type_.to_string());
+        Ok(caps)
+    }
+
+    // the type of cap_id is checked against cap_type before building the call
     pub async fn deposit_cap(
         &self,
         builder: &mut TransactionBuilder,
         cap_id: Address,
-        cap_type: &str,
+        cap_type: impl IntoStructType,
     ) -> Result<()> {
+        let cap_type = cap_type.into_struct_type()?;
+        let object = utils::get_object(&self.sui_client, cap_id).await?;
+        match object.data() {
+            ObjectData::Struct(obj) if StructType::from(obj.object_type().clone()) == cap_type => {}
+            ObjectData::Struct(obj) => {
+                return Err(anyhow!(
+                    "Object {} is a {}, not a {}",
+                    cap_id,
+                    obj.object_type(),
+                    cap_type
+                ))
+            }
+            ObjectData::Package(_) => return Err(anyhow!("Object {} is a package", cap_id)),
+        }
+
         let mut multisig = self.multisig_arg(builder).await?;
         let cap = self.owned_argument(builder, cap_id).await?;
 
         let auth = am::multisig::authenticate(builder, multisig.borrow());
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "access_control".parse()?,
                 "lock_cap".parse()?,
-                vec![cap_type.parse()?],
+                vec![cap_type.into()],
             ),
             vec![auth.into(), multisig.borrow_mut().into(), cap],
         );
@@ -232,7 +693,7 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         max_supply: Option<u64>,
         cap_id: Address,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
         let mut multisig = self.multisig_arg(builder).await?;
         let max_supply = self.pure_arg(builder, max_supply)?;
@@ -241,10 +702,10 @@ impl MultisigClient {
         let auth = am::multisig::authenticate(builder, multisig.borrow());
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency".parse()?,
                 "lock_cap".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
@@ -262,12 +723,12 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         coins_to_merge: Vec<Address>,
         amounts_to_split: Vec<u64>,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<Argument> {
         let mut multisig = self.multisig_arg(builder).await?;
         let mut coin_inputs = Vec::new();
         for coin in coins_to_merge {
-            coin_inputs.push(builder.input(self.obj(coin).await?.with_receiving_kind()));
+            coin_inputs.push(self.receive_argument(builder, coin).await?);
         }
 
         let to_merge = builder.make_move_vec(None, coin_inputs);
@@ -276,10 +737,10 @@ impl MultisigClient {
         let auth = am::multisig::authenticate(builder, multisig.borrow());
         let ids = builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_PROTOCOL_PACKAGE.parse()?,
+                *ACCOUNT_PROTOCOL_PACKAGE_ID,
                 "owned".parse()?,
                 "merge_and_split".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
@@ -338,7 +799,7 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         vault_name: String,
         coin: Argument, // splitted in previous command
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
         let mut multisig = self.multisig_arg(builder).await?;
         let vault_name = builder.input(Serialized(&vault_name));
@@ -346,10 +807,10 @@ impl MultisigClient {
         let auth = am::multisig::authenticate(builder, multisig.borrow());
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vault".parse()?,
                 "deposit".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![auth.into(), multisig.borrow_mut().into(), vault_name, coin],
         );
@@ -357,11 +818,15 @@ impl MultisigClient {
         Ok(())
     }
 
+    // the vault must be empty, checked against the dynamic fields if they are loaded
     pub async fn close_vault(
         &self,
         builder: &mut TransactionBuilder,
         vault_name: &str,
     ) -> Result<()> {
+        if self.dynamic_fields().is_some() {
+            self.validate_close_vault(vault_name)?;
+        }
         let mut multisig = self.multisig_arg(builder).await?;
         let vault_name = self.pure_arg(builder, vault_name.to_string())?;
 
@@ -371,12 +836,54 @@ impl MultisigClient {
         Ok(())
     }
 
+    // closes the vault if it's empty, otherwise proposes to spend and transfer what's left
+    // to the recipient and returns the keys: call it again once they are executed
+    pub async fn drain_and_close_vault(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        vault_name: &str,
+        key_prefix: &str,
+        recipient: Address,
+    ) -> Result<Vec<String>> {
+        self.multisig
+            .as_mut()
+            .and_then(|multisig| multisig.dynamic_fields.as_mut())
+            .ok_or(anyhow!("Dynamic fields not loaded"))?
+            .refresh_vault(vault_name)
+            .await?;
+
+        match self.validate_close_vault(vault_name) {
+            Err(e) => match e.downcast_ref::<MultisigError>() {
+                Some(MultisigError::VaultNotEmpty { balances, .. }) => {
+                    let payouts = balances
+                        .iter()
+                        .map(|(coin_type, amount)| (coin_type.clone(), *amount, recipient))
+                        .collect();
+                    let description = format!("Drain vault {}", vault_name);
+                    self.request_vault_payout(
+                        builder,
+                        key_prefix,
+                        &description,
+                        vault_name,
+                        payouts,
+                    )
+                    .await
+                }
+                _ => Err(e),
+            },
+            _ => {
+                self.close_vault(builder, vault_name).await?;
+                Ok(vec![])
+            }
+        }
+    }
+
     pub async fn claim_vested(
         &self,
         builder: &mut TransactionBuilder,
         vesting_id: Address,
         cap_id: Address,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
         let vesting = self.shared_mut_argument(builder, vesting_id).await?;
         let cap = self
@@ -386,10 +893,10 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vesting".parse()?,
                 "claim".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![vesting, cap.borrow().into(), clock.borrow().into()],
         );
@@ -401,7 +908,7 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
         vesting_id: Address,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
         let multisig = self.multisig_arg(builder).await?;
         let vesting = self.shared_mut_argument(builder, vesting_id).await?;
@@ -409,10 +916,10 @@ impl MultisigClient {
         let auth = am::multisig::authenticate(builder, multisig.borrow());
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vesting".parse()?,
                 "cancel_payment".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![auth.into(), vesting, multisig.borrow().into()],
         );
@@ -424,16 +931,16 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
         vesting_id: Address,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
         let vesting = self.shared_mut_argument(builder, vesting_id).await?;
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vesting".parse()?,
                 "destroy_empty".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![vesting],
         );
@@ -463,13 +970,26 @@ impl MultisigClient {
         intent_args: ParamsArgs,
         actions_args: params::ConfigMultisigArgs,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_config_multisig_with(builder, &mut inputs, intent_args, actions_args)
+            .await
+    }
+
+    pub(crate) async fn request_config_multisig_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::ConfigMultisigArgs,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::ConfigMultisig, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         am::config::request_config_multisig(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
             actions_args.addresses,
@@ -488,6 +1008,30 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_key: &str,
     ) -> Result<()> {
+        self.execute_config_multisig_with_invites(builder, intent_key, false)
+            .await?;
+        Ok(())
+    }
+
+    // optionally sends an invite to the members added by the new config, returns their addresses.
+    // an intent proposing a profile (see proposed_profile) is executed with execute_config_metadata
+    pub async fn execute_config_multisig_with_invites(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+        send_invites: bool,
+    ) -> Result<Vec<Address>> {
+        if self.proposed_profile(intent_key).await?.is_some() {
+            self.execute_config_metadata(builder, intent_key).await?;
+            return Ok(vec![]);
+        }
+
+        let new_members = if send_invites {
+            self.added_members(intent_key).await?
+        } else {
+            vec![]
+        };
+
         let (mut multisig, mut executable, is_last_execution, _executions_count) =
             self.prepare_execute(builder, intent_key).await?;
 
@@ -498,6 +1042,12 @@ impl MultisigClient {
         );
         ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
 
+        // the config is updated at this point so the recipients are members
+        for recipient in new_members.iter() {
+            let recipient_arg = builder.input(Serialized(recipient));
+            am::multisig::send_invite(builder, multisig.borrow(), recipient_arg.into());
+        }
+
         if is_last_execution {
             let key = self.key_arg(builder, intent_key)?;
             let mut expired = ap::account::destroy_empty_intent::<
@@ -509,7 +1059,7 @@ impl MultisigClient {
             ap::intents::destroy_empty_expired(builder, expired);
         }
 
-        Ok(())
+        Ok(new_members)
     }
 
     pub async fn delete_config_multisig(
@@ -526,20 +1076,155 @@ impl MultisigClient {
         Ok(())
     }
 
+    // account_protocol edits the metadata with an Auth only (any member, no approval), so the
+    // profile is proposed as a config intent keeping the current config, with the profile in
+    // its description. enforced by the SDK only: replace_metadata still works for any member
+    pub async fn request_config_metadata(
+        &self,
+        builder: &mut TransactionBuilder,
+        params: IntentParamsBuilder,
+        profile: &AccountProfile,
+    ) -> Result<()> {
+        let params = params
+            .description(&profile.to_intent_description()?)
+            .build_checked(builder, &IntentType::ConfigMultisig)?;
+        let config = &self
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?
+            .config;
+
+        let (mut addresses, mut weights, mut roles) = (Vec::new(), Vec::new(), Vec::new());
+        for member in &config.members {
+            addresses.push(
+                member
+                    .address
+                    .parse::<Address>()
+                    .map_err(|_| anyhow!("Invalid member address: {}", member.address))?,
+            );
+            weights.push(member.weight);
+            roles.push(member.roles.clone());
+        }
+        let (role_names, role_thresholds) = config
+            .roles
+            .iter()
+            .map(|(name, role)| (name.clone(), role.threshold))
+            .unzip();
+        let actions_args = params::ConfigMultisigArgs::new(
+            builder,
+            addresses,
+            weights,
+            roles,
+            config.global.threshold,
+            role_names,
+            role_thresholds,
+        );
+
+        self.request_config_multisig(builder, params, actions_args)
+            .await
+    }
+
+    // the profile of a config intent made by request_config_metadata: its description carries a
+    // profile and its actions keep the current config, None for the other config intents
+    pub async fn proposed_profile(&mut self, intent_key: &str) -> Result<Option<AccountProfile>> {
+        let description = &self.intent(intent_key)?.description;
+        let Some(Ok(profile)) = AccountProfile::from_intent_description(description) else {
+            return Ok(None);
+        };
+        let IntentActions::ConfigMultisig(fields) = self.intent_actions(intent_key).await? else {
+            return Ok(None);
+        };
+        let config = &self
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?
+            .config;
+        Ok(config.matches(&fields).then_some(profile))
+    }
+
+    // executes the config intent then writes the proposed profile,
+    // fails if the config changed since the request as executing it would revert the change
+    pub async fn execute_config_metadata(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+    ) -> Result<()> {
+        let profile =
+            AccountProfile::from_intent_description(&self.intent(intent_key)?.description)
+                .ok_or(anyhow!("Intent {} doesn't propose a profile", intent_key))??;
+        let IntentActions::ConfigMultisig(fields) = self.intent_actions(intent_key).await? else {
+            return Err(anyhow!(
+                "Intent {} is not a config multisig intent",
+                intent_key
+            ));
+        };
+        if !self
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?
+            .config
+            .matches(&fields)
+        {
+            return Err(anyhow!(
+                "The config changed since {} was proposed, delete it and propose the profile again",
+                intent_key
+            ));
+        }
+
+        let (mut multisig, mut executable, is_last_execution, _executions_count) =
+            self.prepare_execute(builder, intent_key).await?;
+
+        am::config::execute_config_multisig(
+            builder,
+            executable.borrow_mut(),
+            multisig.borrow_mut(),
+        );
+        ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
+
+        let (keys, values) = profile.to_metadata();
+        let keys = self.pure_arg(builder, keys)?;
+        let values = self.pure_arg(builder, values)?;
+        let auth = am::multisig::authenticate(builder, multisig.borrow());
+        ap::config::edit_metadata(builder, auth, multisig.borrow_mut(), keys, values);
+
+        if is_last_execution {
+            let key = self.key_arg(builder, intent_key)?;
+            let mut expired = ap::account::destroy_empty_intent::<
+                am::multisig::Multisig,
+                am::multisig::Approvals,
+            >(builder, multisig.borrow_mut(), key);
+
+            am::config::delete_config_multisig(builder, expired.borrow_mut());
+            ap::intents::destroy_empty_expired(builder, expired);
+        }
+
+        Ok(())
+    }
+
     pub async fn request_config_deps(
         &self,
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::ConfigDepsArgs,
     ) -> Result<()> {
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_config_deps_with(builder, &mut inputs, intent_args, actions_args)
+            .await
+    }
+
+    pub(crate) async fn request_config_deps_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::ConfigDepsArgs,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::ConfigDeps, &[])
+            .await?;
         let extensions = self.extensions_arg(builder).await?;
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         ap::config::request_config_deps(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
             extensions.borrow(),
@@ -595,13 +1280,25 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_toggle_unverified_allowed_with(builder, &mut inputs, intent_args)
+            .await
+    }
+
+    pub(crate) async fn request_toggle_unverified_allowed_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::ToggleUnverifiedAllowed, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         ap::config::request_toggle_unverified_allowed(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
         );
@@ -656,21 +1353,34 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
-        cap_type: &str,
+        cap_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_borrow_cap_with(builder, &mut inputs, intent_args, cap_type)
+            .await
+    }
+
+    pub(crate) async fn request_borrow_cap_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        cap_type: impl IntoStructType,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::BorrowCap, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "access_control_intents".parse()?,
                 "request_borrow_cap".parse()?,
-                vec![cap_type.parse()?],
+                vec![cap_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
             ],
@@ -694,7 +1404,7 @@ impl MultisigClient {
         let cap_type = self.actions_generic(intent_key).await?;
         let cap = builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "access_control_intents".parse()?,
                 "execute_borrow_cap".parse()?,
                 vec![cap_type],
@@ -705,6 +1415,20 @@ impl MultisigClient {
         Ok((multisig, executable, cap))
     }
 
+    // same as execute_borrow_cap but the Cap is guarded until it's returned with BorrowedCap::finish,
+    // see execute_with_borrowed_cap to have it returned for you
+    pub async fn borrow_cap(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+    ) -> Result<BorrowedCap> {
+        let (multisig, executable, cap) = self.execute_borrow_cap(builder, intent_key).await?;
+        let cap_type = self.actions_generic(intent_key).await?;
+        Ok(BorrowedCap::new(
+            intent_key, cap_type, cap, multisig, executable,
+        ))
+    }
+
     // Use the Cap between borrow and return
     pub async fn execute_return_cap(
         &mut self,
@@ -718,7 +1442,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "access_control_intents".parse()?,
                 "execute_return_cap".parse()?,
                 vec![cap_type.clone()],
@@ -740,7 +1464,7 @@ impl MultisigClient {
 
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "access_control".parse()?,
                     "delete_borrow".parse()?,
                     vec![cap_type.clone()],
@@ -749,7 +1473,7 @@ impl MultisigClient {
             );
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "access_control".parse()?,
                     "delete_return".parse()?,
                     vec![cap_type],
@@ -763,6 +1487,25 @@ impl MultisigClient {
         Ok(())
     }
 
+    // borrows the Cap, lets the closure use it in the same PTB then returns it and cleans up,
+    // the Cap type is the one of the intent (see actions_generic)
+    pub async fn execute_with_borrowed_cap<F>(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+        use_cap: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut TransactionBuilder, Argument) -> Result<()>,
+    {
+        let cap = self.borrow_cap(builder, intent_key).await?;
+        if let Err(e) = use_cap(builder, cap.arg()) {
+            cap.abandon();
+            return Err(e);
+        }
+        cap.finish(self, builder).await
+    }
+
     pub async fn delete_borrow_cap(
         &mut self,
         builder: &mut TransactionBuilder,
@@ -775,7 +1518,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "access_control".parse()?,
                 "delete_borrow".parse()?,
                 vec![cap_type.clone()],
@@ -784,7 +1527,7 @@ impl MultisigClient {
         );
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "access_control".parse()?,
                 "delete_return".parse()?,
                 vec![cap_type],
@@ -801,21 +1544,35 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::DisableRulesArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_disable_rules_with(builder, &mut inputs, intent_args, actions_args, coin_type)
+            .await
+    }
+
+    pub(crate) async fn request_disable_rules_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::DisableRulesArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::DisableRules, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "request_disable_rules".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.mint.into(),
@@ -842,7 +1599,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "execute_disable_rules".parse()?,
                 vec![coin_type.clone()],
@@ -860,7 +1617,7 @@ impl MultisigClient {
 
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "currency".parse()?,
                     "delete_disable".parse()?,
                     vec![coin_type],
@@ -885,7 +1642,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency".parse()?,
                 "delete_disable".parse()?,
                 vec![coin_type],
@@ -902,21 +1659,41 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::UpdateMetadataArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_update_metadata_with(
+            builder,
+            &mut inputs,
+            intent_args,
+            actions_args,
+            coin_type,
+        )
+        .await
+    }
+
+    pub(crate) async fn request_update_metadata_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::UpdateMetadataArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::UpdateMetadata, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "request_update_metadata".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.symbol.into(),
@@ -939,16 +1716,14 @@ impl MultisigClient {
 
         let coin_type = self.actions_generic(intent_key).await?;
 
-        let coin_metadata_object = self.sui().coin_metadata(coin_type.to_string().as_str())
-            .await?
-            .ok_or(anyhow!("Coin metadata object not found"))?;
+        let coin_metadata_object = self.coin_metadata(coin_type.to_string().as_str()).await?;
         let coin_metadata = self
-            .shared_mut_argument(builder, coin_metadata_object.address)
+            .shared_mut_argument(builder, coin_metadata_object.id)
             .await?;
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "execute_update_metadata".parse()?,
                 vec![coin_type.clone()],
@@ -970,7 +1745,7 @@ impl MultisigClient {
 
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "currency".parse()?,
                     "delete_update".parse()?,
                     vec![coin_type],
@@ -995,7 +1770,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency".parse()?,
                 "delete_update".parse()?,
                 vec![coin_type],
@@ -1012,21 +1787,51 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::MintAndTransferArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_mint_and_transfer_with(
+            builder,
+            &mut inputs,
+            intent_args,
+            actions_args,
+            coin_type,
+        )
+        .await
+    }
+
+    pub(crate) async fn request_mint_and_transfer_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::MintAndTransferArgs,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let coin_type = coin_type.into_struct_type()?;
+        let values = &actions_args.values;
+        let transfers = values
+            .amounts
+            .iter()
+            .copied()
+            .zip(values.recipients.iter().copied());
+        self.check_request_policy(
+            &IntentType::MintAndTransfer,
+            &policy::coin_outflows(&coin_type.to_string(), &transfers.collect::<Vec<_>>()),
+        )
+        .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "request_mint_and_transfer".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.amounts.into(),
@@ -1037,28 +1842,47 @@ impl MultisigClient {
         Ok(())
     }
 
+    // executes every execution of the intent whose time has arrived
     pub async fn execute_mint_and_transfer(
         &mut self,
         builder: &mut TransactionBuilder,
         intent_key: &str,
     ) -> Result<()> {
-        let (mut multisig, mut executable, is_last_execution, executions_count) =
-            self.prepare_execute(builder, intent_key).await?;
+        self.execute_mint_and_transfer_partial(builder, intent_key, usize::MAX)
+            .await
+    }
+
+    // executes at most `max_executions` of the executions whose time has arrived
+    pub async fn execute_mint_and_transfer_partial(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+        max_executions: usize,
+    ) -> Result<()> {
+        let (mut multisig, clock, due_executions, is_last_execution, executions_count) = self
+            .prepare_execute_due(builder, intent_key, max_executions)
+            .await?;
 
         let coin_type = self.actions_generic(intent_key).await?;
 
-        for _ in 0..executions_count {
-            builder.move_call(
-                sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
-                    "currency_intents".parse()?,
-                    "execute_mint_and_transfer".parse()?,
-                    vec![coin_type.clone()],
-                ),
-                vec![executable.borrow_mut().into(), multisig.borrow_mut().into()],
-            );
+        for _ in 0..due_executions {
+            let key = self.key_arg(builder, intent_key)?;
+            let mut executable =
+                am::multisig::execute_intent(builder, multisig.borrow_mut(), key, clock.borrow());
+
+            for _ in 0..executions_count {
+                builder.move_call(
+                    sui_transaction_builder::Function::new(
+                        *ACCOUNT_ACTIONS_PACKAGE_ID,
+                        "currency_intents".parse()?,
+                        "execute_mint_and_transfer".parse()?,
+                        vec![coin_type.clone()],
+                    ),
+                    vec![executable.borrow_mut().into(), multisig.borrow_mut().into()],
+                );
+            }
+            ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
         }
-        ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
 
         if is_last_execution {
             let key = self.key_arg(builder, intent_key)?;
@@ -1070,7 +1894,7 @@ impl MultisigClient {
             for _ in 0..executions_count {
                 builder.move_call(
                     sui_transaction_builder::Function::new(
-                        ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                        *ACCOUNT_ACTIONS_PACKAGE_ID,
                         "currency".parse()?,
                         "delete_mint".parse()?,
                         vec![coin_type.clone()],
@@ -1098,7 +1922,7 @@ impl MultisigClient {
         for _ in 0..executions_count {
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "currency".parse()?,
                     "delete_mint".parse()?,
                     vec![coin_type.clone()],
@@ -1117,21 +1941,43 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::MintAndVestArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_mint_and_vest_with(builder, &mut inputs, intent_args, actions_args, coin_type)
+            .await
+    }
+
+    pub(crate) async fn request_mint_and_vest_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::MintAndVestArgs,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let coin_type = coin_type.into_struct_type()?;
+        let values = &actions_args.values;
+        self.check_request_policy(
+            &IntentType::MintAndVest,
+            &policy::coin_outflows(
+                &coin_type.to_string(),
+                &[(values.total_amount, values.recipient)],
+            ),
+        )
+        .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "request_mint_and_vest".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.total_amount.into(),
@@ -1156,7 +2002,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "execute_mint_and_vest".parse()?,
                 vec![coin_type.clone()],
@@ -1174,7 +2020,7 @@ impl MultisigClient {
 
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "currency".parse()?,
                     "delete_mint".parse()?,
                     vec![coin_type],
@@ -1200,7 +2046,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency".parse()?,
                 "delete_mint".parse()?,
                 vec![coin_type],
@@ -1218,21 +2064,41 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::WithdrawAndBurnArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_withdraw_and_burn_with(
+            builder,
+            &mut inputs,
+            intent_args,
+            actions_args,
+            coin_type,
+        )
+        .await
+    }
+
+    pub(crate) async fn request_withdraw_and_burn_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::WithdrawAndBurnArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::WithdrawAndBurn, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "request_withdraw_and_burn".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.coin_id.into(),
@@ -1267,7 +2133,7 @@ impl MultisigClient {
         let receive_coin = self.receive_argument(builder, coin_id).await?;
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "execute_withdraw_and_burn".parse()?,
                 vec![coin_type.clone()],
@@ -1290,7 +2156,7 @@ impl MultisigClient {
             ap::owned::delete_withdraw(builder, expired.borrow_mut(), multisig.borrow_mut());
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "currency".parse()?,
                     "delete_burn".parse()?,
                     vec![coin_type],
@@ -1316,7 +2182,7 @@ impl MultisigClient {
         ap::owned::delete_withdraw(builder, expired.borrow_mut(), multisig.borrow_mut());
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency".parse()?,
                 "delete_burn".parse()?,
                 vec![coin_type],
@@ -1333,21 +2199,41 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::WithdrawAndTransferToVaultArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_withdraw_and_transfer_to_vault_with(
+            builder,
+            &mut inputs,
+            intent_args,
+            actions_args,
+            coin_type,
+        )
+        .await
+    }
+
+    pub(crate) async fn request_withdraw_and_transfer_to_vault_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::WithdrawAndTransferToVaultArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::WithdrawAndTransferToVault, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "request_withdraw_and_transfer_to_vault".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.coin_id.into(),
@@ -1359,6 +2245,55 @@ impl MultisigClient {
         Ok(())
     }
 
+    // deposits coins owned by the multisig into a vault, one intent per coin since they can't be
+    // merged without an intent, all the unlocked coins of the type are deposited if amount is None
+    pub async fn request_deposit_owned_to_vault(
+        &self,
+        builder: &mut TransactionBuilder,
+        key_prefix: &str,
+        description: &str,
+        vault_name: &str,
+        coin_type: impl IntoStructType,
+        amount: Option<u64>,
+    ) -> Result<Vec<String>> {
+        let coin_type = coin_type.into_struct_type()?;
+        let multisig = self.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+        let coins = self
+            .owned_objects()
+            .ok_or(anyhow!("Owned objects not loaded"))?
+            .coins
+            .iter()
+            .filter(|coin| !multisig.locked_objects.contains(&coin.id))
+            .filter(|coin| {
+                coin.coin_type()
+                    .parse::<StructType>()
+                    .is_ok_and(|type_| type_ == coin_type)
+            })
+            .map(|coin| (coin.id, coin.balance))
+            .collect::<Vec<_>>();
+        let selected = owned_objects::select_coins(&coins, amount)
+            .map_err(|e| anyhow!("{} for {}", e, coin_type))?;
+
+        let mut batch = self.batch(builder, key_prefix).await?;
+        for (coin_id, coin_amount) in selected {
+            let actions_args = params::WithdrawAndTransferToVaultArgs::new(
+                batch.builder(),
+                coin_id.into(),
+                coin_amount,
+                vault_name.to_string(),
+            );
+            batch
+                .request_withdraw_and_transfer_to_vault(
+                    description,
+                    actions_args,
+                    coin_type.clone(),
+                )
+                .await?;
+        }
+
+        Ok(batch.finish())
+    }
+
     pub async fn execute_withdraw_and_transfer_to_vault(
         &mut self,
         builder: &mut TransactionBuilder,
@@ -1382,7 +2317,7 @@ impl MultisigClient {
         let receive_coin = self.receive_argument(builder, coin_id).await?;
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "currency_intents".parse()?,
                 "execute_withdraw_and_transfer_to_vault".parse()?,
                 vec![coin_type.clone()],
@@ -1405,7 +2340,7 @@ impl MultisigClient {
             ap::owned::delete_withdraw(builder, expired.borrow_mut(), multisig.borrow_mut());
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "vault".parse()?,
                     "delete_deposit".parse()?,
                     vec![coin_type],
@@ -1431,7 +2366,7 @@ impl MultisigClient {
         ap::owned::delete_withdraw(builder, expired.borrow_mut(), multisig.borrow_mut());
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vault".parse()?,
                 "delete_deposit".parse()?,
                 vec![coin_type],
@@ -1449,13 +2384,36 @@ impl MultisigClient {
         intent_args: ParamsArgs,
         actions_args: params::WithdrawAndTransferArgs,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_withdraw_and_transfer_with(builder, &mut inputs, intent_args, actions_args)
+            .await
+    }
+
+    pub(crate) async fn request_withdraw_and_transfer_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::WithdrawAndTransferArgs,
+    ) -> Result<()> {
+        let outflows = actions_args
+            .values
+            .recipients
+            .iter()
+            .map(|recipient| policy::object_outflow(*recipient))
+            .collect::<Vec<_>>();
+        self.check_request_policy(&IntentType::WithdrawAndTransfer, &outflows)
+            .await?;
+        self.validate_withdrawals(
+            &actions_args.values.object_ids,
+            &actions_args.values.recipients,
+        )?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         aa::owned_intents::request_withdraw_and_transfer(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
             actions_args.object_ids,
@@ -1485,15 +2443,12 @@ impl MultisigClient {
         };
 
         for (id, _recipient) in transfers {
-            let receive_id = builder.input(self.obj(id).await?.with_receiving_kind());
-            let obj_type = self
-                .owned_objects()
-                .and_then(|o| o.get_type_by_id(id))
-                .ok_or(anyhow!("Object type not found"))?;
+            let receive_id = self.receive_argument(builder, id).await?;
+            let obj_type = self.owned_object_type(id).await?;
 
             builder.move_call(
                 Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "owned_intents".parse()?,
                     "execute_withdraw_and_transfer".parse()?,
                     vec![obj_type.parse()?],
@@ -1545,13 +2500,29 @@ impl MultisigClient {
         intent_args: ParamsArgs,
         actions_args: params::WithdrawAndVestArgs,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_withdraw_and_vest_with(builder, &mut inputs, intent_args, actions_args)
+            .await
+    }
+
+    pub(crate) async fn request_withdraw_and_vest_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::WithdrawAndVestArgs,
+    ) -> Result<()> {
+        self.check_request_policy(
+            &IntentType::WithdrawAndVest,
+            &[policy::object_outflow(actions_args.values.recipient)],
+        )
+        .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         aa::owned_intents::request_withdraw_and_vest(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
             actions_args.coin_id,
@@ -1585,12 +2556,10 @@ impl MultisigClient {
         let receive_id = self.receive_argument(builder, coin_id).await?;
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "owned_intents".parse()?,
                 "execute_withdraw_and_vest".parse()?,
-                vec![format!("0x2::coin::Coin<{}>", coin_type)
-                    .parse()
-                    .unwrap()],
+                vec![format!("0x2::coin::Coin<{}>", coin_type).parse()?],
             ),
             vec![
                 executable.borrow_mut().into(),
@@ -1636,13 +2605,26 @@ impl MultisigClient {
         intent_args: ParamsArgs,
         actions_args: params::UpgradePackageArgs,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_upgrade_package_with(builder, &mut inputs, intent_args, actions_args)
+            .await
+    }
+
+    pub(crate) async fn request_upgrade_package_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::UpgradePackageArgs,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::UpgradePackage, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         aa::package_upgrade_intents::request_upgrade_package(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
             actions_args.package_name,
@@ -1666,7 +2648,11 @@ impl MultisigClient {
 
         let intent = self.intent(intent_key)?;
         let current_timestamp = self.clock_timestamp().await?;
-        if current_timestamp < *intent.execution_times.first().unwrap() {
+        let first_execution = intent
+            .execution_times
+            .first()
+            .ok_or(anyhow!("Intent {} has no execution time", intent_key))?;
+        if current_timestamp < *first_execution {
             return Err(anyhow!("Intent cannot be executed"));
         }
 
@@ -1724,13 +2710,26 @@ impl MultisigClient {
         intent_args: ParamsArgs,
         actions_args: params::RestrictPolicyArgs,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_restrict_policy_with(builder, &mut inputs, intent_args, actions_args)
+            .await
+    }
+
+    pub(crate) async fn request_restrict_policy_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::RestrictPolicyArgs,
+    ) -> Result<()> {
+        self.check_request_policy(&IntentType::RestrictPolicy, &[])
+            .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         aa::package_upgrade_intents::request_restrict_policy(
             builder,
             auth,
-            multisig.borrow_mut(),
+            inputs.multisig.borrow_mut(),
             params,
             outcome,
             actions_args.package_name,
@@ -1751,7 +2750,11 @@ impl MultisigClient {
 
         let intent = self.intent(intent_key)?;
         let current_timestamp = self.clock_timestamp().await?;
-        if current_timestamp < *intent.execution_times.first().unwrap() {
+        let first_execution = intent
+            .execution_times
+            .first()
+            .ok_or(anyhow!("Intent {} has no execution time", intent_key))?;
+        if current_timestamp < *first_execution {
             return Err(anyhow!("Intent cannot be executed"));
         }
 
@@ -1799,21 +2802,51 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::SpendAndTransferArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_spend_and_transfer_with(
+            builder,
+            &mut inputs,
+            intent_args,
+            actions_args,
+            coin_type,
+        )
+        .await
+    }
+
+    pub(crate) async fn request_spend_and_transfer_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::SpendAndTransferArgs,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let coin_type = coin_type.into_struct_type()?;
+        let values = &actions_args.values;
+        let transfers = values
+            .amounts
+            .iter()
+            .copied()
+            .zip(values.recipients.iter().copied());
+        self.check_request_policy(
+            &IntentType::SpendAndTransfer,
+            &policy::coin_outflows(&coin_type.to_string(), &transfers.collect::<Vec<_>>()),
+        )
+        .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vault_intents".parse()?,
                 "request_spend_and_transfer".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.vault_name.into(),
@@ -1825,28 +2858,47 @@ impl MultisigClient {
         Ok(())
     }
 
+    // executes every execution of the intent whose time has arrived
     pub async fn execute_spend_and_transfer(
         &mut self,
         builder: &mut TransactionBuilder,
         intent_key: &str,
     ) -> Result<()> {
-        let (mut multisig, mut executable, is_last_execution, executions_count) =
-            self.prepare_execute(builder, intent_key).await?;
+        self.execute_spend_and_transfer_partial(builder, intent_key, usize::MAX)
+            .await
+    }
+
+    // executes at most `max_executions` of the executions whose time has arrived
+    pub async fn execute_spend_and_transfer_partial(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+        max_executions: usize,
+    ) -> Result<()> {
+        let (mut multisig, clock, due_executions, is_last_execution, executions_count) = self
+            .prepare_execute_due(builder, intent_key, max_executions)
+            .await?;
 
         let coin_type = self.actions_generic(intent_key).await?;
 
-        for _ in 0..executions_count {
-            builder.move_call(
-                sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
-                    "vault_intents".parse()?,
-                    "execute_spend_and_transfer".parse()?,
-                    vec![coin_type.clone()],
-                ),
-                vec![executable.borrow_mut().into(), multisig.borrow_mut().into()],
-            );
+        for _ in 0..due_executions {
+            let key = self.key_arg(builder, intent_key)?;
+            let mut executable =
+                am::multisig::execute_intent(builder, multisig.borrow_mut(), key, clock.borrow());
+
+            for _ in 0..executions_count {
+                builder.move_call(
+                    sui_transaction_builder::Function::new(
+                        *ACCOUNT_ACTIONS_PACKAGE_ID,
+                        "vault_intents".parse()?,
+                        "execute_spend_and_transfer".parse()?,
+                        vec![coin_type.clone()],
+                    ),
+                    vec![executable.borrow_mut().into(), multisig.borrow_mut().into()],
+                );
+            }
+            ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
         }
-        ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
 
         if is_last_execution {
             let key = self.key_arg(builder, intent_key)?;
@@ -1858,7 +2910,7 @@ impl MultisigClient {
             for _ in 0..executions_count {
                 builder.move_call(
                     sui_transaction_builder::Function::new(
-                        ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                        *ACCOUNT_ACTIONS_PACKAGE_ID,
                         "vault".parse()?,
                         "delete_spend".parse()?,
                         vec![coin_type.clone()],
@@ -1886,7 +2938,7 @@ impl MultisigClient {
         for _ in 0..executions_count {
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "vault".parse()?,
                     "delete_spend".parse()?,
                     vec![coin_type.clone()],
@@ -1900,26 +2952,92 @@ impl MultisigClient {
         Ok(())
     }
 
+    // one spend and transfer intent per coin type, keys are <key_prefix>-1, <key_prefix>-2, ...
+    pub async fn request_vault_payout(
+        &self,
+        builder: &mut TransactionBuilder,
+        key_prefix: &str,
+        description: &str,
+        vault_name: &str,
+        payouts: Vec<(String, u64, Address)>, // (coin_type, amount, recipient)
+    ) -> Result<Vec<String>> {
+        let mut by_coin_type: Vec<(String, Vec<u64>, Vec<Address>)> = Vec::new();
+        for (coin_type, amount, recipient) in payouts {
+            let normalized = normalize_type(&coin_type);
+            match by_coin_type
+                .iter_mut()
+                .find(|(type_, _, _)| *type_ == normalized)
+            {
+                Some((_, amounts, recipients)) => {
+                    amounts.push(amount);
+                    recipients.push(recipient);
+                }
+                None => by_coin_type.push((normalized, vec![amount], vec![recipient])),
+            }
+        }
+
+        let mut batch = self.batch(builder, key_prefix).await?;
+        for (coin_type, amounts, recipients) in by_coin_type {
+            let actions_args = params::SpendAndTransferArgs::new(
+                batch.builder(),
+                vault_name.to_string(),
+                amounts,
+                recipients,
+            );
+            batch
+                .request_spend_and_transfer(
+                    &format!("{} ({})", description, coin_type),
+                    actions_args,
+                    &coin_type,
+                )
+                .await?;
+        }
+
+        Ok(batch.finish())
+    }
+
     pub async fn request_spend_and_vest(
         &self,
         builder: &mut TransactionBuilder,
         intent_args: ParamsArgs,
         actions_args: params::SpendAndVestArgs,
-        coin_type: &str,
+        coin_type: impl IntoStructType,
     ) -> Result<()> {
-        let (mut multisig, auth, params, outcome) =
-            self.prepare_request(builder, intent_args).await?;
+        let mut inputs = self.request_inputs(builder).await?;
+        self.request_spend_and_vest_with(builder, &mut inputs, intent_args, actions_args, coin_type)
+            .await
+    }
+
+    pub(crate) async fn request_spend_and_vest_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &mut RequestInputs,
+        intent_args: ParamsArgs,
+        actions_args: params::SpendAndVestArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<()> {
+        let coin_type = coin_type.into_struct_type()?;
+        let values = &actions_args.values;
+        self.check_request_policy(
+            &IntentType::SpendAndVest,
+            &policy::coin_outflows(
+                &coin_type.to_string(),
+                &[(values.coin_amount, values.recipient)],
+            ),
+        )
+        .await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, inputs, intent_args);
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vault_intents".parse()?,
                 "request_spend_and_vest".parse()?,
-                vec![coin_type.parse()?],
+                vec![coin_type.into_struct_type()?.into()],
             ),
             vec![
                 auth.into(),
-                multisig.borrow_mut().into(),
+                inputs.multisig.borrow_mut().into(),
                 params.into(),
                 outcome.into(),
                 actions_args.vault_name.into(),
@@ -1945,7 +3063,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vault_intents".parse()?,
                 "execute_spend_and_vest".parse()?,
                 vec![coin_type.clone()],
@@ -1963,7 +3081,7 @@ impl MultisigClient {
 
             builder.move_call(
                 sui_transaction_builder::Function::new(
-                    ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                    *ACCOUNT_ACTIONS_PACKAGE_ID,
                     "vault".parse()?,
                     "delete_spend".parse()?,
                     vec![coin_type],
@@ -1989,7 +3107,7 @@ impl MultisigClient {
 
         builder.move_call(
             sui_transaction_builder::Function::new(
-                ACCOUNT_ACTIONS_PACKAGE.parse()?,
+                *ACCOUNT_ACTIONS_PACKAGE_ID,
                 "vault".parse()?,
                 "delete_spend".parse()?,
                 vec![coin_type],
@@ -2002,12 +3120,377 @@ impl MultisigClient {
         Ok(())
     }
 
+    // executes mint/spend and transfer intents (e.g. from a chunked request) in as many
+    // transactions as needed, each running at most policy.max_executions_per_tx executions
+    // and staying under policy.max_commands_per_tx, gas is paid with several coins if needed
+    pub async fn execute_chunked<S: SuiSigner>(
+        &mut self,
+        intent_keys: &[String],
+        signer: &S,
+        policy: ChunkPolicy,
+    ) -> Result<ExecutionGroup> {
+        let sender = self.user().ok_or(anyhow!("User not loaded"))?.address;
+
+        let mut group = ExecutionGroup::default();
+        for intent_key in intent_keys {
+            let max_executions = self.max_executions_per_tx(intent_key, &policy).await?;
+            // read before the intent is removed by its last execution
+            let outflows = policy::outflows(self.intent_mut(intent_key)?.get_actions_args().await?);
+            // the intent is removed from the multisig after its last execution
+            while self.intent(intent_key).is_ok() && self.due_executions(intent_key).await? > 0 {
+                let executions = self.due_executions(intent_key).await?.min(max_executions);
+                let intent_type = IntentType::try_from(self.intent(intent_key)?.type_.as_str())?;
+                let mut builder =
+                    utils::init_tx_with_budget(&self.sui_client, sender, policy.gas_budget).await?;
+                match intent_type {
+                    IntentType::MintAndTransfer => {
+                        self.execute_mint_and_transfer_partial(
+                            &mut builder,
+                            intent_key,
+                            max_executions,
+                        )
+                        .await?
+                    }
+                    IntentType::SpendAndTransfer => {
+                        self.execute_spend_and_transfer_partial(
+                            &mut builder,
+                            intent_key,
+                            max_executions,
+                        )
+                        .await?
+                    }
+                    _ => return Err(anyhow!("Intent {} can't be executed in chunks", intent_key)),
+                }
+
+                let tx = builder.finish()?;
+                let signature = signer.sign_transaction(&tx)?;
+                let effects = utils::submit_tx(&self.sui_client, &tx, vec![signature])
+                    .await
+                    .map_err(|e| {
+                        e.context(format!(
+                            "Chunked execution stopped after {} transaction(s): {:?}",
+                            group.digests.len(),
+                            group.digests
+                        ))
+                    })?;
+                group.digests.push(tx.digest());
+                let receipt = ExecutionReceipt::from_effects(
+                    &self.sui_client,
+                    intent_key,
+                    &outflows.repeat(executions),
+                    tx.digest(),
+                    &effects,
+                )
+                .await
+                .map_err(|e| {
+                    e.context(format!(
+                        "Receipt of {} failed, {} transaction(s) sent: {:?}",
+                        tx.digest(),
+                        group.digests.len(),
+                        group.digests
+                    ))
+                })?;
+                group.effects.push(effects);
+                group.receipts.push(receipt);
+                self.record_spends(&outflows.repeat(executions))
+                    .await
+                    .map_err(|e| {
+                        e.context(format!(
+                            "Spends of {} not recorded, {} transaction(s) sent: {:?}",
+                            tx.digest(),
+                            group.digests.len(),
+                            group.digests
+                        ))
+                    })?;
+
+                // the executions left, the intent is removed after the last one
+                self.refresh_intent(intent_key).await?;
+            }
+        }
+
+        Ok(group)
+    }
+
+    // approves the intents allowed by the rules every interval, forever,
+    // on_decision receives each decision (e.g. to log ApprovalDecision::to_json) and on_error the
+    // failed polls, retried at the next tick
+    pub async fn run_auto_approver<S: SuiSigner>(
+        &mut self,
+        signer: &S,
+        rules: Policy,
+        interval: Duration,
+        mut on_decision: impl FnMut(&ApprovalDecision),
+        mut on_error: impl FnMut(anyhow::Error),
+    ) -> Result<()> {
+        let mut approver = AutoApprover::new(rules);
+        loop {
+            match approver.poll(self, signer).await {
+                Ok(decisions) => decisions.iter().for_each(&mut on_decision),
+                Err(e) => on_error(e),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    // each execution runs one command per transfer plus execute and confirm,
+    // the last one also deletes the actions (2 commands per transfer)
+    async fn max_executions_per_tx(
+        &mut self,
+        intent_key: &str,
+        policy: &ChunkPolicy,
+    ) -> Result<usize> {
+        let transfers = self.intent_mut(intent_key)?.get_executions_count().await?;
+        let reserved = 2 * transfers + 8;
+        let by_commands = policy.max_commands_per_tx.saturating_sub(reserved) / (transfers + 2);
+        Ok(policy.max_executions_per_tx.min(by_commands).max(1))
+    }
+
+    // === Validation ===
+
+    // amount is the total minted by the intent (all actions and executions)
+    pub fn validate_mint(&self, coin_type: &str, amount: u64) -> Result<()> {
+        let currency = self
+            .dynamic_fields()
+            .ok_or(anyhow!("Dynamic fields not loaded"))?
+            .currency(coin_type)
+            .ok_or(MultisigError::CurrencyNotFound {
+                coin_type: coin_type.to_string(),
+            })?;
+
+        if !currency.can_mint {
+            return Err(MultisigError::MintDisabled {
+                coin_type: coin_type.to_string(),
+            }
+            .into());
+        }
+        if let Some(max_supply) = currency.max_supply {
+            if currency.current_supply.saturating_add(amount) > max_supply {
+                return Err(MultisigError::MaxSupplyExceeded {
+                    coin_type: coin_type.to_string(),
+                    max_supply,
+                    current_supply: currency.current_supply,
+                    amount,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_close_vault(&self, vault_name: &str) -> Result<()> {
+        let vault = self
+            .dynamic_fields()
+            .ok_or(anyhow!("Dynamic fields not loaded"))?
+            .vaults
+            .get(vault_name)
+            .ok_or(MultisigError::VaultNotFound {
+                vault_name: vault_name.to_string(),
+            })?;
+
+        let mut balances = vault
+            .coins
+            .iter()
+            .filter(|(_, amount)| **amount > 0)
+            .map(|(coin_type, amount)| (coin_type.clone(), *amount))
+            .collect::<Vec<_>>();
+        if !balances.is_empty() {
+            balances.sort();
+            return Err(MultisigError::VaultNotEmpty {
+                vault_name: vault_name.to_string(),
+                balances,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    // amount is the total spent by the intent (all actions and executions)
+    pub fn validate_spend(&self, vault_name: &str, coin_type: &str, amount: u64) -> Result<()> {
+        let vault = self
+            .dynamic_fields()
+            .ok_or(anyhow!("Dynamic fields not loaded"))?
+            .vaults
+            .get(vault_name)
+            .ok_or(MultisigError::VaultNotFound {
+                vault_name: vault_name.to_string(),
+            })?;
+
+        let balance = vault
+            .coins
+            .get(&normalize_type(coin_type))
+            .copied()
+            .unwrap_or_default();
+        if balance < amount {
+            return Err(MultisigError::InsufficientVaultBalance {
+                vault_name: vault_name.to_string(),
+                coin_type: coin_type.to_string(),
+                balance,
+                amount,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    // checks the objects of a WithdrawAndTransfer before requesting it (done by the request
+    // methods), instead of letting the intent fail at execution
+    pub fn validate_withdrawals(
+        &self,
+        object_ids: &[ObjectId],
+        recipients: &[Address],
+    ) -> Result<()> {
+        if object_ids.len() != recipients.len() {
+            return Err(MultisigError::RecipientsMismatch {
+                objects: object_ids.len(),
+                recipients: recipients.len(),
+            }
+            .into());
+        }
+
+        let multisig = self.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+        let owned_objects = self
+            .owned_objects()
+            .ok_or(anyhow!("Owned objects not loaded"))?;
+
+        let mut invalid = Vec::new();
+        for (i, id) in object_ids.iter().enumerate() {
+            let id = *id.as_address();
+            let issue = if object_ids[..i]
+                .iter()
+                .any(|other| *other.as_address() == id)
+            {
+                Some(WithdrawalIssue::Duplicated)
+            } else if multisig.locked_objects.contains(&id) {
+                Some(WithdrawalIssue::Locked)
+            } else if owned_objects.get_type_by_id(id).is_none() {
+                Some(WithdrawalIssue::NotOwned)
+            } else {
+                None
+            };
+            if let Some(issue) = issue {
+                invalid.push((id, issue));
+            }
+        }
+
+        if !invalid.is_empty() {
+            return Err(MultisigError::InvalidWithdrawals { invalid }.into());
+        }
+        Ok(())
+    }
+
+    // checks a request against the policy before building it, does nothing without policy
+    pub async fn check_request_policy(
+        &self,
+        intent_type: &IntentType,
+        outflows: &[Outflow],
+    ) -> Result<()> {
+        let Some(policy) = &self.policy else {
+            return Ok(());
+        };
+        policy.check(intent_type, outflows, self.clock_timestamp().await?)
+    }
+
+    // checks the next executions of the intent against the policy, does nothing without policy
+    pub async fn check_policy(&mut self, intent_key: &str, executions: usize) -> Result<()> {
+        if self.policy.is_none() {
+            return Ok(());
+        }
+        let intent_type = IntentType::try_from(self.intent(intent_key)?.type_.as_str())?;
+        let outflows = policy::outflows(self.intent_mut(intent_key)?.get_actions_args().await?)
+            .repeat(executions);
+        let now = self.clock_timestamp().await?;
+        match &self.policy {
+            Some(policy) => policy.check(&intent_type, &outflows, now),
+            None => Ok(()),
+        }
+    }
+
+    // counts executed outflows against the policy limits, does nothing without policy
+    pub async fn record_spends(&mut self, outflows: &[Outflow]) -> Result<()> {
+        if self.policy.is_none() {
+            return Ok(());
+        }
+        let now = self.clock_timestamp().await?;
+        match &mut self.policy {
+            Some(policy) => policy.record(outflows, now),
+            None => Ok(()),
+        }
+    }
+
     // === Getters ===
 
     pub fn sui(&self) -> &Client {
         &self.sui_client
     }
 
+    // the Fees object is shared by all multisigs and rarely changes, see refresh_fees
+    pub async fn fees(&self) -> Result<&Fees> {
+        self.fees
+            .get_or_try_init(|| async {
+                let fee_obj = utils::get_object(&self.sui_client, *FEE_OBJECT_ID).await?;
+                if let ObjectData::Struct(obj) = fee_obj.data() {
+                    let fees: am::fees::Fees =
+                        utils::decode(obj.contents(), Some(&obj.object_type().to_string()))?;
+                    Ok(Fees {
+                        amount: fees.amount,
+                        recipient: fees.recipient,
+                    })
+                } else {
+                    Err(anyhow!("Fee object not a struct"))
+                }
+            })
+            .await
+    }
+
+    // refetches the Fees object, e.g. after the protocol changed the creation fee
+    pub async fn refresh_fees(&mut self) -> Result<&Fees> {
+        self.fees = OnceCell::new();
+        self.fees().await
+    }
+
+    pub fn coin_registry(&self) -> &CoinRegistry {
+        &self.coin_registry
+    }
+
+    pub fn coin_registry_mut(&mut self) -> &mut CoinRegistry {
+        &mut self.coin_registry
+    }
+
+    // "USDC" to its full coin type on the network of the client
+    pub fn resolve_coin(&self, coin: &str) -> Result<String> {
+        self.coin_registry.resolve(coin)
+    }
+
+    // "alice.sui" or "@alice" to the address it targets, addresses are returned as is
+    pub async fn resolve_address(&self, name_or_address: &str) -> Result<Address> {
+        self.names.resolve(&self.sui_client, name_or_address).await
+    }
+
+    pub async fn resolve_addresses(
+        &self,
+        names_or_addresses: &[impl AsRef<str>],
+    ) -> Result<Vec<Address>> {
+        self.names
+            .resolve_all(&self.sui_client, names_or_addresses)
+            .await
+    }
+
+    pub async fn coin_metadata(&self, coin_type: &str) -> Result<CoinMetadata> {
+        self.coin_metadata
+            .get_or_fetch(&self.sui_client, coin_type)
+            .await
+    }
+
+    // amount with decimals and symbol, e.g. "1.5 SUI"
+    pub async fn format_amount(&self, coin_type: &str, amount: u64) -> String {
+        self.coin_metadata
+            .format_amount(&self.sui_client, coin_type, amount)
+            .await
+    }
+
     pub fn user(&self) -> Option<&User> {
         self.user.as_ref()
     }
@@ -2047,12 +3530,37 @@ impl MultisigClient {
             .ok_or(anyhow!("Intent not found"))
     }
 
+    // actions with the values depending on the multisig state filled in,
+    // e.g. the unverified deps flag after a toggle if executed next
+    pub async fn intent_actions(&mut self, key: &str) -> Result<IntentActions> {
+        let mut actions = self.intent_mut(key)?.get_actions_args().await?.clone();
+        if let IntentActions::ToggleUnverifiedAllowed(fields) = &mut actions {
+            let multisig = self.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+            fields.will_become = Some(!multisig.unverified_deps_allowed);
+        }
+        Ok(actions)
+    }
+
+    // intent, actions, approvers and the transactions that created, approved and executed it,
+    // the executed intents are only known until the next refresh
+    pub async fn export_approval_record(&mut self, intent_key: &str) -> Result<serde_json::Value> {
+        self.intent_mut(intent_key)?.get_actions_args().await?;
+        let field_id = self
+            .intents()
+            .ok_or(anyhow!("Intents not loaded"))?
+            .field_id(intent_key)?;
+        let transactions = utils::get_transactions_changing(&self.sui_client, field_id).await?;
+
+        let multisig = self.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+        Ok(json::approval_record(
+            multisig,
+            self.intent(intent_key)?,
+            &transactions,
+        ))
+    }
+
     pub async fn actions_generic(&mut self, key: &str) -> Result<TypeTag> {
-        self.intent_mut(key)
-            .unwrap()
-            .get_actions_args()
-            .await?
-            .asset_type()
+        self.intent_mut(key)?.get_actions_args().await?.asset_type()
     }
 
     pub fn owned_objects(&self) -> Option<&OwnedObjects> {
@@ -2063,22 +3571,91 @@ impl MultisigClient {
         self.multisig.as_ref()?.dynamic_fields.as_ref()
     }
 
+    pub fn intent_defaults(&self) -> IntentDefaults {
+        self.intent_defaults
+    }
+
+    pub fn policy(&self) -> Option<&Policy> {
+        self.policy.as_ref()
+    }
+
+    pub fn policy_mut(&mut self) -> Option<&mut Policy> {
+        self.policy.as_mut()
+    }
+
     // === Helpers ===
 
-    async fn obj(&self, id: Address) -> Result<Input> {
-        utils::get_object_as_input(&self.sui_client, id).await
+    async fn refresh_all(&mut self, incremental: bool) -> Result<()> {
+        if let Some(multisig) = self.multisig.as_mut() {
+            if incremental {
+                multisig.refresh_incremental().await?;
+            } else {
+                multisig.refresh().await?;
+            }
+        }
+        if let Some(user) = self.user.as_mut() {
+            user.refresh().await?;
+        }
+        Ok(())
     }
 
-    pub async fn clock_timestamp(&self) -> Result<u64> {
-        let clock_object =
-            utils::get_object(&self.sui_client, CLOCK_OBJECT.parse()?).await?;
-        if let ObjectData::Struct(obj) = clock_object.data() {
-            let clock: sui::clock::Clock = bcs::from_bytes(obj.contents())
-                .map_err(|e| anyhow!("Failed to parse clock object: {}", e))?;
-            Ok(clock.timestamp_ms)
-        } else {
-            Err(anyhow!("Clock object data is missing"))
+    // counts the transport errors of the current endpoint and switches to the next one if needed,
+    // done by the refreshes, the callers of the other methods pass their results
+    pub fn record_result<T>(&mut self, result: &Result<T>) -> Result<()> {
+        let Some(endpoints) = self.endpoints.as_mut() else {
+            return Ok(());
+        };
+        if endpoints.record(result) {
+            self.connect()?;
         }
+        Ok(())
+    }
+
+    // uses the current endpoint in the client and the loaded objects
+    fn connect(&mut self) -> Result<String> {
+        let endpoints = self
+            .endpoints
+            .as_ref()
+            .ok_or(anyhow!("Client created without a list of endpoints"))?;
+        let url = endpoints.current().to_string();
+        self.sui_client = Arc::new(endpoints.client()?);
+        self.chain_id = OnceCell::new();
+        if let Some(multisig) = self.multisig.as_mut() {
+            multisig.set_sui_client(self.sui_client.clone());
+        }
+        if let Some(user) = self.user.as_mut() {
+            user.sui_client = self.sui_client.clone();
+        }
+        Ok(url)
+    }
+
+    // falls back to the node (and refreshes the cache) if the object isn't in OwnedObjects
+    pub async fn owned_object_type(&mut self, id: Address) -> Result<String> {
+        if let Some(type_) = self.owned_objects().and_then(|o| o.get_type_by_id(id)) {
+            return Ok(type_);
+        }
+
+        let object = utils::get_object(&self.sui_client, id).await?;
+        let type_ = match object.data() {
+            ObjectData::Struct(obj) => obj.object_type().to_string(),
+            ObjectData::Package(_) => return Err(anyhow!("Object {} is a package", id)),
+        };
+        if let Some(owned_objects) = self
+            .multisig
+            .as_mut()
+            .and_then(|multisig| multisig.owned_objects.as_mut())
+        {
+            owned_objects.refresh().await?;
+        }
+        Ok(type_)
+    }
+
+    async fn obj(&self, id: Address, kind: InputKind) -> Result<Input> {
+        utils::get_object_as_input(&self.sui_client, id, kind).await
+    }
+
+    pub async fn clock_timestamp(&self) -> Result<u64> {
+        utils::clock_timestamp(&self.sui_client).await
     }
 
     pub fn pure_arg<Pure: serde::Serialize + MoveType>(
@@ -2095,7 +3672,7 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         id: Address,
     ) -> Result<Arg<Obj>> {
-        let object_input = self.obj(id).await?;
+        let object_input = self.obj(id, InputKind::Owned).await?;
         let object_arg = builder.input(object_input).into();
         Ok(object_arg)
     }
@@ -2105,18 +3682,27 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         id: Address,
     ) -> Result<Argument> {
-        let object_input = self.obj(id).await?;
+        let object_input = self.obj(id, InputKind::Owned).await?;
         let object_arg = builder.input(object_input);
         Ok(object_arg)
     }
 
+    // the object must have been sent to the multisig
     pub async fn receive_argument(
         &self,
         builder: &mut TransactionBuilder,
         id: Address,
     ) -> Result<Argument> {
-        let object_input = self.obj(id).await?;
-        let object_arg = builder.input(object_input.with_receiving_kind());
+        let object = utils::get_object(&self.sui_client, id).await?;
+        if *object.owner() != Owner::Address(self.multisig_id()?) {
+            return Err(MultisigError::NotOwnedByAccount {
+                id,
+                owner: *object.owner(),
+            }
+            .into());
+        }
+        let object_input = utils::object_as_input(&object, InputKind::Receiving)?;
+        let object_arg = builder.input(object_input);
         Ok(object_arg)
     }
 
@@ -2125,8 +3711,8 @@ impl MultisigClient {
         builder: &mut TransactionBuilder,
         id: Address,
     ) -> Result<Argument> {
-        let object_input = self.obj(id).await?;
-        let object_arg = builder.input(object_input.by_mut());
+        let object_input = self.obj(id, InputKind::Shared { mutable: true }).await?;
+        let object_arg = builder.input(object_input);
         Ok(object_arg)
     }
 
@@ -2144,8 +3730,10 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
     ) -> Result<Arg<sui::clock::Clock>> {
-        let clock_input = self.obj(CLOCK_OBJECT.parse()?).await?;
-        let clock = builder.input(clock_input.by_ref()).into();
+        // the clock is created at genesis, its initial shared version is always 1
+        let clock = builder
+            .input(Input::shared(*CLOCK_OBJECT_ID, 1, false))
+            .into();
         Ok(clock)
     }
 
@@ -2153,8 +3741,10 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
     ) -> Result<Arg<ae::extensions::Extensions>> {
-        let extensions_input = self.obj(EXTENSIONS_OBJECT.parse()?).await?;
-        let extensions = builder.input(extensions_input.by_ref()).into();
+        let extensions_input = self
+            .obj(*EXTENSIONS_OBJECT_ID, InputKind::Shared { mutable: false })
+            .await?;
+        let extensions = builder.input(extensions_input).into();
         Ok(extensions)
     }
 
@@ -2162,8 +3752,10 @@ impl MultisigClient {
         &self,
         builder: &mut TransactionBuilder,
     ) -> Result<Arg<ap::account::Account<am::multisig::Multisig>>> {
-        let ms_input = self.obj(self.multisig_id()?).await?;
-        let multisig = builder.input(ms_input.by_mut()).into();
+        let ms_input = self
+            .obj(self.multisig_id()?, InputKind::Shared { mutable: true })
+            .await?;
+        let multisig = builder.input(ms_input).into();
         Ok(multisig)
     }
 
@@ -2177,21 +3769,66 @@ impl MultisigClient {
         Arg<ap::intents::Params>,
         Arg<am::multisig::Approvals>,
     )> {
+        let inputs = self.request_inputs(builder).await?;
+        let (auth, params, outcome) = self.prepare_request_with(builder, &inputs, params_args);
+
+        Ok((inputs.multisig, auth, params, outcome))
+    }
+
+    // members of a config multisig intent that are not in the current config
+    pub async fn added_members(&mut self, intent_key: &str) -> Result<Vec<Address>> {
+        let current = self
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?
+            .config
+            .members
+            .iter()
+            .map(|member| member.address.clone())
+            .collect::<Vec<_>>();
+
+        match self.intent_mut(intent_key)?.get_actions_args().await? {
+            IntentActions::ConfigMultisig(fields) => Ok(fields
+                .members
+                .iter()
+                .map(|(address, _, _)| *address)
+                .filter(|address| !current.contains(&address.to_string()))
+                .collect()),
+            _ => Err(anyhow!(
+                "Intent {} is not a config multisig intent",
+                intent_key
+            )),
+        }
+    }
+
+    pub async fn request_inputs(&self, builder: &mut TransactionBuilder) -> Result<RequestInputs> {
         let multisig = self.multisig_arg(builder).await?;
         let clock = self.clock_arg(builder).await?;
 
-        let auth = am::multisig::authenticate(builder, multisig.borrow());
+        Ok(RequestInputs { multisig, clock })
+    }
+
+    pub fn prepare_request_with(
+        &self,
+        builder: &mut TransactionBuilder,
+        inputs: &RequestInputs,
+        params_args: ParamsArgs,
+    ) -> (
+        Arg<ap::account::Auth>,
+        Arg<ap::intents::Params>,
+        Arg<am::multisig::Approvals>,
+    ) {
+        let auth = am::multisig::authenticate(builder, inputs.multisig.borrow());
         let params = ap::intents::new_params(
             builder,
             params_args.key,
             params_args.description,
             params_args.execution_times,
             params_args.expiration_time,
-            clock.borrow(),
+            inputs.clock.borrow(),
         );
         let outcome = am::multisig::empty_outcome(builder);
 
-        Ok((multisig, auth, params, outcome))
+        (auth, params, outcome)
     }
 
     pub async fn prepare_execute(
@@ -2208,14 +3845,15 @@ impl MultisigClient {
         let clock = self.clock_arg(builder).await?;
         let key = self.key_arg(builder, intent_key)?;
 
+        self.check_policy(intent_key, 1).await?;
         let executions_count = self.intent_mut(intent_key)?.get_executions_count().await?;
 
-        let intent = self.intent(intent_key)?;
-        let current_timestamp = self.clock_timestamp().await?;
-        if current_timestamp < *intent.execution_times.first().unwrap() {
+        // an intent whose first execution time is 0 is executable at any time, no need for the clock
+        let executable_now = self.intent(intent_key)?.execution_times.first() == Some(&0);
+        if !executable_now && self.due_executions(intent_key).await? == 0 {
             return Err(anyhow!("Intent cannot be executed"));
         }
-        let is_last_execution = intent.execution_times.len() == 1;
+        let is_last_execution = self.intent(intent_key)?.execution_times.len() == 1;
 
         let executable =
             am::multisig::execute_intent(builder, multisig.borrow_mut(), key, clock.borrow());
@@ -2223,6 +3861,50 @@ impl MultisigClient {
         Ok((multisig, executable, is_last_execution, executions_count))
     }
 
+    // same as prepare_execute but lets the caller run several executions in the same PTB
+    pub async fn prepare_execute_due(
+        &mut self,
+        builder: &mut TransactionBuilder,
+        intent_key: &str,
+        max_executions: usize,
+    ) -> Result<(
+        Arg<ap::account::Account<am::multisig::Multisig>>,
+        Arg<sui::clock::Clock>,
+        usize,
+        bool,
+        usize,
+    )> {
+        let multisig = self.multisig_arg(builder).await?;
+        let clock = self.clock_arg(builder).await?;
+
+        let executions_count = self.intent_mut(intent_key)?.get_executions_count().await?;
+        let due_executions = self.due_executions(intent_key).await?.min(max_executions);
+        if due_executions == 0 {
+            return Err(anyhow!("Intent cannot be executed"));
+        }
+        self.check_policy(intent_key, due_executions).await?;
+        let is_last_execution = self.intent(intent_key)?.execution_times.len() == due_executions;
+
+        Ok((
+            multisig,
+            clock,
+            due_executions,
+            is_last_execution,
+            executions_count,
+        ))
+    }
+
+    // number of execution times that have been reached and can be executed now
+    pub async fn due_executions(&self, intent_key: &str) -> Result<usize> {
+        let intent = self.intent(intent_key)?;
+        let current_timestamp = self.clock_timestamp().await?;
+        Ok(intent
+            .execution_times
+            .iter()
+            .filter(|time| **time <= current_timestamp)
+            .count())
+    }
+
     pub async fn prepare_delete(
         &mut self,
         builder: &mut TransactionBuilder,
@@ -2271,70 +3953,6 @@ impl fmt::Debug for MultisigClient {
     }
 }
 
-// #[macro_export]
-// macro_rules! define_move_type {
-//     (
-//         $move_type:ident,
-//         $full_path:expr $(,)?
-//     ) => {
-//         use move_types::{MoveType, StructTag, TypeTag};
-
-//         #[derive(serde::Serialize, serde::Deserialize)]
-//         pub struct $move_type {}
-
-//         impl MoveType for $move_type {
-//             fn type_() -> TypeTag {
-//                 let parts: Vec<&str> = $full_path.split("::").collect();
-//                 if parts.len() != 3 {
-//                     panic!(
-//                         "Invalid coin type path: {}. Expected format: address::module::name",
-//                         $full_path
-//                     );
-//                 }
-
-//                 let address = parts[0];
-//                 let module = parts[1];
-//                 let name = parts[2];
-
-//                 TypeTag::Struct(Box::new(StructTag {
-//                     address: address.parse()?,
-//                     module: module.parse()?,
-//                     name: name.parse()?,
-//                     type_params: vec![],
-//                 }))
-//             }
-//         }
-//     };
-// }
-
-// #[macro_export]
-// macro_rules! define_move_object {
-//     (
-//         $move_object_name:ident,
-//         $id:expr,
-//         $full_path:expr $(,)?
-//     ) => {
-//         use move_types::{Key, MoveStruct, MoveType, ObjectId, StructTag, TypeTag};
-
-//         #[derive(serde::Serialize, serde::Deserialize)]
-//         pub struct $move_object_name {
-//             pub id: ObjectId,
-//         }
-
-//         impl MoveStruct for $move_object_name {
-//             fn struct_type() -> StructTag {
-//                 $full_path.parse()?
-//             }
-//         }
-
-//         impl Key for $move_object_name {
-//             fn id(&self) -> &ObjectId {
-//                 &self.id
-//             }
-//         }
-//     };
-// }
-
 //**************************************************************************************************//
 // Tests                                                                              //
 //**************************************************************************************************//
@@ -2345,9 +3963,23 @@ mod tests {
     use base64ct::{Base64, Encoding};
     use sui_crypto::ed25519::Ed25519PrivateKey;
     use sui_crypto::SuiSigner;
-    use sui_graphql_client::{Client, PaginationFilter};
+    use sui_graphql_client::Client;
     use sui_sdk_types::{ExecutionStatus, ObjectIn, ObjectOut, TransactionEffects};
 
+    #[test]
+    fn constant_ids_are_valid() {
+        for id in [
+            &ACCOUNT_MULTISIG_PACKAGE_ID,
+            &ACCOUNT_PROTOCOL_PACKAGE_ID,
+            &ACCOUNT_ACTIONS_PACKAGE_ID,
+            &EXTENSIONS_OBJECT_ID,
+            &FEE_OBJECT_ID,
+            &CLOCK_OBJECT_ID,
+        ] {
+            LazyLock::force(id);
+        }
+    }
+
     /// Helper function to setup a transaction builder with a gas object and a sender address.
     async fn init_tx(sui_client: &Client) -> (Ed25519PrivateKey, TransactionBuilder) {
         let pk = Ed25519PrivateKey::new(
@@ -2357,31 +3989,7 @@ mod tests {
         );
         let address = pk.public_key().derive_address();
 
-        let mut builder = TransactionBuilder::new();
-
-        let gas_coin = sui_client
-            .coins(
-                address,
-                Some("0x2::coin::Coin<0x2::sui::SUI>"),
-                PaginationFilter::default(),
-            )
-            .await
-            .unwrap()
-            .data()
-            .first()
-            .unwrap()
-            .to_owned();
-        let gas_input: Input = (&sui_client
-            .object(gas_coin.id().to_owned().into(), None)
-            .await
-            .unwrap()
-            .unwrap())
-            .into();
-
-        builder.add_gas_objects(vec![gas_input.with_owned_kind()]);
-        builder.set_gas_budget(100000000);
-        builder.set_gas_price(1000);
-        builder.set_sender(address);
+        let builder = utils::init_tx(sui_client, address).await.unwrap();
 
         (pk, builder)
     }
@@ -2440,4 +4048,64 @@ mod tests {
         assert!(client.intents().is_some());
         assert!(client.owned_objects().is_some());
     }
+
+    #[tokio::test]
+    async fn request_config_multisig_ptb() {
+        let client = MultisigClient::new_testnet();
+        let mut builder = test_utils::offline_builder();
+        let mut inputs = test_utils::request_inputs(&mut builder);
+        let params = test_utils::params(&mut builder, "config_multisig");
+        let args = params::ConfigMultisigArgs::new(
+            &mut builder,
+            vec![Address::new([0xa; 32]), Address::new([0xb; 32])],
+            vec![1, 1],
+            vec![vec![], vec![]],
+            2,
+            vec![],
+            vec![],
+        );
+        client
+            .request_config_multisig_with(&mut builder, &mut inputs, params, args)
+            .await
+            .unwrap();
+        test_utils::assert_ptb_snapshot("request_config_multisig", builder);
+    }
+
+    #[tokio::test]
+    async fn request_toggle_unverified_allowed_ptb() {
+        let client = MultisigClient::new_testnet();
+        let mut builder = test_utils::offline_builder();
+        let mut inputs = test_utils::request_inputs(&mut builder);
+        let params = test_utils::params(&mut builder, "toggle");
+        client
+            .request_toggle_unverified_allowed_with(&mut builder, &mut inputs, params)
+            .await
+            .unwrap();
+        test_utils::assert_ptb_snapshot("request_toggle_unverified_allowed", builder);
+    }
+
+    #[tokio::test]
+    async fn request_spend_and_transfer_ptb() {
+        let client = MultisigClient::new_testnet();
+        let mut builder = test_utils::offline_builder();
+        let mut inputs = test_utils::request_inputs(&mut builder);
+        let params = test_utils::params(&mut builder, "payroll");
+        let args = params::SpendAndTransferArgs::new(
+            &mut builder,
+            "treasury".to_string(),
+            vec![10, 20],
+            vec![Address::new([0xa; 32]), Address::new([0xb; 32])],
+        );
+        client
+            .request_spend_and_transfer_with(
+                &mut builder,
+                &mut inputs,
+                params,
+                args,
+                "0x2::sui::SUI",
+            )
+            .await
+            .unwrap();
+        test_utils::assert_ptb_snapshot("request_spend_and_transfer", builder);
+    }
 }
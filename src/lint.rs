@@ -0,0 +1,231 @@
+use std::fmt;
+use sui_sdk_types::Address;
+
+use crate::multisig::Multisig;
+use crate::proposals::simulation::simulate_config;
+
+// consistency checks of a loaded multisig for ops tooling, see Multisig::lint.
+// the checks needing data that isn't loaded are skipped
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    // a member has a role missing from config.roles, it counts for no threshold
+    UndefinedRole {
+        member: String,
+        role: String,
+    },
+    ThresholdUnreachable {
+        name: String,
+        threshold: u64,
+        total_weight: u64,
+    },
+    // not in the Extensions object while unverified deps aren't allowed
+    UnverifiedDep {
+        name: String,
+        addr: Address,
+        version: u64,
+    },
+    // locked object withdrawn by no intent, it can't be withdrawn anymore
+    UnreferencedLock {
+        id: Address,
+    },
+    EmptyVaultEntry {
+        vault: String,
+        coin_type: String,
+    },
+}
+
+// verified: packages from utils::verified_extensions, the deps aren't checked if None
+pub fn lint(multisig: &Multisig, verified: Option<&[(String, Address, u64)]>) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+
+    let mut members = multisig.config.members.iter().collect::<Vec<_>>();
+    members.sort_by(|a, b| a.address.cmp(&b.address));
+    for member in members {
+        for role in &member.roles {
+            if !multisig.config.roles.contains_key(role) {
+                warnings.push(LintWarning::UndefinedRole {
+                    member: member.address.clone(),
+                    role: role.clone(),
+                });
+            }
+        }
+    }
+
+    let simulation = simulate_config(&multisig.config.to_fields());
+    warnings.extend(
+        std::iter::once(&simulation.global)
+            .chain(&simulation.roles)
+            .filter(|report| !report.achievable)
+            .map(|report| LintWarning::ThresholdUnreachable {
+                name: report.name.clone(),
+                threshold: report.threshold,
+                total_weight: report.total_weight,
+            }),
+    );
+
+    if let Some(verified) = verified.filter(|_| !multisig.unverified_deps_allowed) {
+        warnings.extend(
+            multisig
+                .deps
+                .iter()
+                .filter(|dep| {
+                    !verified.iter().any(|(name, addr, version)| {
+                        *name == dep.name && *addr == dep.addr && *version == dep.version
+                    })
+                })
+                .map(|dep| LintWarning::UnverifiedDep {
+                    name: dep.name.clone(),
+                    addr: dep.addr,
+                    version: dep.version,
+                }),
+        );
+    }
+
+    // every intent's actions are needed to tell that a lock is unreferenced
+    if let Some(intents) = &multisig.intents {
+        let withdrawn = intents
+            .intents
+            .values()
+            .map(|intent| {
+                intent
+                    .actions_args
+                    .as_ref()
+                    .map(|actions| actions.withdrawn_objects())
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(withdrawn) = withdrawn {
+            warnings.extend(
+                multisig
+                    .locked_objects
+                    .iter()
+                    .filter(|id| !withdrawn.iter().flatten().any(|other| other == *id))
+                    .map(|id| LintWarning::UnreferencedLock { id: *id }),
+            );
+        }
+    }
+
+    if let Some(dynamic_fields) = &multisig.dynamic_fields {
+        let mut empty = dynamic_fields
+            .vaults
+            .iter()
+            .flat_map(|(vault, content)| {
+                content
+                    .coins
+                    .iter()
+                    .filter(|(_, balance)| **balance == 0)
+                    .map(move |(coin_type, _)| LintWarning::EmptyVaultEntry {
+                        vault: vault.clone(),
+                        coin_type: coin_type.clone(),
+                    })
+            })
+            .collect::<Vec<_>>();
+        empty.sort_by_key(|warning| warning.to_string());
+        warnings.extend(empty);
+    }
+
+    warnings
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintWarning::UndefinedRole { member, role } => {
+                write!(
+                    f,
+                    "member {} has role {} which is not defined",
+                    member, role
+                )
+            }
+            LintWarning::ThresholdUnreachable {
+                name,
+                threshold,
+                total_weight,
+            } => write!(
+                f,
+                "{} threshold {} cannot be reached (total weight {})",
+                name, threshold, total_weight
+            ),
+            LintWarning::UnverifiedDep {
+                name,
+                addr,
+                version,
+            } => write!(
+                f,
+                "dep {} ({} v{}) is not verified while unverified deps are not allowed",
+                name, addr, version
+            ),
+            LintWarning::UnreferencedLock { id } => {
+                write!(f, "object {} is locked by no intent", id)
+            }
+            LintWarning::EmptyVaultEntry { vault, coin_type } => {
+                write!(f, "vault {} holds 0 {}", vault, coin_type)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multisig::{Dep, Role};
+    use crate::test_utils;
+
+    #[test]
+    fn lint_warnings() {
+        let alice = Address::new([0xa; 32]);
+        let mut multisig = test_utils::multisig(&[(alice, vec!["treasury"])], 2);
+        multisig.deps.push(Dep {
+            name: "AccountProtocol".to_string(),
+            addr: Address::new([0x1; 32]),
+            version: 1,
+        });
+        multisig.locked_objects.push(Address::new([0xc; 32]));
+        multisig.config.roles.insert(
+            "admin".to_string(),
+            Role {
+                threshold: 1,
+                total_weight: 0,
+            },
+        );
+
+        let verified = vec![("AccountProtocol".to_string(), Address::new([0x1; 32]), 2)];
+        assert_eq!(
+            lint(&multisig, Some(&verified)),
+            vec![
+                LintWarning::UndefinedRole {
+                    member: alice.to_string(),
+                    role: "treasury".to_string(),
+                },
+                LintWarning::ThresholdUnreachable {
+                    name: "global".to_string(),
+                    threshold: 2,
+                    total_weight: 1,
+                },
+                LintWarning::ThresholdUnreachable {
+                    name: "admin".to_string(),
+                    threshold: 1,
+                    total_weight: 0,
+                },
+                LintWarning::UnverifiedDep {
+                    name: "AccountProtocol".to_string(),
+                    addr: Address::new([0x1; 32]),
+                    version: 1,
+                },
+            ]
+        );
+
+        // the deps are only checked if unverified ones are forbidden
+        multisig.unverified_deps_allowed = true;
+        assert_eq!(lint(&multisig, Some(&verified)).len(), 3);
+
+        // no intent withdraws the locked object
+        multisig.intents = Some(test_utils::intents(Vec::new()));
+        assert_eq!(
+            lint(&multisig, None).last(),
+            Some(&LintWarning::UnreferencedLock {
+                id: Address::new([0xc; 32])
+            })
+        );
+    }
+}
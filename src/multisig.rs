@@ -4,19 +4,25 @@ use std::sync::Arc;
 use std::fmt;
 
 use sui_graphql_client::Client;
-use sui_sdk_types::{ObjectData, Address};
+use sui_sdk_types::{ObjectData, Address, StructTag, TypeTag};
 
 use crate::move_binding::{account_protocol as ap, account_multisig as am};
 use crate::assets::{dynamic_fields::DynamicFields, owned_objects::OwnedObjects};
-use crate::proposals::intents::Intents;
+use crate::errors::MultisigError;
+use crate::lint::{self, LintWarning};
+use crate::profile::{self, AccountProfile};
+use crate::proposals::{
+    actions::{ConfigMultisigFields, IntentType},
+    intents::{Intent, Intents},
+    simulation::{analyze_config_change, ConfigWarning},
+};
+use crate::roles::RoleOverview;
 use crate::utils;
-use crate::FEE_OBJECT;
 
 pub struct Multisig {
     pub sui_client: Arc<Client>,
-    pub fee_amount: u64,
-    pub fee_recipient: Address,
     pub id: Address,
+    pub version: u64, // of the Account object, bumped by any mutation
     pub metadata: HashMap<String, String>,
     pub deps: Vec<Dep>,
     pub unverified_deps_allowed: bool,
@@ -26,6 +32,24 @@ pub struct Multisig {
     pub intents: Option<Intents>, // if None then not fetched yet
     pub owned_objects: Option<OwnedObjects>, // if None then not fetched yet
     pub dynamic_fields: Option<DynamicFields>, // if None then not fetched yet
+    pub consistent_reads: Option<usize>, // max attempts to get a consistent snapshot
+    pub load_options: LoadOptions,
+}
+
+// what is fetched on refresh besides the account, its intents and dynamic fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub owned_objects: OwnedObjectsLoad,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OwnedObjectsLoad {
+    #[default]
+    All,
+    // for accounts holding thousands of NFTs, see OwnedObjects::refresh_type
+    CoinsOnly,
+    // owned_objects stays None
+    Skip,
 }
 
 #[derive(Debug)]
@@ -35,6 +59,18 @@ pub struct Dep {
     pub version: u64,
 }
 
+#[derive(Debug, Clone)]
+pub struct Fees {
+    pub amount: u64,
+    pub recipient: Address,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingInvite {
+    pub id: Address,
+    pub recipient: Address,
+}
+
 #[derive(Debug, Default)]
 pub struct Config {
     pub members: Vec<Member>,
@@ -42,7 +78,7 @@ pub struct Config {
     pub roles: HashMap<String, Role>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Member {
     // social data
     pub username: String,
@@ -61,13 +97,98 @@ pub struct Role {
     pub total_weight: u64,
 }
 
+#[derive(Debug)]
+pub struct MemberPermissions {
+    pub address: Address,
+    pub is_member: bool,
+    pub weight: u64,
+    pub roles: Vec<String>,
+    // intents the member can approve and execute without anyone else
+    pub solo_intent_types: Vec<IntentType>,
+    // snapshot of the config thresholds
+    global_threshold: u64,
+    role_thresholds: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalStatus {
+    NotMember,
+    AlreadyApproved,
+    // approving would (not) make the intent executable
+    CanApprove { reaches_threshold: bool },
+}
+
+impl Config {
+    // members with an invalid address are skipped
+    pub fn to_fields(&self) -> ConfigMultisigFields {
+        ConfigMultisigFields {
+            global: self.global.threshold,
+            members: self
+                .members
+                .iter()
+                .filter_map(|member| {
+                    Some((
+                        member.address.parse().ok()?,
+                        member.weight,
+                        member.roles.clone(),
+                    ))
+                })
+                .collect(),
+            roles: self
+                .roles
+                .iter()
+                .map(|(name, role)| (name.clone(), role.threshold))
+                .collect(),
+        }
+    }
+
+    // same members, weights, roles and thresholds as the action, in any order
+    pub fn matches(&self, fields: &ConfigMultisigFields) -> bool {
+        let mut members = self.members
+            .iter()
+            .map(|member| {
+                let mut roles = member.roles.clone();
+                roles.sort();
+                (member.address.clone(), member.weight, roles)
+            })
+            .collect::<Vec<_>>();
+        let mut proposed = fields.members
+            .iter()
+            .map(|(address, weight, roles)| {
+                let mut roles = roles.clone();
+                roles.sort();
+                (address.to_string(), *weight, roles)
+            })
+            .collect::<Vec<_>>();
+        members.sort();
+        proposed.sort();
+
+        let mut roles = self.roles
+            .iter()
+            .map(|(name, role)| (name.clone(), role.threshold))
+            .collect::<Vec<_>>();
+        let mut proposed_roles = fields.roles.clone();
+        roles.sort();
+        proposed_roles.sort();
+
+        self.global.threshold == fields.global && members == proposed && roles == proposed_roles
+    }
+}
+
 impl Multisig {
     pub async fn from_id(sui_client: Arc<Client>, id: Address) -> Result<Self> {
+        Self::from_id_with_options(sui_client, id, LoadOptions::default()).await
+    }
+
+    pub async fn from_id_with_options(
+        sui_client: Arc<Client>,
+        id: Address,
+        load_options: LoadOptions,
+    ) -> Result<Self> {
         let mut multisig = Self {
             sui_client: sui_client.clone(),
-            fee_amount: 0,
-            fee_recipient: Address::ZERO,
             id,
+            version: 0,
             metadata: HashMap::new(),
             deps: Vec::new(),
             unverified_deps_allowed: false,
@@ -77,23 +198,73 @@ impl Multisig {
             intents: None,
             owned_objects: None,
             dynamic_fields: None,
+            consistent_reads: None,
+            load_options,
         };
 
         multisig.refresh().await?;
         Ok(multisig)
     }
 
+    // refetches until the Account object version is the same after the refresh as during it,
+    // so that intents, owned objects and dynamic fields all reflect the same account state
+    pub async fn from_id_consistent(
+        sui_client: Arc<Client>,
+        id: Address,
+        max_attempts: usize,
+    ) -> Result<Self> {
+        let mut multisig = Self::from_id(sui_client, id).await?;
+        multisig.consistent_reads = Some(max_attempts);
+        multisig.ensure_consistent().await?;
+        Ok(multisig)
+    }
+
     pub async fn refresh(&mut self) -> Result<()> {
+        self.fetch().await?;
+        self.ensure_consistent().await
+    }
 
+    // retries the refresh while the account changed concurrently (if consistent reads are enabled)
+    pub async fn ensure_consistent(&mut self) -> Result<()> {
+        let Some(max_attempts) = self.consistent_reads else {
+            return Ok(());
+        };
+        for _ in 0..max_attempts {
+            let latest = utils::get_object(&self.sui_client, self.id)
+                .await?
+                .version();
+            if latest == self.version {
+                return Ok(());
+            }
+            self.fetch().await?;
+        }
+        Err(anyhow!(
+            "Multisig {} kept changing during {} refreshes",
+            self.id,
+            max_attempts
+        ))
+    }
+
+    async fn fetch(&mut self) -> Result<()> {
         // --- Account<Multisig> ---
 
         // fetch Account<Multisig> object
         let multisig_obj = utils::get_object(&self.sui_client, self.id).await?;
+        self.version = multisig_obj.version();
 
         // parse the Account<Multisig> object
+        if let ObjectData::Package(_) = multisig_obj.data() {
+            return Err(MultisigError::NotAMultisigAccount {
+                id: self.id,
+                actual_type: "package".to_string(),
+                config_type: None,
+            }
+            .into());
+        }
         if let ObjectData::Struct(obj) = multisig_obj.data() {
-            let multisig: ap::account::Account<am::multisig::Multisig> = bcs::from_bytes(obj.contents())
-                .map_err(|e| anyhow!("Failed to parse multisig object: {}", e))?;
+            check_account_type(self.id, obj.object_type())?;
+            let multisig: ap::account::Account<am::multisig::Multisig> =
+                utils::decode(obj.contents(), Some(&obj.object_type().to_string()))?;
 
             // get the metadata map
             self.metadata = multisig.metadata.inner.contents
@@ -159,29 +330,41 @@ impl Multisig {
 
         // --- Owned Objects ---
 
-        let owned_objects = OwnedObjects::from_multisig_id(self.sui_client.clone(), self.id).await?;
-        self.owned_objects = Some(owned_objects);
+        self.owned_objects = self.fetch_owned_objects().await?;
 
         // --- Dynamic Fields ---
 
         let dynamic_fields = DynamicFields::from_multisig_id(self.sui_client.clone(), self.id).await?;
         self.dynamic_fields = Some(dynamic_fields);
 
-        // --- Fees ---
-
-        // fetch the Fees object
-        let fee_obj = utils::get_object(&self.sui_client, Address::from_hex(FEE_OBJECT).unwrap()).await?;
+        Ok(())
+    }
 
-        // parse the Fees object
-        if let ObjectData::Struct(obj) = fee_obj.data() {
-            let fees: am::fees::Fees = bcs::from_bytes(obj.contents())
-                .map_err(|e| anyhow!("Failed to parse fees object: {}", e))?;   
-        
-                self.fee_amount = fees.amount;
-                self.fee_recipient = fees.recipient;
+    // intents, config and dynamic fields can only change with the Account object version,
+    // owned objects are always refreshed as they are received without touching the account
+    // returns whether a full refresh happened
+    pub async fn refresh_incremental(&mut self) -> Result<bool> {
+        let multisig_obj = utils::get_object(&self.sui_client, self.id).await?;
+        if self.intents.is_none() || multisig_obj.version() != self.version {
+            self.refresh().await?;
+            return Ok(true);
         }
 
-        Ok(())
+        self.owned_objects = self.fetch_owned_objects().await?;
+        Ok(false)
+    }
+
+    async fn fetch_owned_objects(&self) -> Result<Option<OwnedObjects>> {
+        let sui_client = self.sui_client.clone();
+        Ok(match self.load_options.owned_objects {
+            OwnedObjectsLoad::All => {
+                Some(OwnedObjects::from_multisig_id(sui_client, self.id).await?)
+            }
+            OwnedObjectsLoad::CoinsOnly => {
+                Some(OwnedObjects::coins_only(sui_client, self.id).await?)
+            }
+            OwnedObjectsLoad::Skip => None,
+        })
     }
 
     pub async fn switch_multisig(&mut self, id: Address) -> Result<()> {
@@ -189,13 +372,226 @@ impl Multisig {
         self.refresh().await?;
         Ok(())
     }
+
+    // e.g. to fail over to another endpoint, the loaded data is kept
+    pub fn set_sui_client(&mut self, sui_client: Arc<Client>) {
+        if let Some(intents) = self.intents.as_mut() {
+            intents.sui_client = sui_client.clone();
+            for intent in intents.intents.values_mut() {
+                intent.sui_client = sui_client.clone();
+            }
+        }
+        if let Some(owned_objects) = self.owned_objects.as_mut() {
+            owned_objects.sui_client = sui_client.clone();
+        }
+        if let Some(dynamic_fields) = self.dynamic_fields.as_mut() {
+            dynamic_fields.sui_client = sui_client.clone();
+        }
+        self.sui_client = sui_client;
+    }
+
+    // === Metadata ===
+
+    pub fn profile(&self) -> AccountProfile {
+        AccountProfile::from_metadata(&self.metadata)
+    }
+
+    pub fn name(&self) -> &str {
+        self.metadata.get(profile::NAME).map_or("", String::as_str)
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.metadata.get(profile::DESCRIPTION).map(String::as_str)
+    }
+
+    pub fn image(&self) -> Option<&str> {
+        self.metadata.get(profile::IMAGE).map(String::as_str)
+    }
+
+    // what breaks if the member is removed from the config, errors if the address is not a member
+    pub fn analyze_member_removal(&self, address: Address) -> Result<Vec<ConfigWarning>> {
+        let mut proposed = self.config.to_fields();
+        let before = proposed.members.len();
+        proposed.members.retain(|(member, _, _)| *member != address);
+        if proposed.members.len() == before {
+            return Err(anyhow!("{} is not a member", address));
+        }
+        Ok(analyze_config_change(self, &proposed))
+    }
+
+    // loads the actions of the intents and the verified packages then runs lint::lint,
+    // the locked objects aren't checked if the actions of an intent can't be read
+    pub async fn lint(&mut self) -> Result<Vec<LintWarning>> {
+        if let Some(intents) = self.intents.as_mut() {
+            for intent in intents.intents.values_mut() {
+                let _ = intent.get_actions_args().await;
+            }
+        }
+        let verified = if self.unverified_deps_allowed {
+            None
+        } else {
+            Some(utils::verified_extensions(&self.sui_client).await?)
+        };
+        Ok(lint::lint(self, verified.as_deref()))
+    }
+
+    // roles sorted by name with their thresholds and members
+    pub fn roles_overview(&self) -> Vec<RoleOverview> {
+        let mut overview = self.config.roles
+            .iter()
+            .map(|(name, role)| RoleOverview {
+                name: name.clone(),
+                threshold: role.threshold,
+                total_weight: role.total_weight,
+                members: self.config.members
+                    .iter()
+                    .filter(|member| member.roles.contains(name))
+                    .map(|member| (member.address.clone(), member.weight))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        overview.sort_by(|a, b| a.name.cmp(&b.name));
+        overview
+    }
+
+    // invites sent by this multisig that haven't been accepted or refused yet, invites are sent
+    // to members so only the objects owned by the members are fetched
+    pub async fn pending_invites(&self) -> Result<Vec<PendingInvite>> {
+        let invite_type = format!("{}::user::Invite", crate::ACCOUNT_PROTOCOL_PACKAGE);
+        let mut invites = Vec::new();
+        for member in &self.config.members {
+            let recipient = member.address.parse::<Address>()?;
+            let invite_objects =
+                utils::get_owned_objects(&self.sui_client, recipient, Some(&invite_type)).await?;
+            for object in invite_objects {
+                let ObjectData::Struct(move_struct) = object.data() else {
+                    continue;
+                };
+                // an invite that doesn't decode can't be from this multisig
+                let Ok(invite) = utils::decode::<ap::user::Invite>(
+                    move_struct.contents(),
+                    Some(&move_struct.object_type().to_string()),
+                ) else {
+                    continue;
+                };
+                if invite.account_addr == self.id {
+                    invites.push(PendingInvite {
+                        id: *invite.id.as_address(),
+                        recipient,
+                    });
+                }
+            }
+        }
+
+        Ok(invites)
+    }
+
+    // same rule as the multisig module: global threshold or role threshold reached
+    pub fn is_approved(&self, intent: &Intent) -> bool {
+        intent.outcome.total_weight >= self.config.global.threshold
+            || self
+                .config
+                .roles
+                .get(&intent.role)
+                .is_some_and(|role| intent.outcome.role_weight >= role.threshold)
+    }
+
+    // intents still collecting approvals that the member hasn't approved, the most urgent first
+    pub fn awaiting_approval(&self, address: Address) -> Vec<&Intent> {
+        let Some(intents) = self.intents.as_ref() else {
+            return Vec::new();
+        };
+        intents
+            .for_approver(address, &self.config)
+            .into_iter()
+            .filter(|intent| {
+                !intent.outcome.approved.contains(&address) && !self.is_approved(intent)
+            })
+            .collect()
+    }
+
+    pub fn permissions(&self, address: &Address) -> MemberPermissions {
+        let member = self.config.members
+            .iter()
+            .find(|member| member.address == address.to_string());
+        let weight = member.map_or(0, |member| member.weight);
+        let roles = member.map_or(vec![], |member| member.roles.clone());
+        let role_thresholds = self.config.roles
+            .iter()
+            .map(|(name, role)| (name.clone(), role.threshold))
+            .collect::<HashMap<String, u64>>();
+
+        let mut permissions = MemberPermissions {
+            address: *address,
+            is_member: member.is_some(),
+            weight,
+            roles,
+            solo_intent_types: vec![],
+            global_threshold: self.config.global.threshold,
+            role_thresholds,
+        };
+        if permissions.is_member {
+            permissions.solo_intent_types = IntentType::ALL
+                .iter()
+                .filter(|type_| permissions.reaches_threshold(0, 0, &type_.role().to_string()))
+                .cloned()
+                .collect();
+        }
+        permissions
+    }
+}
+
+impl MemberPermissions {
+    pub fn approval_status(&self, intent: &Intent) -> ApprovalStatus {
+        if !self.is_member {
+            return ApprovalStatus::NotMember;
+        }
+        if intent.outcome.approved.contains(&self.address) {
+            return ApprovalStatus::AlreadyApproved;
+        }
+        ApprovalStatus::CanApprove {
+            reaches_threshold: self.reaches_threshold(
+                intent.outcome.total_weight,
+                intent.outcome.role_weight,
+                &intent.role,
+            ),
+        }
+    }
+
+    pub fn can_approve(&self, intent: &Intent) -> bool {
+        matches!(self.approval_status(intent), ApprovalStatus::CanApprove { .. })
+    }
+
+    // same rule as the multisig module: global threshold or role threshold reached
+    fn reaches_threshold(&self, total_weight: u64, role_weight: u64, role: &str) -> bool {
+        if total_weight + self.weight >= self.global_threshold {
+            return true;
+        }
+        match self.role_thresholds.get(role) {
+            Some(threshold) if self.roles.iter().any(|r| r == role) => {
+                role_weight + self.weight >= *threshold
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ApprovalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApprovalStatus::NotMember => write!(f, "not a member, cannot approve"),
+            ApprovalStatus::AlreadyApproved => write!(f, "already approved"),
+            ApprovalStatus::CanApprove { reaches_threshold: true } => {
+                write!(f, "can approve (reaches threshold)")
+            }
+            ApprovalStatus::CanApprove { reaches_threshold: false } => write!(f, "can approve"),
+        }
+    }
 }
 
 impl fmt::Debug for Multisig {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Multisig")
-            .field("fee_amount", &self.fee_amount)
-            .field("fee_recipient", &self.fee_recipient)
             .field("id", &self.id)
             .field("metadata", &self.metadata)
             .field("deps", &self.deps)
@@ -208,4 +604,130 @@ impl fmt::Debug for Multisig {
             .field("dynamic_fields", &self.dynamic_fields)
             .finish()
     }
-}
\ No newline at end of file
+}
+
+// checked by name before decoding, the addresses change with package upgrades
+pub fn check_account_type(id: Address, type_: &StructTag) -> Result<()> {
+    let is_account = type_.module.as_str() == "account" && type_.name.as_str() == "Account";
+    let config = match type_.type_params.first() {
+        Some(TypeTag::Struct(config)) if is_account => Some(config),
+        _ => None,
+    };
+    match config {
+        Some(config)
+            if config.module.as_str() == "multisig" && config.name.as_str() == "Multisig" =>
+        {
+            Ok(())
+        }
+        _ => Err(MultisigError::NotAMultisigAccount {
+            id,
+            actual_type: type_.to_string(),
+            config_type: config.map(|config| config.to_string()),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn config_matches_action() {
+        let (alice, bob) = (Address::new([0xa; 32]), Address::new([0xb; 32]));
+        let mut config = Config::default();
+        config.global.threshold = 2;
+        for (address, roles) in [(alice, vec!["b".to_string(), "a".to_string()]), (bob, vec![])] {
+            config.members.push(Member {
+                address: address.to_string(),
+                weight: 1,
+                roles,
+                ..Default::default()
+            });
+        }
+        config.roles.insert("a".to_string(), Role { threshold: 1, total_weight: 1 });
+
+        let mut fields = ConfigMultisigFields {
+            global: 2,
+            members: vec![
+                (bob, 1, vec![]),
+                (alice, 1, vec!["a".to_string(), "b".to_string()]),
+            ],
+            roles: vec![("a".to_string(), 1)],
+        };
+        assert!(config.matches(&fields));
+        fields.members[0].1 = 2;
+        assert!(!config.matches(&fields));
+        fields.members[0].1 = 1;
+        fields.roles[0].1 = 2;
+        assert!(!config.matches(&fields));
+    }
+
+    #[test]
+    fn member_removal_warnings() {
+        let (alice, bob) = (Address::new([0xa; 32]), Address::new([0xb; 32]));
+        let mut multisig =
+            test_utils::multisig(&[(alice, vec!["treasury"]), (bob, vec!["treasury"])], 2);
+        multisig.config.roles.insert(
+            "treasury".to_string(),
+            Role {
+                threshold: 1,
+                total_weight: 2,
+            },
+        );
+        multisig.intents = Some(test_utils::intents(vec![
+            test_utils::intent("pay", "treasury"),
+            test_utils::intent("config", "config"),
+        ]));
+
+        // the treasury role is still reachable by alice, the global threshold isn't
+        assert_eq!(
+            multisig.analyze_member_removal(bob).unwrap(),
+            vec![
+                ConfigWarning::ThresholdUnreachable {
+                    name: "global".to_string(),
+                    threshold: 2,
+                    total_weight: 1,
+                },
+                ConfigWarning::IntentUnexecutable {
+                    key: "config".to_string(),
+                    role: "config".to_string(),
+                },
+            ]
+        );
+        assert!(multisig.analyze_member_removal(Address::ZERO).is_err());
+    }
+
+    #[test]
+    fn check_account_types() {
+        let tag = |s: &str| match s.parse::<TypeTag>().unwrap() {
+            TypeTag::Struct(tag) => *tag,
+            _ => unreachable!(),
+        };
+        let id = Address::ZERO;
+        assert!(
+            check_account_type(id, &tag("0x1::account::Account<0x2::multisig::Multisig>")).is_ok()
+        );
+
+        let error =
+            check_account_type(id, &tag("0x1::account::Account<0x3::dao::Dao>")).unwrap_err();
+        match error.downcast_ref::<MultisigError>() {
+            Some(MultisigError::NotAMultisigAccount {
+                config_type: Some(config_type),
+                ..
+            }) => {
+                assert!(config_type.ends_with("::dao::Dao"))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let error = check_account_type(id, &tag("0x2::coin::Coin<0x2::sui::SUI>")).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MultisigError>(),
+            Some(MultisigError::NotAMultisigAccount {
+                config_type: None,
+                ..
+            })
+        ));
+    }
+}
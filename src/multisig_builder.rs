@@ -1,11 +1,14 @@
 use anyhow::{anyhow, Result};
 use move_types::Address;
-use sui_transaction_builder::TransactionBuilder;
+use sui_sdk_types::{Argument, GasCostSummary, ObjectData};
+use sui_transaction_builder::{Function, Serialized, TransactionBuilder};
 
 use crate::{
-    move_binding::{account_multisig as am, account_protocol as ap, sui},
+    move_binding::{account_actions as aa, account_multisig as am, account_protocol as ap, sui},
     proposals::params::{ConfigMultisigArgs, ParamsArgs},
-    MultisigClient,
+    types::{IntoStructType, StructType},
+    utils::{self, InputKind},
+    MultisigClient, ACCOUNT_ACTIONS_PACKAGE_ID,
 };
 
 pub struct MultisigBuilder<'a> {
@@ -13,9 +16,57 @@ pub struct MultisigBuilder<'a> {
     pub builder: &'a mut TransactionBuilder,
     pub name: Option<String>,
     pub config: Option<Config>,
+    // run in order after setting the name, while the creator is still the only member
+    pub steps: Vec<Step>,
+    // execute the config intent in the creation transaction (default),
+    // otherwise it is only approved by the creator and left pending
+    pub auto_execute: bool,
 }
 
 #[derive(Debug, Clone)]
+pub enum Step {
+    OpenVault(String),
+    // into the last vault opened before, owned by the multisig if none,
+    // from the given coins of the creator or from enough of them (gas for SUI) if empty
+    DepositCoin {
+        coin_type: String,
+        amount: u64,
+        coins: Vec<Address>,
+    },
+    // cap owned by the creator, its type is checked before building the call
+    LockCap {
+        cap_id: Address,
+        cap_type: String,
+    },
+    LockUpgradeCap {
+        cap_id: Address,
+        package_name: String,
+        timelock_duration: u64,
+    },
+}
+
+// cost of creating the multisig, from a dry run of the creation transaction
+#[derive(Debug, Clone)]
+pub struct CreationEstimate {
+    // protocol fee, paid to the Fees recipient
+    pub fee: u64,
+    pub gas: GasCostSummary,
+}
+
+impl CreationEstimate {
+    // computation and storage costs minus the storage rebate
+    pub fn net_gas(&self) -> u64 {
+        (self.gas.computation_cost + self.gas.storage_cost).saturating_sub(self.gas.storage_rebate)
+    }
+
+    // without the SUI deposited
+    pub fn total(&self) -> u64 {
+        self.fee + self.net_gas()
+    }
+}
+
+// starts from the loaded multisig config, or from an empty one if none is loaded
+#[derive(Debug, Clone, Default)]
 pub struct Config {
     pub addresses: Vec<String>,
     pub weights: Vec<u64>,
@@ -37,6 +88,16 @@ impl Config {
             role_thresholds: config.roles.iter().map(|(_, role)| role.threshold).collect(),
         })
     }
+
+    // 0 if the address is not a member
+    pub fn weight_of(&self, address: &str) -> u64 {
+        self.addresses
+            .iter()
+            .zip(&self.weights)
+            .filter(|(a, _)| a.as_str() == address)
+            .map(|(_, weight)| *weight)
+            .sum()
+    }
 }
 
 impl<'a> MultisigBuilder<'a> {
@@ -46,6 +107,8 @@ impl<'a> MultisigBuilder<'a> {
             builder,
             name: None,
             config: None,
+            steps: Vec::new(),
+            auto_execute: true,
         }
     }
 
@@ -56,7 +119,7 @@ impl<'a> MultisigBuilder<'a> {
 
     pub fn set_global_threshold(mut self, threshold: u64) -> Self {
         if self.config.is_none() {
-            self.config = Some(Config::from_state(self.client).unwrap());
+            self.config = Some(Config::from_state(self.client).unwrap_or_default());
         }
         self.config.as_mut().unwrap().global_threshold = threshold;
         self
@@ -64,7 +127,7 @@ impl<'a> MultisigBuilder<'a> {
 
     pub fn add_member(mut self, address: &str, weight: u64, roles: Vec<&str>) -> Self {
         if self.config.is_none() {
-            self.config = Some(Config::from_state(self.client).unwrap());
+            self.config = Some(Config::from_state(self.client).unwrap_or_default());
             // clear addresses, weights, and roles to add new ones
             self.config.as_mut().unwrap().addresses = vec![];
             self.config.as_mut().unwrap().weights = vec![];
@@ -88,7 +151,7 @@ impl<'a> MultisigBuilder<'a> {
 
     pub fn add_role(mut self, role: &str, threshold: u64) -> Self {
         if self.config.is_none() {
-            self.config = Some(Config::from_state(self.client).unwrap());
+            self.config = Some(Config::from_state(self.client).unwrap_or_default());
             // clear role names and thresholds to add new ones
             self.config.as_mut().unwrap().role_names = vec![];
             self.config.as_mut().unwrap().role_thresholds = vec![];
@@ -108,12 +171,110 @@ impl<'a> MultisigBuilder<'a> {
         self
     }
 
+    pub fn auto_execute(mut self, auto_execute: bool) -> Self {
+        self.auto_execute = auto_execute;
+        self
+    }
+
+    pub fn open_vault(mut self, vault_name: &str) -> Self {
+        self.steps.push(Step::OpenVault(vault_name.to_string()));
+        self
+    }
+
+    // into the last vault opened before, owned by the multisig if none
+    pub fn deposit_coin(self, coin_type: &str, amount: u64) -> Self {
+        self.deposit_coin_from(coin_type, amount, Vec::new())
+    }
+
+    pub fn deposit_coin_from(mut self, coin_type: &str, amount: u64, coins: Vec<Address>) -> Self {
+        self.steps.push(Step::DepositCoin {
+            coin_type: coin_type.to_string(),
+            amount,
+            coins,
+        });
+        self
+    }
+
+    pub fn lock_cap(mut self, cap_id: Address, cap_type: &str) -> Self {
+        self.steps.push(Step::LockCap {
+            cap_id,
+            cap_type: cap_type.to_string(),
+        });
+        self
+    }
+
+    // timelock_duration can be 0
+    pub fn lock_upgrade_cap(
+        mut self,
+        cap_id: Address,
+        package_name: &str,
+        timelock_duration: u64,
+    ) -> Self {
+        self.steps.push(Step::LockUpgradeCap {
+            cap_id,
+            package_name: package_name.to_string(),
+            timelock_duration,
+        });
+        self
+    }
+
+    // issues to show before building, nothing blocks the creation
+    pub fn warnings(&self) -> Vec<String> {
+        let (Some(config), Some(user)) = (&self.config, self.client.user()) else {
+            return Vec::new();
+        };
+        // the creator is the only member until the config is executed, alone it
+        // won't reach the threshold afterwards
+        let weight = config.weight_of(&user.address.to_string());
+        if weight >= config.global_threshold {
+            return Vec::new();
+        }
+        vec![format!(
+            "Creator weight {} is below the global threshold {}, {}",
+            weight,
+            config.global_threshold,
+            if self.auto_execute {
+                "the creator won't be able to act alone after the config is executed"
+            } else {
+                "the config intent is left pending"
+            }
+        )]
+    }
+
+    // builds the same steps in a separate transaction and dry runs it, nothing is submitted
+    pub async fn estimate(&self) -> Result<CreationEstimate> {
+        let sender = self
+            .client
+            .user()
+            .ok_or(anyhow!("User not loaded"))?
+            .address;
+        let mut builder = self.client.init_tx(sender).await?;
+        MultisigBuilder {
+            client: self.client,
+            builder: &mut builder,
+            name: self.name.clone(),
+            config: self.config.clone(),
+            steps: self.steps.clone(),
+            auto_execute: self.auto_execute,
+        }
+        .build()
+        .await?;
+
+        let effects = utils::dry_run_tx(self.client.sui(), &builder.finish()?).await?;
+        Ok(CreationEstimate {
+            fee: self.client.fees().await?.amount,
+            gas: effects.gas_summary().clone(),
+        })
+    }
+
     pub async fn build(self) -> Result<()> {
         let Self {
             client,
             builder,
             name,
             config,
+            steps,
+            auto_execute,
         } = self;
 
         if client.user().is_none() {
@@ -150,6 +311,82 @@ impl<'a> MultisigBuilder<'a> {
             );
         }
 
+        // open vaults, deposit coins and lock caps, before the config removes the creator
+        let mut vault = None;
+        for step in steps {
+            match step {
+                Step::OpenVault(vault_name) => {
+                    let vault_name_arg = client.pure_arg(builder, vault_name.clone())?;
+                    let auth = am::multisig::authenticate(builder, multisig.borrow());
+                    aa::vault::open(builder, auth, multisig.borrow_mut(), vault_name_arg);
+                    vault = Some(vault_name);
+                }
+                Step::DepositCoin {
+                    coin_type,
+                    amount,
+                    coins,
+                } => {
+                    let coin = split_coin(client, builder, &coin_type, amount, coins).await?;
+                    match &vault {
+                        Some(vault_name) => {
+                            let vault_name = builder.input(Serialized(vault_name));
+                            let auth = am::multisig::authenticate(builder, multisig.borrow());
+                            builder.move_call(
+                                Function::new(
+                                    *ACCOUNT_ACTIONS_PACKAGE_ID,
+                                    "vault".parse()?,
+                                    "deposit".parse()?,
+                                    vec![coin_type.as_str().into_struct_type()?.into()],
+                                ),
+                                vec![auth.into(), multisig.borrow_mut().into(), vault_name, coin],
+                            );
+                        }
+                        None => {
+                            let addr = ap::account::addr::<am::multisig::Multisig>(
+                                builder,
+                                multisig.borrow(),
+                            );
+                            builder.transfer_objects(vec![coin], addr.into());
+                        }
+                    }
+                }
+                Step::LockCap { cap_id, cap_type } => {
+                    let cap_type = cap_type.as_str().into_struct_type()?;
+                    let cap = cap_argument(client, builder, cap_id, &cap_type).await?;
+                    let auth = am::multisig::authenticate(builder, multisig.borrow());
+                    builder.move_call(
+                        Function::new(
+                            *ACCOUNT_ACTIONS_PACKAGE_ID,
+                            "access_control".parse()?,
+                            "lock_cap".parse()?,
+                            vec![cap_type.into()],
+                        ),
+                        vec![auth.into(), multisig.borrow_mut().into(), cap],
+                    );
+                }
+                Step::LockUpgradeCap {
+                    cap_id,
+                    package_name,
+                    timelock_duration,
+                } => {
+                    let package_name = client.pure_arg(builder, package_name)?;
+                    let timelock_duration = client.pure_arg(builder, timelock_duration)?;
+                    let upgrade_cap = client
+                        .owned_arg::<sui::package::UpgradeCap>(builder, cap_id)
+                        .await?;
+                    let auth = am::multisig::authenticate(builder, multisig.borrow());
+                    aa::package_upgrade::lock_cap(
+                        builder,
+                        auth,
+                        multisig.borrow_mut(),
+                        upgrade_cap,
+                        package_name,
+                        timelock_duration,
+                    );
+                }
+            }
+        }
+
         // set config if provided
         if let Some(config) = config {
             let Config {
@@ -161,13 +398,24 @@ impl<'a> MultisigBuilder<'a> {
                 role_thresholds,
             } = config;
 
+            // a pending intent must not expire before the creator executes it
+            let (execution_times, expiration_time) = if auto_execute {
+                (vec![0], 0)
+            } else {
+                client
+                    .intent_params("config_multisig")
+                    .await?
+                    .immediate()
+                    .resolve()
+            };
+
             let clock = client.clock_arg(builder).await?;
             let params = ParamsArgs::new(
                 builder,
                 "config_multisig".to_string(),
                 "".to_string(),
-                vec![0],
-                0,
+                execution_times,
+                expiration_time,
             );
 
             let auth = am::multisig::authenticate(builder, multisig.borrow());
@@ -184,10 +432,11 @@ impl<'a> MultisigBuilder<'a> {
             let action_args = ConfigMultisigArgs::new(
                 builder,
                 addresses
-                    .clone()
                     .iter()
-                    .map(|a| Address::from_hex(a).unwrap())
-                    .collect(),
+                    .map(|a| {
+                        Address::from_hex(a).map_err(|_| anyhow!("Invalid member address: {}", a))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
                 weights,
                 roles,
                 global_threshold,
@@ -212,35 +461,37 @@ impl<'a> MultisigBuilder<'a> {
             let key = client.key_arg(builder, "config_multisig")?;
             am::multisig::approve_intent(builder, multisig.borrow_mut(), key);
 
-            let key = client.key_arg(builder, "config_multisig")?;
-            let mut executable = am::multisig::execute_intent(
-                builder,
-                multisig.borrow_mut(),
-                key,
-                clock.borrow(),
-            );
-            am::config::execute_config_multisig(
-                builder,
-                executable.borrow_mut(),
-                multisig.borrow_mut(),
-            );
-            ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
+            if auto_execute {
+                let key = client.key_arg(builder, "config_multisig")?;
+                let mut executable = am::multisig::execute_intent(
+                    builder,
+                    multisig.borrow_mut(),
+                    key,
+                    clock.borrow(),
+                );
+                am::config::execute_config_multisig(
+                    builder,
+                    executable.borrow_mut(),
+                    multisig.borrow_mut(),
+                );
+                ap::account::confirm_execution(builder, multisig.borrow_mut(), executable);
 
-            let key = client.key_arg(builder, "config_multisig")?;
-            let mut expired = ap::account::destroy_empty_intent::<
-                am::multisig::Multisig,
-                am::multisig::Approvals,
-            >(builder, multisig.borrow_mut(), key);
+                let key = client.key_arg(builder, "config_multisig")?;
+                let mut expired = ap::account::destroy_empty_intent::<
+                    am::multisig::Multisig,
+                    am::multisig::Approvals,
+                >(builder, multisig.borrow_mut(), key);
 
-            am::config::delete_config_multisig(builder, expired.borrow_mut());
-            ap::intents::destroy_empty_expired(builder, expired);
+                am::config::delete_config_multisig(builder, expired.borrow_mut());
+                ap::intents::destroy_empty_expired(builder, expired);
+            }
 
             for addr in addresses {
-                if addr == client.user().unwrap().address.to_string() {
+                if addr == creator {
                     // add multisig to User object
                     am::multisig::join(builder, user.borrow_mut(), multisig.borrow());
-                } else {    
-                    // send invite to other addresses
+                } else if auto_execute {
+                    // send invite to other addresses, once they are members
                     client
                         .user()
                         .unwrap()
@@ -262,3 +513,56 @@ impl<'a> MultisigBuilder<'a> {
         Ok(())
     }
 }
+
+// coin of amount from the given coins of the creator, from enough of its coins of the type
+// if none are given, or from the gas coin for SUI
+async fn split_coin(
+    client: &MultisigClient,
+    builder: &mut TransactionBuilder,
+    coin_type: &str,
+    amount: u64,
+    mut coins: Vec<Address>,
+) -> Result<Argument> {
+    let owner = client.user().ok_or(anyhow!("User not loaded"))?.address;
+    let amount_arg = builder.input(Serialized(&amount));
+    if coins.is_empty() {
+        if coin_type.into_struct_type()? == "0x2::sui::SUI".into_struct_type()? {
+            return Ok(builder.split_coins(builder.gas(), vec![amount_arg]));
+        }
+        let owned = utils::get_coins_for_amount(client.sui(), owner, coin_type, amount).await?;
+        coins = owned.iter().map(|coin| *coin.id().as_address()).collect();
+    }
+
+    let mut inputs = Vec::new();
+    for coin in coins {
+        inputs.push(
+            builder.input(utils::get_object_as_input(client.sui(), coin, InputKind::Owned).await?),
+        );
+    }
+    let primary = inputs.remove(0);
+    if !inputs.is_empty() {
+        builder.merge_coins(primary, inputs);
+    }
+    Ok(builder.split_coins(primary, vec![amount_arg]))
+}
+
+async fn cap_argument(
+    client: &MultisigClient,
+    builder: &mut TransactionBuilder,
+    cap_id: Address,
+    cap_type: &StructType,
+) -> Result<Argument> {
+    match utils::get_object(client.sui(), cap_id).await?.data() {
+        ObjectData::Struct(obj) if StructType::from(obj.object_type().clone()) == *cap_type => {}
+        ObjectData::Struct(obj) => {
+            return Err(anyhow!(
+                "Object {} is a {}, not a {}",
+                cap_id,
+                obj.object_type(),
+                cap_type
+            ))
+        }
+        ObjectData::Package(_) => return Err(anyhow!("Object {} is a package", cap_id)),
+    }
+    client.owned_argument(builder, cap_id).await
+}
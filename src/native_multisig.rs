@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+use base64ct::{Base64, Encoding};
+use std::collections::BTreeMap;
+use sui_crypto::simple::SimpleVerifier;
+use sui_crypto::{SignatureError, SuiSigner, SuiVerifier};
+use sui_sdk_types::{
+    Address, MultisigAggregatedSignature, MultisigCommittee, MultisigMemberPublicKey,
+    MultisigMemberSignature, PersonalMessage, SimpleSignature, Transaction, TransactionDigest,
+    UserSignature,
+};
+
+// Sui native multisig addresses as members of an account multisig:
+// the transaction is exported, each key holder signs it and the partial signatures are merged
+
+// what is sent to the key holders of a native multisig
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningPayload {
+    pub digest: TransactionDigest,
+    // base64 of the bcs encoded transaction
+    pub tx_bytes: String,
+}
+
+impl SigningPayload {
+    pub fn new(tx: &Transaction) -> Result<Self> {
+        Ok(Self {
+            digest: tx.digest(),
+            tx_bytes: Base64::encode_string(&bcs::to_bytes(tx)?),
+        })
+    }
+
+    // payload received without its digest
+    pub fn from_tx_bytes(tx_bytes: String) -> Result<Self> {
+        let bytes =
+            Base64::decode_vec(&tx_bytes).map_err(|e| anyhow!("Invalid tx bytes: {}", e))?;
+        let tx: Transaction = bcs::from_bytes(&bytes)?;
+        Ok(Self {
+            digest: tx.digest(),
+            tx_bytes,
+        })
+    }
+
+    pub fn transaction(&self) -> Result<Transaction> {
+        let bytes =
+            Base64::decode_vec(&self.tx_bytes).map_err(|e| anyhow!("Invalid tx bytes: {}", e))?;
+        let tx: Transaction = bcs::from_bytes(&bytes)?;
+        if tx.digest() != self.digest {
+            return Err(anyhow!(
+                "Transaction digest mismatch: expected {}, got {}",
+                self.digest,
+                tx.digest()
+            ));
+        }
+        Ok(tx)
+    }
+}
+
+// signatures collected from the members of a native multisig for a given transaction
+#[derive(Debug, Clone)]
+pub struct PartialSignatures {
+    committee: MultisigCommittee,
+    // signatures are checked against it when added
+    tx: Transaction,
+    digest: TransactionDigest,
+    // member index -> signature
+    signatures: BTreeMap<usize, MultisigMemberSignature>,
+}
+
+impl PartialSignatures {
+    pub fn new(committee: MultisigCommittee, tx: &Transaction) -> Self {
+        Self {
+            committee,
+            tx: tx.clone(),
+            digest: tx.digest(),
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    // the address of the native multisig, member of the account multisig
+    pub fn address(&self) -> Address {
+        self.committee.derive_address()
+    }
+
+    pub fn digest(&self) -> TransactionDigest {
+        self.digest
+    }
+
+    // signature of a member over the transaction, as returned by SuiSigner::sign_transaction
+    pub fn add(&mut self, signature: UserSignature) -> Result<()> {
+        SimpleVerifier
+            .verify_transaction(&self.tx, &signature)
+            .map_err(|_| anyhow!("Invalid signature for transaction {}", self.digest))?;
+
+        let (public_key, signature) = match signature {
+            UserSignature::Simple(SimpleSignature::Ed25519 {
+                signature,
+                public_key,
+            }) => (
+                MultisigMemberPublicKey::Ed25519(public_key),
+                MultisigMemberSignature::Ed25519(signature),
+            ),
+            UserSignature::Simple(SimpleSignature::Secp256k1 {
+                signature,
+                public_key,
+            }) => (
+                MultisigMemberPublicKey::Secp256k1(public_key),
+                MultisigMemberSignature::Secp256k1(signature),
+            ),
+            UserSignature::Simple(SimpleSignature::Secp256r1 {
+                signature,
+                public_key,
+            }) => (
+                MultisigMemberPublicKey::Secp256r1(public_key),
+                MultisigMemberSignature::Secp256r1(signature),
+            ),
+            _ => return Err(anyhow!("Unsupported signature scheme for native multisig")),
+        };
+
+        let index = self
+            .committee
+            .members()
+            .iter()
+            .position(|member| member.public_key() == &public_key)
+            .ok_or(anyhow!("Signer is not a member of the native multisig"))?;
+        self.signatures.insert(index, signature);
+        Ok(())
+    }
+
+    // serialized signature as exported by wallets and the sui cli
+    pub fn add_base64(&mut self, signature: &str) -> Result<()> {
+        let signature = UserSignature::from_base64(signature)
+            .map_err(|e| anyhow!("Invalid signature: {}", e))?;
+        self.add(signature)
+    }
+
+    // combines the signatures collected by someone else for the same transaction
+    pub fn merge(&mut self, other: PartialSignatures) -> Result<()> {
+        if self.committee != other.committee {
+            return Err(anyhow!("Partial signatures are from different multisigs"));
+        }
+        if self.digest != other.digest {
+            return Err(anyhow!(
+                "Partial signatures are for different transactions: {} and {}",
+                self.digest,
+                other.digest
+            ));
+        }
+        self.signatures.extend(other.signatures);
+        Ok(())
+    }
+
+    pub fn weight(&self) -> u16 {
+        self.signatures
+            .keys()
+            .map(|index| self.committee.members()[*index].weight() as u16)
+            .sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.weight() >= self.committee.threshold()
+    }
+
+    pub fn finish(&self) -> Result<UserSignature> {
+        if !self.is_complete() {
+            return Err(anyhow!(
+                "Not enough signatures: weight {} / threshold {}",
+                self.weight(),
+                self.committee.threshold()
+            ));
+        }
+
+        let bitmap = self
+            .signatures
+            .keys()
+            .fold(0u16, |bitmap, index| bitmap | (1 << index));
+        let signatures = self.signatures.values().cloned().collect();
+        Ok(UserSignature::Multisig(MultisigAggregatedSignature::new(
+            self.committee.clone(),
+            signatures,
+            bitmap,
+        )))
+    }
+}
+
+// signer for a native multisig member, can be passed to utils::execute_tx
+// once enough partial signatures have been collected
+pub struct NativeMultisigSigner {
+    partials: PartialSignatures,
+}
+
+impl NativeMultisigSigner {
+    pub fn new(partials: PartialSignatures) -> Self {
+        Self { partials }
+    }
+}
+
+impl SuiSigner for NativeMultisigSigner {
+    fn sign_transaction(&self, transaction: &Transaction) -> Result<UserSignature, SignatureError> {
+        // partials are only valid for the transaction they were collected for
+        if transaction.digest() != self.partials.digest {
+            return Err(SignatureError::new());
+        }
+        self.partials.finish().map_err(|_| SignatureError::new())
+    }
+
+    fn sign_personal_message(
+        &self,
+        _message: &PersonalMessage,
+    ) -> Result<UserSignature, SignatureError> {
+        Err(SignatureError::new())
+    }
+}
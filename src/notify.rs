@@ -0,0 +1,450 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_graphql_client::Client;
+use sui_sdk_types::Address;
+use tokio::sync::mpsc;
+
+use crate::endpoints::Endpoints;
+use crate::multisig::Multisig;
+use crate::proposals::intents::{self, Intent};
+
+// === Events ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Created,
+    Approved,
+    Executable,
+    Executed,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Created => "proposal_created",
+            EventKind::Approved => "proposal_approved",
+            EventKind::Executable => "proposal_executable",
+            EventKind::Executed => "proposal_executed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IntentEvent {
+    pub kind: EventKind,
+    pub multisig: Address,
+    pub key: String,
+    pub intent_type: String,
+    pub creator: Address,
+    pub total_weight: u64,
+    // global threshold of the multisig
+    pub threshold: u64,
+    pub approved: Vec<Address>,
+    // members who approved since the previous poll (Approved events)
+    pub new_approvers: Vec<Address>,
+    // members to ping, see Intent::pending_approvers
+    pub pending_approvers: Vec<String>,
+}
+
+impl IntentEvent {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "event": self.kind.as_str(),
+            "multisig": self.multisig.to_string(),
+            "key": self.key,
+            "intent_type": self.intent_type,
+            "creator": self.creator.to_string(),
+            "total_weight": self.total_weight,
+            "threshold": self.threshold,
+            "approved": self.approved.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            "new_approvers": self.new_approvers.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            "pending_approvers": self.pending_approvers,
+        })
+    }
+
+    // human-readable summary for chat messages, one line per item
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "{}: {} ({})",
+            match self.kind {
+                EventKind::Created => "New proposal",
+                EventKind::Approved => "Proposal approved",
+                EventKind::Executable => "Proposal ready to execute",
+                EventKind::Executed => "Proposal executed",
+            },
+            self.key,
+            self.intent_type
+        )];
+        match self.kind {
+            EventKind::Created => lines.push(format!("Created by {}", self.creator)),
+            EventKind::Approved => lines.push(format!(
+                "Approved by {}",
+                self.new_approvers
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            _ => {}
+        }
+        if self.kind != EventKind::Executed {
+            lines.push(format!(
+                "{} {}/{}",
+                progress_bar(self.total_weight, self.threshold, 10),
+                self.total_weight,
+                self.threshold
+            ));
+        }
+        if matches!(self.kind, EventKind::Created | EventKind::Approved)
+            && !self.pending_approvers.is_empty()
+        {
+            lines.push(format!("Waiting for {}", self.pending_approvers.join(", ")));
+        }
+        lines.push(intents::share_link(
+            intents::DEFAULT_FRONTEND,
+            self.multisig,
+            &self.key,
+        ));
+        lines.join("\n")
+    }
+}
+
+// ▰▰▰▱▱ filled in proportion of the threshold
+pub fn progress_bar(weight: u64, threshold: u64, width: usize) -> String {
+    let filled = match threshold {
+        0 => width,
+        _ => ((weight.min(threshold) as u128 * width as u128) / threshold as u128) as usize,
+    };
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(width - filled))
+}
+
+// === Sinks ===
+
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+pub trait NotificationSink: Send + Sync {
+    fn notify<'a>(&'a self, event: &'a IntentEvent) -> SinkFuture<'a>;
+}
+
+// prints one JSON payload per line
+pub struct StdoutSink;
+
+impl NotificationSink for StdoutSink {
+    fn notify<'a>(&'a self, event: &'a IntentEvent) -> SinkFuture<'a> {
+        Box::pin(async move {
+            println!("{}", event.to_json());
+            Ok(())
+        })
+    }
+}
+
+// custom sink from a closure
+pub struct FnSink<F>(pub F);
+
+impl<F> NotificationSink for FnSink<F>
+where
+    F: Fn(&IntentEvent) -> Result<()> + Send + Sync,
+{
+    fn notify<'a>(&'a self, event: &'a IntentEvent) -> SinkFuture<'a> {
+        Box::pin(async move { (self.0)(event) })
+    }
+}
+
+// POSTs the JSON payload to the url
+#[cfg(feature = "webhook")]
+pub struct WebhookSink {
+    url: String,
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl NotificationSink for WebhookSink {
+    fn notify<'a>(&'a self, event: &'a IntentEvent) -> SinkFuture<'a> {
+        Box::pin(async move {
+            self.http
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .body(event.to_json().to_string())
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+// posts the event summary to a Slack or Discord incoming webhook
+#[cfg(feature = "chat")]
+pub struct ChatSink {
+    url: String,
+    platform: ChatPlatform,
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "chat")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatPlatform {
+    Slack,
+    Discord,
+}
+
+#[cfg(feature = "chat")]
+impl ChatSink {
+    pub fn slack(url: &str) -> Self {
+        Self::new(url, ChatPlatform::Slack)
+    }
+
+    pub fn discord(url: &str) -> Self {
+        Self::new(url, ChatPlatform::Discord)
+    }
+
+    pub fn new(url: &str, platform: ChatPlatform) -> Self {
+        Self {
+            url: url.to_string(),
+            platform,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn message(&self, event: &IntentEvent) -> Value {
+        match self.platform {
+            ChatPlatform::Slack => json!({ "text": event.summary() }),
+            ChatPlatform::Discord => json!({ "content": event.summary() }),
+        }
+    }
+}
+
+#[cfg(feature = "chat")]
+impl NotificationSink for ChatSink {
+    fn notify<'a>(&'a self, event: &'a IntentEvent) -> SinkFuture<'a> {
+        Box::pin(async move {
+            self.http
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .body(self.message(event).to_string())
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+// sends each event to all the sinks until the stream ends,
+// a failing sink doesn't prevent the others from being notified, on_error receives its error
+pub async fn dispatch(
+    mut events: mpsc::Receiver<IntentEvent>,
+    sinks: Vec<Box<dyn NotificationSink>>,
+    mut on_error: impl FnMut(&IntentEvent, anyhow::Error),
+) {
+    while let Some(event) = events.recv().await {
+        for sink in &sinks {
+            if let Err(e) = sink.notify(&event).await {
+                on_error(&event, e);
+            }
+        }
+    }
+}
+
+// === Watcher ===
+
+// intent as seen by the previous poll
+struct IntentState {
+    intent_type: String,
+    creator: Address,
+    total_weight: u64,
+    approved: Vec<Address>,
+    pending_approvers: Vec<String>,
+    executable: bool,
+}
+
+// polls a multisig and emits events by comparing its intents between polls
+pub struct IntentWatcher {
+    multisig: Multisig,
+    interval: Duration,
+    states: HashMap<String, IntentState>,
+    // fails over on repeated poll failures if set
+    endpoints: Option<Endpoints>,
+}
+
+impl IntentWatcher {
+    // existing intents are recorded without emitting events
+    pub async fn new(
+        sui_client: Arc<Client>,
+        multisig_id: Address,
+        interval: Duration,
+    ) -> Result<Self> {
+        let multisig = Multisig::from_id(sui_client, multisig_id).await?;
+        let mut watcher = Self {
+            multisig,
+            interval,
+            states: HashMap::new(),
+            endpoints: None,
+        };
+        watcher.states = watcher.snapshot()?;
+        Ok(watcher)
+    }
+
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = Some(endpoints);
+        self
+    }
+
+    pub async fn poll(&mut self) -> Result<Vec<IntentEvent>> {
+        let result = self.multisig.refresh().await;
+        if let Some(endpoints) = self.endpoints.as_mut() {
+            if endpoints.record(&result) {
+                self.multisig.set_sui_client(Arc::new(endpoints.client()?));
+            }
+        }
+        result?;
+        let states = self.snapshot()?;
+
+        let mut events = Vec::new();
+        for (key, state) in &states {
+            match self.states.get(key) {
+                None => {
+                    events.push(self.event(EventKind::Created, key, state, vec![]));
+                    if state.executable {
+                        events.push(self.event(EventKind::Executable, key, state, vec![]));
+                    }
+                }
+                Some(previous) => {
+                    let new_approvers = state
+                        .approved
+                        .iter()
+                        .filter(|addr| !previous.approved.contains(addr))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if !new_approvers.is_empty() {
+                        events.push(self.event(EventKind::Approved, key, state, new_approvers));
+                    }
+                    if state.executable && !previous.executable {
+                        events.push(self.event(EventKind::Executable, key, state, vec![]));
+                    }
+                }
+            }
+        }
+        // intents removed while executable were executed, the others were deleted
+        for (key, previous) in &self.states {
+            if !states.contains_key(key) && previous.executable {
+                events.push(self.event(EventKind::Executed, key, previous, vec![]));
+            }
+        }
+
+        self.states = states;
+        Ok(events)
+    }
+
+    // polls in the background and streams the events, stops when the receiver is dropped
+    pub fn spawn(mut self) -> mpsc::Receiver<IntentEvent> {
+        let (sender, receiver) = mpsc::channel(100);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.interval).await;
+                if sender.is_closed() {
+                    return;
+                }
+                // rpc errors are retried at the next tick
+                let Ok(events) = self.poll().await else {
+                    continue;
+                };
+                for event in events {
+                    if sender.send(event).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        receiver
+    }
+
+    // === Helpers ===
+
+    fn snapshot(&self) -> Result<HashMap<String, IntentState>> {
+        let intents = self
+            .multisig
+            .intents
+            .as_ref()
+            .ok_or(anyhow!("Intents not loaded"))?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+
+        Ok(intents
+            .intents
+            .iter()
+            .map(|(key, intent)| {
+                let state = IntentState {
+                    intent_type: intent.type_.clone(),
+                    creator: intent.creator,
+                    total_weight: intent.outcome.total_weight,
+                    approved: intent.outcome.approved.clone(),
+                    pending_approvers: intent
+                        .pending_approvers(&self.multisig.config)
+                        .into_iter()
+                        .map(|member| member.address)
+                        .collect(),
+                    executable: self.is_executable(intent, now),
+                };
+                (key.clone(), state)
+            })
+            .collect())
+    }
+
+    // approved and first execution time reached (local time, may differ slightly from the clock)
+    fn is_executable(&self, intent: &Intent, now: u64) -> bool {
+        self.multisig.is_approved(intent)
+            && intent
+                .execution_times
+                .first()
+                .is_some_and(|time| *time <= now)
+    }
+
+    fn event(
+        &self,
+        kind: EventKind,
+        key: &str,
+        state: &IntentState,
+        new_approvers: Vec<Address>,
+    ) -> IntentEvent {
+        IntentEvent {
+            kind,
+            multisig: self.multisig.id,
+            key: key.to_string(),
+            intent_type: state.intent_type.clone(),
+            creator: state.creator,
+            total_weight: state.total_weight,
+            threshold: self.multisig.config.global.threshold,
+            approved: state.approved.clone(),
+            new_approvers,
+            pending_approvers: state.pending_approvers.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_bars() {
+        assert_eq!(progress_bar(0, 4, 4), "▱▱▱▱");
+        assert_eq!(progress_bar(2, 4, 4), "▰▰▱▱");
+        assert_eq!(progress_bar(9, 4, 4), "▰▰▰▰");
+        assert_eq!(progress_bar(0, 0, 2), "▰▰");
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use sui_sdk_types::Address;
+
+use crate::assets::dynamic_fields::normalize_type;
+use crate::errors::MultisigError;
+use crate::proposals::actions::{IntentActions, IntentType};
+use crate::MultisigClient;
+
+// local rules checked before requesting or executing intents,
+// for the services holding an approval key. example policy.toml:
+//
+// allowed_intents = ["SpendAndTransfer"]
+// blocked_intents = ["UpgradePackage", "ConfigMultisig"]
+// allowed_recipients = ["0x..."]
+//
+// [[limits]]
+// coin_type = "0x2::sui::SUI"
+// max_amount = 1000000000
+// period_ms = 86400000
+//
+// the request methods of the client check the requests against it, the spends are only counted
+// once executed (see record) and saved in a separate file (see with_spent_file).
+// an intent running several executions at once is checked and counted for each of them
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    // IntentType names, any type is allowed if empty
+    pub allowed_intents: Vec<String>,
+    pub blocked_intents: Vec<String>,
+    // any recipient is allowed if empty
+    pub allowed_recipients: Vec<Address>,
+    pub limits: Vec<SpendingLimit>,
+    // spends counted against the limits, see record
+    #[serde(skip)]
+    pub spent: Vec<Spend>,
+    // where the spends are saved, apart from the rules that are written by hand
+    #[serde(skip)]
+    pub spent_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    pub coin_type: String,
+    pub max_amount: u64,
+    pub period_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spend {
+    pub coin_type: String,
+    pub amount: u64,
+    pub timestamp: u64,
+}
+
+// coins or objects leaving the multisig in one execution, coin_type is None for objects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outflow {
+    pub coin_type: Option<String>,
+    pub amount: u64,
+    pub recipient: Address,
+}
+
+impl Policy {
+    pub fn from_toml(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    // loads the spends saved in the file (if it exists), record saves them there from now on
+    pub fn with_spent_file(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            self.spent = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        }
+        self.spent_path = Some(path);
+        Ok(self)
+    }
+
+    // now is in ms
+    pub fn check(&self, intent_type: &IntentType, outflows: &[Outflow], now: u64) -> Result<()> {
+        let name = format!("{:?}", intent_type);
+        if self.blocked_intents.contains(&name)
+            || (!self.allowed_intents.is_empty() && !self.allowed_intents.contains(&name))
+        {
+            return Err(MultisigError::IntentBlocked { intent_type: name }.into());
+        }
+
+        if !self.allowed_recipients.is_empty() {
+            if let Some(outflow) = outflows
+                .iter()
+                .find(|outflow| !self.allowed_recipients.contains(&outflow.recipient))
+            {
+                return Err(MultisigError::RecipientNotAllowed {
+                    recipient: outflow.recipient,
+                }
+                .into());
+            }
+        }
+
+        for limit in &self.limits {
+            let coin_type = normalize_type(&limit.coin_type);
+            let amount = outflows
+                .iter()
+                .filter(|outflow| {
+                    outflow
+                        .coin_type
+                        .as_ref()
+                        .is_some_and(|type_| normalize_type(type_) == coin_type)
+                })
+                .map(|outflow| outflow.amount)
+                .sum::<u64>();
+            if amount == 0 {
+                continue;
+            }
+            let spent = self.spent_since(&coin_type, now.saturating_sub(limit.period_ms));
+            if spent.saturating_add(amount) > limit.max_amount {
+                return Err(MultisigError::SpendingLimitExceeded {
+                    coin_type: limit.coin_type.clone(),
+                    max_amount: limit.max_amount,
+                    spent,
+                    amount,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn check_actions(
+        &self,
+        intent_type: &IntentType,
+        actions: &IntentActions,
+        now: u64,
+    ) -> Result<()> {
+        self.check(intent_type, &outflows(actions), now)
+    }
+
+    // to be called once the execution succeeded, saves the spends if there is a spent file
+    pub fn record(&mut self, outflows: &[Outflow], now: u64) -> Result<()> {
+        for outflow in outflows {
+            if let Some(coin_type) = &outflow.coin_type {
+                self.spent.push(Spend {
+                    coin_type: normalize_type(coin_type),
+                    amount: outflow.amount,
+                    timestamp: now,
+                });
+            }
+        }
+        // spends older than the longest period aren't needed anymore
+        let longest = self
+            .limits
+            .iter()
+            .map(|limit| limit.period_ms)
+            .max()
+            .unwrap_or_default();
+        self.spent
+            .retain(|spend| spend.timestamp >= now.saturating_sub(longest));
+
+        if let Some(path) = &self.spent_path {
+            std::fs::write(path, serde_json::to_string_pretty(&self.spent)?)?;
+        }
+        Ok(())
+    }
+
+    fn spent_since(&self, coin_type: &str, since: u64) -> u64 {
+        self.spent
+            .iter()
+            .filter(|spend| spend.coin_type == coin_type && spend.timestamp >= since)
+            .map(|spend| spend.amount)
+            .sum()
+    }
+}
+
+// client whose policy is put back on drop, even on early returns, see MultisigClient::skip_policy
+pub struct PolicyBypass<'a> {
+    client: &'a mut MultisigClient,
+    skipped: Option<Policy>,
+}
+
+impl<'a> PolicyBypass<'a> {
+    pub fn new(client: &'a mut MultisigClient, skip: bool) -> Self {
+        let skipped = if skip { client.set_policy(None) } else { None };
+        Self { client, skipped }
+    }
+}
+
+impl Deref for PolicyBypass<'_> {
+    type Target = MultisigClient;
+
+    fn deref(&self) -> &MultisigClient {
+        self.client
+    }
+}
+
+impl DerefMut for PolicyBypass<'_> {
+    fn deref_mut(&mut self) -> &mut MultisigClient {
+        self.client
+    }
+}
+
+impl Drop for PolicyBypass<'_> {
+    fn drop(&mut self) {
+        if let Some(policy) = self.skipped.take() {
+            self.client.set_policy(Some(policy));
+        }
+    }
+}
+
+// (amount, recipient) transfers of a coin
+pub fn coin_outflows(coin_type: &str, transfers: &[(u64, Address)]) -> Vec<Outflow> {
+    transfers
+        .iter()
+        .map(|(amount, recipient)| Outflow {
+            coin_type: Some(coin_type.to_string()),
+            amount: *amount,
+            recipient: *recipient,
+        })
+        .collect()
+}
+
+// an object sent to the recipient, only checked against the allowed recipients
+pub fn object_outflow(recipient: Address) -> Outflow {
+    Outflow {
+        coin_type: None,
+        amount: 0,
+        recipient,
+    }
+}
+
+// what one execution of the intent sends out of the multisig
+pub fn outflows(actions: &IntentActions) -> Vec<Outflow> {
+    match actions {
+        IntentActions::MintAndTransfer(fields) => {
+            coin_outflows(&fields.coin_type, &fields.transfers)
+        }
+        IntentActions::SpendAndTransfer(fields) => {
+            coin_outflows(&fields.coin_type, &fields.transfers)
+        }
+        IntentActions::MintAndVest(fields) => {
+            coin_outflows(&fields.coin_type, &[(fields.amount, fields.recipient)])
+        }
+        IntentActions::SpendAndVest(fields) => {
+            coin_outflows(&fields.coin_type, &[(fields.amount, fields.recipient)])
+        }
+        IntentActions::WithdrawAndTransfer(fields) => fields
+            .transfers
+            .iter()
+            .map(|(_, recipient)| object_outflow(*recipient))
+            .collect(),
+        IntentActions::WithdrawAndVest(fields) => vec![object_outflow(fields.recipient)],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sui(amount: u64, recipient: Address) -> Outflow {
+        Outflow {
+            coin_type: Some("0x2::sui::SUI".to_string()),
+            amount,
+            recipient,
+        }
+    }
+
+    #[test]
+    fn check_rules_and_limits() {
+        let alice = Address::new([0xa; 32]);
+        let mut policy = Policy::from_toml(&format!(
+            r#"
+            blocked_intents = ["UpgradePackage"]
+            allowed_recipients = ["{}"]
+
+            [[limits]]
+            coin_type = "0x2::sui::SUI"
+            max_amount = 100
+            period_ms = 1000
+            "#,
+            alice
+        ))
+        .unwrap();
+
+        assert!(policy.check(&IntentType::UpgradePackage, &[], 0).is_err());
+        policy.allowed_intents = vec!["SpendAndTransfer".to_string()];
+        assert!(policy.check(&IntentType::MintAndTransfer, &[], 0).is_err());
+        assert!(policy
+            .check(&IntentType::SpendAndTransfer, &[sui(10, Address::ZERO)], 0)
+            .is_err());
+
+        assert!(policy
+            .check(&IntentType::SpendAndTransfer, &[sui(80, alice)], 0)
+            .is_ok());
+        policy.record(&[sui(80, alice)], 0).unwrap();
+        assert!(policy
+            .check(&IntentType::SpendAndTransfer, &[sui(30, alice)], 500)
+            .is_err());
+        // the first spend is out of the period
+        assert!(policy
+            .check(&IntentType::SpendAndTransfer, &[sui(30, alice)], 1_001)
+            .is_ok());
+    }
+
+    #[test]
+    fn spends_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("policy-spent-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let rules =
+            "[[limits]]\ncoin_type = \"0x2::sui::SUI\"\nmax_amount = 100\nperiod_ms = 1000\n";
+
+        let mut policy = Policy::from_toml(rules)
+            .unwrap()
+            .with_spent_file(&path)
+            .unwrap();
+        policy.record(&[sui(80, Address::ZERO)], 0).unwrap();
+
+        let restarted = Policy::from_toml(rules)
+            .unwrap()
+            .with_spent_file(&path)
+            .unwrap();
+        assert!(restarted
+            .check(
+                &IntentType::SpendAndTransfer,
+                &[sui(30, Address::ZERO)],
+                500
+            )
+            .is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+// the types most programs need: use account_multisig_sdk::prelude::*;
+//
+// module layout:
+// - MultisigClient (crate root): loads a multisig and a user, builds the transactions
+// - multisig, user: account, config and user state
+// - proposals: intents (state), actions (IntentType, IntentActions), params (*Args),
+//   batch (several proposals per transaction), workflows built on top (migration, recovery,
+//   scaffold)
+// - assets: owned objects and dynamic fields (vaults, currencies, caps, packages, kiosks)
+// - types, errors, utils: shared helpers
+
+pub use crate::assets::dynamic_fields::{Cap, Currency, DynamicFields, Kiosk, Package, Vault};
+pub use crate::assets::owned_objects::{Coin, Object, OwnedObjects};
+pub use crate::errors::MultisigError;
+pub use crate::lint::LintWarning;
+pub use crate::multisig::{
+    ApprovalStatus, Config, Dep, Fees, LoadOptions, Member, Multisig, OwnedObjectsLoad, Role,
+};
+pub use crate::policy::Policy;
+pub use crate::profile::AccountProfile;
+pub use crate::proposals::actions::{IntentActions, IntentType};
+pub use crate::proposals::batch::{ChunkPolicy, ProposalBatch};
+pub use crate::proposals::borrowed_cap::BorrowedCap;
+pub use crate::proposals::intents::{Approvals, Intent, Intents};
+pub use crate::proposals::params::{
+    ConfigDepsArgs, ConfigMultisigArgs, DisableRulesArgs, ListNftsArgs, MintAndTransferArgs,
+    MintAndVestArgs, ParamsArgs, RestrictPolicyArgs, SpendAndTransferArgs, SpendAndVestArgs,
+    TakeNftsArgs, UpdateMetadataArgs, UpgradePackageArgs, WithdrawAndBurnArgs,
+    WithdrawAndTransferArgs, WithdrawAndTransferToVaultArgs, WithdrawAndVestArgs,
+};
+pub use crate::proposals::scaffold::Scaffold;
+pub use crate::types::{CoinType, IntoStructType, StructType};
+pub use crate::user::User;
+pub use crate::{MultisigBuilder, MultisigClient};
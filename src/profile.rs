@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// typed view of the account metadata, with the keys used by the account.tech frontends.
+// the metadata is replaced as a whole on chain, so edit the profile and write all of it back
+// (MultisigClient::replace_profile)
+
+pub const NAME: &str = "name";
+pub const DESCRIPTION: &str = "description";
+pub const IMAGE: &str = "image";
+pub const LINK_KEYS: &[&str] = &["website", "twitter", "telegram", "discord", "github"];
+// description of the config intents proposing a profile, see request_config_metadata
+pub const METADATA_INTENT_PREFIX: &str = "metadata:";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountProfile {
+    pub name: String,
+    pub description: Option<String>,
+    // url of the avatar
+    pub image: Option<String>,
+    // by LINK_KEYS
+    pub links: BTreeMap<String, String>,
+    // other keys, kept when the profile is written back
+    pub other: BTreeMap<String, String>,
+}
+
+impl AccountProfile {
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Self {
+        let mut profile = Self::default();
+        for (key, value) in metadata {
+            match key.as_str() {
+                NAME => profile.name = value.clone(),
+                DESCRIPTION => profile.description = Some(value.clone()),
+                IMAGE => profile.image = Some(value.clone()),
+                key if LINK_KEYS.contains(&key) => {
+                    profile.links.insert(key.to_string(), value.clone());
+                }
+                _ => {
+                    profile.other.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        profile
+    }
+
+    // keys and values for config::edit_metadata, empty fields are removed
+    pub fn to_metadata(&self) -> (Vec<String>, Vec<String>) {
+        let mut entries = BTreeMap::new();
+        entries.insert(NAME.to_string(), self.name.clone());
+        if let Some(description) = &self.description {
+            entries.insert(DESCRIPTION.to_string(), description.clone());
+        }
+        if let Some(image) = &self.image {
+            entries.insert(IMAGE.to_string(), image.clone());
+        }
+        entries.extend(self.links.clone());
+        entries.extend(self.other.clone());
+        entries
+            .into_iter()
+            .filter(|(key, value)| key == NAME || !value.is_empty())
+            .unzip()
+    }
+
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn set_description(&mut self, description: Option<&str>) -> &mut Self {
+        self.description = description.map(str::to_string);
+        self
+    }
+
+    pub fn set_image(&mut self, image: Option<&str>) -> &mut Self {
+        self.image = image.map(str::to_string);
+        self
+    }
+
+    // replaces all the links, errors on keys that are not in LINK_KEYS
+    pub fn set_links(&mut self, links: &[(&str, &str)]) -> Result<&mut Self> {
+        if let Some((key, _)) = links.iter().find(|(key, _)| !LINK_KEYS.contains(key)) {
+            return Err(anyhow!(
+                "Unknown link {}, expected one of: {}",
+                key,
+                LINK_KEYS.join(", ")
+            ));
+        }
+        self.links = links
+            .iter()
+            .map(|(key, url)| (key.to_string(), url.to_string()))
+            .collect();
+        Ok(self)
+    }
+
+    pub fn link(&self, key: &str) -> Option<&str> {
+        self.links.get(key).map(String::as_str)
+    }
+
+    // carried by the description of a config intent, since it has no metadata action
+    pub fn to_intent_description(&self) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            METADATA_INTENT_PREFIX,
+            serde_json::to_string(self)?
+        ))
+    }
+
+    // None if the intent doesn't propose a profile
+    pub fn from_intent_description(description: &str) -> Option<Result<Self>> {
+        let json = description.strip_prefix(METADATA_INTENT_PREFIX)?;
+        Some(
+            serde_json::from_str(json)
+                .map_err(|e| anyhow!("Invalid profile in the intent description: {}", e)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_roundtrip() {
+        let metadata = [
+            ("name", "Treasury"),
+            ("twitter", "https://x.com/treasury"),
+            ("color", "blue"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+
+        let mut profile = AccountProfile::from_metadata(&metadata);
+        assert_eq!(profile.name, "Treasury");
+        assert_eq!(profile.link("twitter"), Some("https://x.com/treasury"));
+        assert_eq!(profile.other.get("color").map(String::as_str), Some("blue"));
+
+        profile
+            .set_description(Some("Team funds"))
+            .set_links(&[("website", "https://treasury.xyz"), ("github", "")])
+            .unwrap();
+        assert!(profile.set_links(&[("myspace", "")]).is_err());
+        let description = profile.to_intent_description().unwrap();
+        assert_eq!(
+            AccountProfile::from_intent_description(&description)
+                .unwrap()
+                .unwrap(),
+            profile
+        );
+        assert!(AccountProfile::from_intent_description("pay the team").is_none());
+        assert_eq!(
+            profile.to_metadata(),
+            (
+                vec!["color", "description", "name", "website"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                vec!["blue", "Team funds", "Treasury", "https://treasury.xyz"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            )
+        );
+    }
+}
@@ -5,6 +5,8 @@ use sui_sdk_types::{Address, TypeTag};
 use crate::move_binding::account_actions as aa;
 use crate::move_binding::account_multisig as am;
 use crate::move_binding::account_protocol as ap;
+use crate::roles::{Package, Role};
+use crate::utils;
 
 // === IntentActions ===
 
@@ -48,8 +50,12 @@ pub struct ConfigDepsFields {
     pub deps: Vec<(String, Address, u64)>,
 }
 
+// the action only flips the flag, the new value is computed from the current state
+// of the multisig (see MultisigClient::intent_actions)
 #[derive(Debug, Clone)]
-pub struct ToggleUnverifiedAllowedFields {}
+pub struct ToggleUnverifiedAllowedFields {
+    pub will_become: Option<bool>,
+}
 
 #[derive(Debug, Clone)]
 pub struct BorrowCapFields {
@@ -218,6 +224,19 @@ impl IntentActions {
             }
         }
     }
+
+    // objects owned by the account that the intent withdraws, locked until it's executed or deleted
+    pub fn withdrawn_objects(&self) -> Vec<Address> {
+        match self {
+            IntentActions::WithdrawAndBurn(fields) => vec![fields.coin_id],
+            IntentActions::WithdrawAndTransferToVault(fields) => vec![fields.coin_id],
+            IntentActions::WithdrawAndTransfer(fields) => {
+                fields.transfers.iter().map(|(id, _)| *id).collect()
+            }
+            IntentActions::WithdrawAndVest(fields) => vec![fields.coin_id],
+            _ => Vec::new(),
+        }
+    }
 }
 
 // === IntentType ===
@@ -273,6 +292,69 @@ impl TryFrom<&str> for IntentType {
 }
 
 impl IntentType {
+    pub const ALL: [IntentType; 18] = [
+        IntentType::ConfigMultisig,
+        IntentType::ConfigDeps,
+        IntentType::ToggleUnverifiedAllowed,
+        IntentType::BorrowCap,
+        IntentType::DisableRules,
+        IntentType::UpdateMetadata,
+        IntentType::MintAndTransfer,
+        IntentType::MintAndVest,
+        IntentType::WithdrawAndBurn,
+        IntentType::TakeNfts,
+        IntentType::ListNfts,
+        IntentType::WithdrawAndTransferToVault,
+        IntentType::WithdrawAndTransfer,
+        IntentType::WithdrawAndVest,
+        IntentType::UpgradePackage,
+        IntentType::RestrictPolicy,
+        IntentType::SpendAndTransfer,
+        IntentType::SpendAndVest,
+    ];
+
+    // role attached to the intent on creation (package + module of the intent)
+    pub fn role(&self) -> Role {
+        match self {
+            IntentType::ConfigMultisig => Role::for_module(Package::Multisig, "config"),
+            IntentType::ConfigDeps => Role::for_module(Package::Protocol, "config"),
+            IntentType::ToggleUnverifiedAllowed => Role::for_module(Package::Protocol, "config"),
+            IntentType::BorrowCap => Role::for_module(Package::Actions, "access_control_intents"),
+            IntentType::DisableRules => Role::for_module(Package::Actions, "currency_intents"),
+            IntentType::UpdateMetadata => Role::for_module(Package::Actions, "currency_intents"),
+            IntentType::MintAndTransfer => Role::for_module(Package::Actions, "currency_intents"),
+            IntentType::MintAndVest => Role::for_module(Package::Actions, "currency_intents"),
+            IntentType::WithdrawAndBurn => Role::for_module(Package::Actions, "currency_intents"),
+            IntentType::TakeNfts => Role::for_module(Package::Actions, "kiosk_intents"),
+            IntentType::ListNfts => Role::for_module(Package::Actions, "kiosk_intents"),
+            IntentType::WithdrawAndTransferToVault => {
+                Role::for_module(Package::Actions, "owned_intents")
+            }
+            IntentType::WithdrawAndTransfer => Role::for_module(Package::Actions, "owned_intents"),
+            IntentType::WithdrawAndVest => Role::for_module(Package::Actions, "owned_intents"),
+            IntentType::UpgradePackage => {
+                Role::for_module(Package::Actions, "package_upgrade_intents")
+            }
+            IntentType::RestrictPolicy => {
+                Role::for_module(Package::Actions, "package_upgrade_intents")
+            }
+            IntentType::SpendAndTransfer => Role::for_module(Package::Actions, "vault_intents"),
+            IntentType::SpendAndVest => Role::for_module(Package::Actions, "vault_intents"),
+        }
+    }
+
+    // the other intents are created with a single execution time
+    pub fn allows_recurring(&self) -> bool {
+        matches!(
+            self,
+            IntentType::BorrowCap
+                | IntentType::MintAndTransfer
+                | IntentType::MintAndVest
+                | IntentType::SpendAndTransfer
+                | IntentType::SpendAndVest
+        )
+    }
+
     pub fn count_repetitions(&self, actions: &[(Vec<TypeTag>, Vec<u8>)]) -> Result<usize> {
         match self {
             IntentType::ConfigMultisig => Ok(1),
@@ -296,13 +378,58 @@ impl IntentType {
         }
     }
 
+    // (min number of actions, whether they go by pairs), so that decoding never indexes out
+    // of bounds on a malformed bag
+    fn actions_shape(&self) -> (usize, bool) {
+        match self {
+            IntentType::MintAndTransfer
+            | IntentType::WithdrawAndTransfer
+            | IntentType::SpendAndTransfer => (2, true),
+            IntentType::MintAndVest
+            | IntentType::WithdrawAndBurn
+            | IntentType::WithdrawAndTransferToVault
+            | IntentType::WithdrawAndVest
+            | IntentType::SpendAndVest => (2, false),
+            IntentType::TakeNfts | IntentType::ListNfts => (0, false),
+            _ => (1, false),
+        }
+    }
+
+    fn check_actions_shape(&self, actions: &[(Vec<TypeTag>, Vec<u8>)]) -> Result<()> {
+        let (min, pairs) = self.actions_shape();
+        if actions.len() < min {
+            return Err(self.malformed(format!("{} action(s), expected {}", actions.len(), min)));
+        }
+        if pairs && actions.len() % 2 != 0 {
+            return Err(self.malformed(format!("{} actions, expected pairs", actions.len())));
+        }
+        Ok(())
+    }
+
+    fn type_param(&self, actions: &[(Vec<TypeTag>, Vec<u8>)], index: usize) -> Result<String> {
+        actions
+            .get(index)
+            .and_then(|(type_params, _)| type_params.first())
+            .map(|type_param| type_param.to_string())
+            .ok_or_else(|| self.malformed(format!("no type parameter for action {}", index)))
+    }
+
+    fn malformed(&self, reason: String) -> anyhow::Error {
+        MultisigError::MalformedActions {
+            intent_type: format!("{:?}", self),
+            reason,
+        }
+        .into()
+    }
+
     pub fn deserialize_actions(
         &self,
         actions: &[(Vec<TypeTag>, Vec<u8>)],
     ) -> Result<IntentActions> {
+        self.check_actions_shape(actions)?;
         match self {
             IntentType::ConfigMultisig => {
-                let action: am::config::ConfigMultisigAction = bcs::from_bytes(&actions[0].1)?;
+                let action: am::config::ConfigMultisigAction = utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::ConfigMultisig(ConfigMultisigFields {
                     global: action.config.global,
                     members: action
@@ -331,7 +458,7 @@ impl IntentType {
                 }))
             }
             IntentType::ConfigDeps => {
-                let action: ap::config::ConfigDepsAction = bcs::from_bytes(&actions[0].1)?;
+                let action: ap::config::ConfigDepsAction = utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::ConfigDeps(ConfigDepsFields {
                     deps: action
                         .deps
@@ -342,21 +469,22 @@ impl IntentType {
             }
             IntentType::ToggleUnverifiedAllowed => {
                 let _action: ap::config::ToggleUnverifiedAllowedAction =
-                    bcs::from_bytes(&actions[0].1)?;
+                    utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::ToggleUnverifiedAllowed(
-                    ToggleUnverifiedAllowedFields {},
+                    ToggleUnverifiedAllowedFields { will_become: None },
                 ))
             }
             IntentType::BorrowCap => {
-                let _action: aa::access_control::BorrowAction<()> = bcs::from_bytes(&actions[0].1)?;
+                let _action: aa::access_control::BorrowAction<()> =
+                    utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::BorrowCap(BorrowCapFields {
-                    cap_type: actions[0].0[0].to_string(),
+                    cap_type: self.type_param(actions, 0)?,
                 }))
             }
             IntentType::DisableRules => {
-                let action: aa::currency::DisableAction<()> = bcs::from_bytes(&actions[0].1)?;
+                let action: aa::currency::DisableAction<()> = utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::DisableRules(DisableRulesFields {
-                    coin_type: actions[0].0[0].to_string(),
+                    coin_type: self.type_param(actions, 0)?,
                     mint: action.mint,
                     burn: action.burn,
                     update_symbol: action.update_symbol,
@@ -366,9 +494,9 @@ impl IntentType {
                 }))
             }
             IntentType::UpdateMetadata => {
-                let action: aa::currency::UpdateAction<()> = bcs::from_bytes(&actions[0].1)?;
+                let action: aa::currency::UpdateAction<()> = utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::UpdateMetadata(UpdateMetadataFields {
-                    coin_type: actions[0].0[0].to_string(),
+                    coin_type: self.type_param(actions, 0)?,
                     new_name: action.name,
                     new_symbol: action.symbol,
                     new_description: action.description,
@@ -378,22 +506,22 @@ impl IntentType {
             IntentType::MintAndTransfer => {
                 let mut transfers = Vec::new();
                 for chunk in actions.chunks(2) {
-                    let mint: aa::currency::MintAction<()> = bcs::from_bytes(&chunk[0].1)?;
-                    let transfer: aa::transfer::TransferAction = bcs::from_bytes(&chunk[1].1)?;
+                    let mint: aa::currency::MintAction<()> = utils::decode(&chunk[0].1, None)?;
+                    let transfer: aa::transfer::TransferAction = utils::decode(&chunk[1].1, None)?;
                     transfers.push((mint.amount, transfer.recipient));
                 }
 
                 Ok(IntentActions::MintAndTransfer(MintAndTransferFields {
-                    coin_type: actions[0].0[0].to_string(),
+                    coin_type: self.type_param(actions, 0)?,
                     transfers,
                 }))
             }
             IntentType::MintAndVest => {
-                let mint: aa::currency::MintAction<()> = bcs::from_bytes(&actions[0].1)?;
-                let vest: aa::vesting::VestAction = bcs::from_bytes(&actions[1].1)?;
+                let mint: aa::currency::MintAction<()> = utils::decode(&actions[0].1, None)?;
+                let vest: aa::vesting::VestAction = utils::decode(&actions[1].1, None)?;
 
                 Ok(IntentActions::MintAndVest(MintAndVestFields {
-                    coin_type: actions[0].0[0].to_string(),
+                    coin_type: self.type_param(actions, 0)?,
                     amount: mint.amount,
                     start: vest.start_timestamp,
                     end: vest.end_timestamp,
@@ -401,11 +529,11 @@ impl IntentType {
                 }))
             }
             IntentType::WithdrawAndBurn => {
-                let withdraw: ap::owned::WithdrawAction = bcs::from_bytes(&actions[0].1)?;
-                let burn: aa::currency::BurnAction<()> = bcs::from_bytes(&actions[1].1)?;
+                let withdraw: ap::owned::WithdrawAction = utils::decode(&actions[0].1, None)?;
+                let burn: aa::currency::BurnAction<()> = utils::decode(&actions[1].1, None)?;
 
                 Ok(IntentActions::WithdrawAndBurn(WithdrawAndBurnFields {
-                    coin_type: actions[1].0[0].to_string(),
+                    coin_type: self.type_param(actions, 1)?,
                     coin_id: withdraw.object_id.into(),
                     amount: burn.amount,
                 }))
@@ -414,7 +542,7 @@ impl IntentType {
                 let (mut kiosk_name, mut recipient) = (String::new(), Address::ZERO);
                 let mut nft_ids = Vec::new();
                 for action in actions {
-                    let take: aa::kiosk::TakeAction = bcs::from_bytes(&action.1)?;
+                    let take: aa::kiosk::TakeAction = utils::decode(&action.1, None)?;
                     if kiosk_name.is_empty() {
                         kiosk_name = take.name.to_owned();
                     }
@@ -434,7 +562,7 @@ impl IntentType {
                 let mut kiosk_name = String::new();
                 let mut listings = Vec::new();
                 for action in actions {
-                    let list: aa::kiosk::ListAction = bcs::from_bytes(&action.1)?;
+                    let list: aa::kiosk::ListAction = utils::decode(&action.1, None)?;
                     if kiosk_name.is_empty() {
                         kiosk_name = list.name.to_owned();
                     }
@@ -447,12 +575,12 @@ impl IntentType {
                 }))
             }
             IntentType::WithdrawAndTransferToVault => {
-                let withdraw: ap::owned::WithdrawAction = bcs::from_bytes(&actions[0].1)?;
-                let deposit: aa::vault::DepositAction<()> = bcs::from_bytes(&actions[1].1)?;
+                let withdraw: ap::owned::WithdrawAction = utils::decode(&actions[0].1, None)?;
+                let deposit: aa::vault::DepositAction<()> = utils::decode(&actions[1].1, None)?;
 
                 Ok(IntentActions::WithdrawAndTransferToVault(
                     WithdrawAndTransferToVaultFields {
-                        coin_type: actions[0].0[0].to_string(),
+                        coin_type: self.type_param(actions, 0)?,
                         coin_id: withdraw.object_id.into(),
                         coin_amount: deposit.amount,
                         vault_name: deposit.name.to_owned(),
@@ -462,8 +590,8 @@ impl IntentType {
             IntentType::WithdrawAndTransfer => {
                 let mut transfers = Vec::new();
                 for chunk in actions.chunks(2) {
-                    let withdraw: ap::owned::WithdrawAction = bcs::from_bytes(&chunk[0].1)?;
-                    let transfer: aa::transfer::TransferAction = bcs::from_bytes(&chunk[1].1)?;
+                    let withdraw: ap::owned::WithdrawAction = utils::decode(&chunk[0].1, None)?;
+                    let transfer: aa::transfer::TransferAction = utils::decode(&chunk[1].1, None)?;
                     transfers.push((withdraw.object_id.into(), transfer.recipient));
                 }
 
@@ -472,8 +600,8 @@ impl IntentType {
                 ))
             }
             IntentType::WithdrawAndVest => {
-                let withdraw: ap::owned::WithdrawAction = bcs::from_bytes(&actions[0].1)?;
-                let vest: aa::vesting::VestAction = bcs::from_bytes(&actions[1].1)?;
+                let withdraw: ap::owned::WithdrawAction = utils::decode(&actions[0].1, None)?;
+                let vest: aa::vesting::VestAction = utils::decode(&actions[1].1, None)?;
 
                 Ok(IntentActions::WithdrawAndVest(WithdrawAndVestFields {
                     coin_id: withdraw.object_id.into(),
@@ -483,14 +611,16 @@ impl IntentType {
                 }))
             }
             IntentType::UpgradePackage => {
-                let upgrade: aa::package_upgrade::UpgradeAction = bcs::from_bytes(&actions[0].1)?;
+                let upgrade: aa::package_upgrade::UpgradeAction =
+                    utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::UpgradePackage(UpgradePackageFields {
                     package_name: upgrade.name.to_owned(),
                     digest: upgrade.digest.to_vec(),
                 }))
             }
             IntentType::RestrictPolicy => {
-                let restrict: aa::package_upgrade::RestrictAction = bcs::from_bytes(&actions[0].1)?;
+                let restrict: aa::package_upgrade::RestrictAction =
+                    utils::decode(&actions[0].1, None)?;
                 Ok(IntentActions::RestrictPolicy(RestrictPolicyFields {
                     package_name: restrict.name.to_owned(),
                     policy: Policy::try_from(restrict.policy)?,
@@ -500,8 +630,8 @@ impl IntentType {
                 let mut vault_name = String::new();
                 let mut transfers = Vec::new();
                 for chunk in actions.chunks(2) {
-                    let spend: aa::vault::SpendAction<()> = bcs::from_bytes(&chunk[0].1)?;
-                    let transfer: aa::transfer::TransferAction = bcs::from_bytes(&chunk[1].1)?;
+                    let spend: aa::vault::SpendAction<()> = utils::decode(&chunk[0].1, None)?;
+                    let transfer: aa::transfer::TransferAction = utils::decode(&chunk[1].1, None)?;
                     if vault_name.is_empty() {
                         vault_name = spend.name.to_owned();
                     }
@@ -510,17 +640,17 @@ impl IntentType {
 
                 Ok(IntentActions::SpendAndTransfer(SpendAndTransferFields {
                     vault_name,
-                    coin_type: actions[0].0[0].to_string(),
+                    coin_type: self.type_param(actions, 0)?,
                     transfers,
                 }))
             }
             IntentType::SpendAndVest => {
-                let spend: aa::vault::SpendAction<()> = bcs::from_bytes(&actions[0].1)?;
-                let vest: aa::vesting::VestAction = bcs::from_bytes(&actions[1].1)?;
+                let spend: aa::vault::SpendAction<()> = utils::decode(&actions[0].1, None)?;
+                let vest: aa::vesting::VestAction = utils::decode(&actions[1].1, None)?;
 
                 Ok(IntentActions::SpendAndVest(SpendAndVestFields {
                     vault_name: spend.name.to_owned(),
-                    coin_type: actions[0].0[0].to_string(),
+                    coin_type: self.type_param(actions, 0)?,
                     amount: spend.amount,
                     start: vest.start_timestamp,
                     end: vest.end_timestamp,
@@ -530,3 +660,49 @@ impl IntentType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn is_malformed(result: Result<IntentActions>) -> bool {
+        matches!(
+            result.unwrap_err().downcast_ref::<MultisigError>(),
+            Some(MultisigError::MalformedActions { .. })
+        )
+    }
+
+    #[test]
+    fn reject_malformed_shapes() {
+        let action = (vec![TypeTag::U64], vec![0; 8]);
+        let actions = [action.clone(), action.clone(), action];
+        assert!(is_malformed(
+            IntentType::MintAndTransfer.deserialize_actions(&actions)
+        ));
+        assert!(is_malformed(
+            IntentType::MintAndVest.deserialize_actions(&actions[..1])
+        ));
+        assert!(is_malformed(
+            IntentType::ConfigMultisig.deserialize_actions(&[])
+        ));
+    }
+
+    // random bags decode or fail with an error, they never panic
+    #[test]
+    fn deserialize_random_actions() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..2000 {
+            let mut actions = Vec::new();
+            for _ in 0..rng.gen_range(0..6) {
+                let type_params = vec![TypeTag::U64; rng.gen_range(0..2)];
+                let mut bytes = vec![0u8; rng.gen_range(0..80)];
+                rng.fill(bytes.as_mut_slice());
+                actions.push((type_params, bytes));
+            }
+            for intent_type in IntentType::ALL {
+                let _ = intent_type.deserialize_actions(&actions);
+            }
+        }
+    }
+}
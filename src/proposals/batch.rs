@@ -0,0 +1,504 @@
+use anyhow::{anyhow, Result};
+use move_types::functions::Arg;
+use sui_sdk_types::{Address, ObjectId, TransactionDigest, TransactionEffects};
+use sui_transaction_builder::TransactionBuilder;
+
+use crate::move_binding::{account_multisig as am, account_protocol as ap, sui};
+use crate::proposals::params::{self, ParamsArgs};
+use crate::proposals::receipt::ExecutionReceipt;
+use crate::types::IntoStructType;
+use crate::utils;
+use crate::MultisigClient;
+
+// inputs shared by every request built in the same PTB
+pub struct RequestInputs {
+    pub multisig: Arg<ap::account::Account<am::multisig::Multisig>>,
+    pub clock: Arg<sui::clock::Clock>,
+}
+
+// splits large transfer intents so they fit in a transaction
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkPolicy {
+    // an execution mints/spends and transfers each entry (2 commands per transfer)
+    pub max_transfers_per_intent: usize,
+    // execution times run per transaction when executing
+    pub max_executions_per_tx: usize,
+    // executions are also capped to stay under the protocol limit of 1024 commands
+    pub max_commands_per_tx: usize,
+    // per transaction, covered by as many gas coins as needed
+    pub gas_budget: u64,
+}
+
+impl Default for ChunkPolicy {
+    fn default() -> Self {
+        Self {
+            max_transfers_per_intent: 200,
+            max_executions_per_tx: 1,
+            max_commands_per_tx: 1000,
+            gas_budget: utils::DEFAULT_GAS_BUDGET,
+        }
+    }
+}
+
+// transactions sent by MultisigClient::execute_chunked, in execution order
+#[derive(Debug, Default)]
+pub struct ExecutionGroup {
+    pub digests: Vec<TransactionDigest>,
+    pub effects: Vec<TransactionEffects>,
+    // one per transaction, the transfers of the executions it ran
+    pub receipts: Vec<ExecutionReceipt>,
+}
+
+// proposes several intents atomically in one transaction,
+// keys are generated from the prefix: <prefix>-1, <prefix>-2, ...
+// a failed request leaves its inputs in the builder: the batch refuses the next requests and the
+// builder must be discarded
+pub struct ProposalBatch<'a> {
+    client: &'a MultisigClient,
+    builder: &'a mut TransactionBuilder,
+    inputs: RequestInputs,
+    key_prefix: String,
+    execution_times: Vec<u64>,
+    expiration_time: u64,
+    keys: Vec<String>,
+    // set until the request is recorded
+    failed: bool,
+}
+
+impl<'a> ProposalBatch<'a> {
+    pub async fn new(
+        client: &'a MultisigClient,
+        builder: &'a mut TransactionBuilder,
+        key_prefix: &str,
+    ) -> Result<Self> {
+        let inputs = client.request_inputs(builder).await?;
+        // client defaults, shared by all the intents of the batch
+        let (execution_times, expiration_time) = client.intent_params(key_prefix).await?.resolve();
+        Ok(Self {
+            client,
+            builder,
+            inputs,
+            key_prefix: key_prefix.to_string(),
+            execution_times,
+            expiration_time,
+            keys: Vec::new(),
+            failed: false,
+        })
+    }
+
+    pub fn set_execution_times(mut self, execution_times: Vec<u64>) -> Self {
+        self.execution_times = execution_times;
+        self
+    }
+
+    pub fn set_expiration_time(mut self, expiration_time: u64) -> Self {
+        self.expiration_time = expiration_time;
+        self
+    }
+
+    // to construct the actions args of the requests
+    pub fn builder(&mut self) -> &mut TransactionBuilder {
+        self.builder
+    }
+
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    pub fn finish(self) -> Vec<String> {
+        self.keys
+    }
+
+    pub async fn request_config_multisig(
+        &mut self,
+        description: &str,
+        actions_args: params::ConfigMultisigArgs,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_config_multisig_with(self.builder, &mut self.inputs, intent_args, actions_args)
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_config_deps(
+        &mut self,
+        description: &str,
+        actions_args: params::ConfigDepsArgs,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_config_deps_with(self.builder, &mut self.inputs, intent_args, actions_args)
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_toggle_unverified_allowed(&mut self, description: &str) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_toggle_unverified_allowed_with(self.builder, &mut self.inputs, intent_args)
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_borrow_cap(
+        &mut self,
+        description: &str,
+        cap_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_borrow_cap_with(self.builder, &mut self.inputs, intent_args, cap_type)
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_disable_rules(
+        &mut self,
+        description: &str,
+        actions_args: params::DisableRulesArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_disable_rules_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_update_metadata(
+        &mut self,
+        description: &str,
+        actions_args: params::UpdateMetadataArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_update_metadata_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_mint_and_transfer(
+        &mut self,
+        description: &str,
+        actions_args: params::MintAndTransferArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_mint_and_transfer_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_mint_and_vest(
+        &mut self,
+        description: &str,
+        actions_args: params::MintAndVestArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_mint_and_vest_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_withdraw_and_burn(
+        &mut self,
+        description: &str,
+        actions_args: params::WithdrawAndBurnArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_withdraw_and_burn_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_withdraw_and_transfer_to_vault(
+        &mut self,
+        description: &str,
+        actions_args: params::WithdrawAndTransferToVaultArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_withdraw_and_transfer_to_vault_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_withdraw_and_transfer(
+        &mut self,
+        description: &str,
+        actions_args: params::WithdrawAndTransferArgs,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_withdraw_and_transfer_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_withdraw_and_vest(
+        &mut self,
+        description: &str,
+        actions_args: params::WithdrawAndVestArgs,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_withdraw_and_vest_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_upgrade_package(
+        &mut self,
+        description: &str,
+        actions_args: params::UpgradePackageArgs,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_upgrade_package_with(self.builder, &mut self.inputs, intent_args, actions_args)
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_restrict_policy(
+        &mut self,
+        description: &str,
+        actions_args: params::RestrictPolicyArgs,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_restrict_policy_with(self.builder, &mut self.inputs, intent_args, actions_args)
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_spend_and_transfer(
+        &mut self,
+        description: &str,
+        actions_args: params::SpendAndTransferArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_spend_and_transfer_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    pub async fn request_spend_and_vest(
+        &mut self,
+        description: &str,
+        actions_args: params::SpendAndVestArgs,
+        coin_type: impl IntoStructType,
+    ) -> Result<String> {
+        let (key, intent_args) = self.next_params(description)?;
+        self.client
+            .request_spend_and_vest_with(
+                self.builder,
+                &mut self.inputs,
+                intent_args,
+                actions_args,
+                coin_type,
+            )
+            .await?;
+        Ok(self.record(key))
+    }
+
+    // one intent per chunk of transfers, returns the keys of the intents
+    pub async fn request_mint_and_transfer_chunked(
+        &mut self,
+        description: &str,
+        amounts: Vec<u64>,
+        recipients: Vec<Address>,
+        coin_type: impl IntoStructType,
+        policy: ChunkPolicy,
+    ) -> Result<Vec<String>> {
+        let coin_type = coin_type.into_struct_type()?;
+        let mut keys = Vec::new();
+        for (amounts, recipients) in chunk_transfers(&amounts, &recipients, policy)? {
+            let actions_args = params::MintAndTransferArgs::new(self.builder, amounts, recipients);
+            keys.push(
+                self.request_mint_and_transfer(description, actions_args, coin_type.clone())
+                    .await?,
+            );
+        }
+        Ok(keys)
+    }
+
+    // one intent per chunk of transfers, returns the keys of the intents
+    pub async fn request_spend_and_transfer_chunked(
+        &mut self,
+        description: &str,
+        vault_name: &str,
+        amounts: Vec<u64>,
+        recipients: Vec<Address>,
+        coin_type: impl IntoStructType,
+        policy: ChunkPolicy,
+    ) -> Result<Vec<String>> {
+        let coin_type = coin_type.into_struct_type()?;
+        let mut keys = Vec::new();
+        for (amounts, recipients) in chunk_transfers(&amounts, &recipients, policy)? {
+            let actions_args = params::SpendAndTransferArgs::new(
+                self.builder,
+                vault_name.to_string(),
+                amounts,
+                recipients,
+            );
+            keys.push(
+                self.request_spend_and_transfer(description, actions_args, coin_type.clone())
+                    .await?,
+            );
+        }
+        Ok(keys)
+    }
+
+    // withdraws all the objects matching the filter (except the locked ones) to the recipient,
+    // one intent per chunk of objects, returns the keys of the intents
+    pub async fn request_sweep_objects(
+        &mut self,
+        description: &str,
+        type_filter: impl IntoStructType,
+        recipient: Address,
+        policy: ChunkPolicy,
+    ) -> Result<Vec<String>> {
+        let filter = type_filter.into_struct_type()?;
+        let multisig = self
+            .client
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?;
+        let object_ids = self
+            .client
+            .owned_objects()
+            .ok_or(anyhow!("Owned objects not loaded"))?
+            .ids_matching(&filter)
+            .into_iter()
+            .filter(|id| !multisig.locked_objects.contains(id))
+            .map(ObjectId::from)
+            .collect::<Vec<_>>();
+        if object_ids.is_empty() {
+            return Err(anyhow!("No object of type {} to sweep", filter));
+        }
+
+        let mut keys = Vec::new();
+        for object_ids in object_ids.chunks(policy.max_transfers_per_intent.max(1)) {
+            let actions_args = params::WithdrawAndTransferArgs::new(
+                self.builder,
+                object_ids.to_vec(),
+                vec![recipient; object_ids.len()],
+            );
+            keys.push(
+                self.request_withdraw_and_transfer(description, actions_args)
+                    .await?,
+            );
+        }
+        Ok(keys)
+    }
+
+    // === Helpers ===
+
+    // the key is only recorded once the request is added, see record
+    fn next_params(&mut self, description: &str) -> Result<(String, ParamsArgs)> {
+        if self.failed {
+            return Err(anyhow!(
+                "A previous request of the batch failed, discard the transaction"
+            ));
+        }
+        // cleared by record if the request succeeds
+        self.failed = true;
+        let key = format!("{}-{}", self.key_prefix, self.keys.len() + 1);
+
+        let params = ParamsArgs::new(
+            self.builder,
+            key.clone(),
+            description.to_string(),
+            self.execution_times.clone(),
+            self.expiration_time,
+        );
+        Ok((key, params))
+    }
+
+    fn record(&mut self, key: String) -> String {
+        self.failed = false;
+        self.keys.push(key.clone());
+        key
+    }
+}
+
+fn chunk_transfers(
+    amounts: &[u64],
+    recipients: &[Address],
+    policy: ChunkPolicy,
+) -> Result<Vec<(Vec<u64>, Vec<Address>)>> {
+    if amounts.len() != recipients.len() {
+        return Err(anyhow!(
+            "{} amounts for {} recipients",
+            amounts.len(),
+            recipients.len()
+        ));
+    }
+    let size = policy.max_transfers_per_intent.max(1);
+    Ok(amounts
+        .chunks(size)
+        .zip(recipients.chunks(size))
+        .map(|(amounts, recipients)| (amounts.to_vec(), recipients.to_vec()))
+        .collect())
+}
@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Result};
+use move_types::{functions::Arg, TypeTag};
+use sui_sdk_types::Argument;
+use sui_transaction_builder::TransactionBuilder;
+
+use crate::move_binding::{account_multisig as am, account_protocol as ap};
+use crate::MultisigClient;
+
+// Cap borrowed from the multisig in a PTB (MultisigClient::borrow_cap), it must be given back
+// with finish before the transaction is built: the PTB aborts if the Cap isn't returned.
+// prefer MultisigClient::execute_with_borrowed_cap which returns it for you, dropping it
+// unreturned only fails a debug assertion, call abandon when the builder is discarded.
+// the Cap type is only known from the intent at runtime, hence the type tag instead of a generic
+#[must_use = "the Cap must be returned with BorrowedCap::finish"]
+pub struct BorrowedCap {
+    intent_key: String,
+    cap_type: TypeTag,
+    cap: Argument,
+    // taken by finish
+    multisig: Option<Arg<ap::account::Account<am::multisig::Multisig>>>,
+    executable: Option<Arg<ap::executable::Executable<am::multisig::Approvals>>>,
+}
+
+impl BorrowedCap {
+    pub fn new(
+        intent_key: &str,
+        cap_type: TypeTag,
+        cap: Argument,
+        multisig: Arg<ap::account::Account<am::multisig::Multisig>>,
+        executable: Arg<ap::executable::Executable<am::multisig::Approvals>>,
+    ) -> Self {
+        Self {
+            intent_key: intent_key.to_string(),
+            cap_type,
+            cap,
+            multisig: Some(multisig),
+            executable: Some(executable),
+        }
+    }
+
+    // the Cap to pass by reference to the move calls between borrow and return
+    pub fn arg(&self) -> Argument {
+        self.cap
+    }
+
+    pub fn cap_type(&self) -> &TypeTag {
+        &self.cap_type
+    }
+
+    pub fn intent_key(&self) -> &str {
+        &self.intent_key
+    }
+
+    // returns the Cap, confirms the execution and cleans up the intent after the last one
+    pub async fn finish(
+        mut self,
+        client: &mut MultisigClient,
+        builder: &mut TransactionBuilder,
+    ) -> Result<()> {
+        let (multisig, executable) = self.take()?;
+        client
+            .execute_return_cap(builder, multisig, executable, self.cap, &self.intent_key)
+            .await
+    }
+
+    // for when the transaction won't be built (e.g. an error while using the Cap)
+    pub fn abandon(mut self) {
+        self.multisig = None;
+        self.executable = None;
+    }
+
+    fn take(
+        &mut self,
+    ) -> Result<(
+        Arg<ap::account::Account<am::multisig::Multisig>>,
+        Arg<ap::executable::Executable<am::multisig::Approvals>>,
+    )> {
+        self.multisig
+            .take()
+            .zip(self.executable.take())
+            .ok_or(anyhow!("Cap already returned"))
+    }
+}
+
+impl Drop for BorrowedCap {
+    fn drop(&mut self) {
+        // don't panic while unwinding
+        debug_assert!(
+            self.multisig.is_none() || std::thread::panicking(),
+            "Cap {} borrowed by intent {} was not returned, call BorrowedCap::finish",
+            self.cap_type,
+            self.intent_key
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use anyhow::{anyhow, Result};
+use base64ct::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use sui_crypto::ed25519::{Ed25519PrivateKey, Ed25519Verifier};
+use sui_crypto::{SuiSigner, SuiVerifier};
+use sui_sdk_types::{hash::Hasher, Address, PersonalMessage, SimpleSignature, UserSignature};
+
+use crate::errors::MultisigError;
+use crate::multisig::Multisig;
+use crate::proposals::intents::Intent;
+
+// approval request shared with the other members (e.g. over chat) once an intent is proposed:
+// each member checks it against the intent on chain and approves it with their own transaction
+// (MultisigClient::approve_request), the coordinator follows the approvals with progress.
+// the intent is refetched with its actions before each check, see refetch_intent
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub multisig: Address,
+    pub key: String,
+    pub intent_type: String,
+    pub description: String,
+    // see intent_digest
+    pub digest: String,
+    // members whose approval was missing when the request was created
+    pub requested: Vec<Address>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalProgress {
+    pub approved: Vec<Address>,
+    // requested members who haven't approved yet
+    pub pending: Vec<Address>,
+    pub total_weight: u64,
+    pub threshold_reached: bool,
+}
+
+// off-chain approval: a member signs a personal message naming the intent and its digest so that
+// the coordinator can tell if the quorum would be reached before anyone pays gas. it doesn't
+// approve anything, the members still approve on chain once the quorum is gathered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preapproval {
+    pub multisig: Address,
+    pub key: String,
+    // see intent_digest
+    pub digest: String,
+    pub signer: Address,
+    // base64 of the signature of preapproval_message
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreapprovalTally {
+    // approved on chain or signed a valid preapproval, each member once
+    pub approvers: Vec<Address>,
+    pub total_weight: u64,
+    pub role_weight: u64,
+    pub threshold_reached: bool,
+    // preapprovals not counted, with the reason
+    pub rejected: Vec<(Address, String)>,
+}
+
+impl ApprovalRequest {
+    pub async fn new(multisig: &mut Multisig, key: &str) -> Result<Self> {
+        refetch_intent(multisig, key).await?;
+        let intent = multisig
+            .intents
+            .as_ref()
+            .ok_or(anyhow!("Intents not loaded"))?
+            .get_intent(key)
+            .ok_or(anyhow!("Intent {} not found", key))?;
+        Self::from_intent(multisig, intent)
+    }
+
+    // the intent described by the request, errors if it was replaced or modified since
+    pub async fn verify<'a>(&self, multisig: &'a mut Multisig) -> Result<&'a Intent> {
+        refetch_intent(multisig, &self.key).await?;
+        self.check(multisig)
+    }
+
+    // approvals on chain, None once the intent is executed or deleted
+    pub async fn progress(&self, multisig: &mut Multisig) -> Result<Option<ApprovalProgress>> {
+        refetch_intent(multisig, &self.key).await?;
+        let intents = multisig
+            .intents
+            .as_ref()
+            .ok_or(anyhow!("Intents not loaded"))?;
+        if multisig.id == self.multisig && intents.get_intent(&self.key).is_none() {
+            return Ok(None);
+        }
+        let intent = self.check(multisig)?;
+        Ok(Some(ApprovalProgress {
+            approved: intent.outcome.approved.clone(),
+            pending: self
+                .requested
+                .iter()
+                .filter(|address| !intent.outcome.approved.contains(address))
+                .copied()
+                .collect(),
+            total_weight: intent.outcome.total_weight,
+            threshold_reached: multisig.is_approved(intent),
+        }))
+    }
+
+    // single line to paste in a message
+    pub fn encode(&self) -> Result<String> {
+        Ok(Base64::encode_string(&serde_json::to_vec(self)?))
+    }
+
+    pub fn decode(s: &str) -> Result<Self> {
+        let bytes =
+            Base64::decode_vec(s.trim()).map_err(|_| anyhow!("Invalid approval request"))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    // signs the request as received, check it against the intent on chain with verify first
+    pub fn preapprove(&self, pk: &Ed25519PrivateKey) -> Result<Preapproval> {
+        let message = preapproval_message(self.multisig, &self.key, &self.digest);
+        let signature = pk.sign_personal_message(&PersonalMessage(message.into()))?;
+        Ok(Preapproval {
+            multisig: self.multisig,
+            key: self.key.clone(),
+            digest: self.digest.clone(),
+            signer: pk.public_key().derive_address(),
+            signature: signature.to_base64(),
+        })
+    }
+
+    // weights of the approvals on chain and of the valid preapprovals
+    pub async fn tally(
+        &self,
+        multisig: &mut Multisig,
+        preapprovals: &[Preapproval],
+    ) -> Result<PreapprovalTally> {
+        refetch_intent(multisig, &self.key).await?;
+        self.tally_cached(multisig, preapprovals)
+    }
+
+    // against the cached intent, see tally
+    fn tally_cached(
+        &self,
+        multisig: &Multisig,
+        preapprovals: &[Preapproval],
+    ) -> Result<PreapprovalTally> {
+        let intent = self.check(multisig)?;
+        let mut approvers = intent.outcome.approved.clone();
+        let mut rejected = Vec::new();
+        for preapproval in preapprovals {
+            match check_preapproval(multisig, preapproval) {
+                Ok(_) if approvers.contains(&preapproval.signer) => {}
+                Ok(_) => approvers.push(preapproval.signer),
+                Err(e) => rejected.push((preapproval.signer, e.to_string())),
+            }
+        }
+
+        let (mut total_weight, mut role_weight) = (0, 0);
+        for member in &multisig.config.members {
+            if !member
+                .address
+                .parse::<Address>()
+                .is_ok_and(|address| approvers.contains(&address))
+            {
+                continue;
+            }
+            total_weight += member.weight;
+            if member.roles.contains(&intent.role) {
+                role_weight += member.weight;
+            }
+        }
+        let threshold_reached = total_weight >= multisig.config.global.threshold
+            || multisig
+                .config
+                .roles
+                .get(&intent.role)
+                .is_some_and(|role| role_weight >= role.threshold);
+
+        Ok(PreapprovalTally {
+            approvers,
+            total_weight,
+            role_weight,
+            threshold_reached,
+            rejected,
+        })
+    }
+
+    // the intent must have been refetched with its actions
+    fn from_intent(multisig: &Multisig, intent: &Intent) -> Result<Self> {
+        Ok(Self {
+            multisig: multisig.id,
+            key: intent.key.clone(),
+            intent_type: intent.type_.clone(),
+            description: intent.description.clone(),
+            digest: intent_digest(multisig.id, intent)?,
+            requested: intent
+                .pending_approvers(&multisig.config)
+                .iter()
+                .filter_map(|member| member.address.parse().ok())
+                .collect(),
+        })
+    }
+
+    // against the cached intent, see verify
+    fn check<'a>(&self, multisig: &'a Multisig) -> Result<&'a Intent> {
+        if multisig.id != self.multisig {
+            return Err(self.mismatch(format!("sent for multisig {}", self.multisig)));
+        }
+        let intent = multisig
+            .intents
+            .as_ref()
+            .ok_or(anyhow!("Intents not loaded"))?
+            .get_intent(&self.key)
+            .ok_or_else(|| self.mismatch("intent not found".to_string()))?;
+        if intent_digest(multisig.id, intent)? != self.digest {
+            return Err(self.mismatch("the intent has different content".to_string()));
+        }
+        Ok(intent)
+    }
+
+    fn mismatch(&self, reason: String) -> anyhow::Error {
+        MultisigError::ApprovalRequestMismatch {
+            key: self.key.clone(),
+            reason,
+        }
+        .into()
+    }
+}
+
+impl Preapproval {
+    // single line to send back to the coordinator
+    pub fn encode(&self) -> Result<String> {
+        Ok(Base64::encode_string(&serde_json::to_vec(self)?))
+    }
+
+    pub fn decode(s: &str) -> Result<Self> {
+        let bytes = Base64::decode_vec(s.trim()).map_err(|_| anyhow!("Invalid preapproval"))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+// what the members sign, readable in the wallets prompting for it
+pub fn preapproval_message(multisig: Address, key: &str, digest: &str) -> Vec<u8> {
+    format!(
+        "account.tech multisig preapproval\nmultisig: {}\nintent: {}\ndigest: {}",
+        multisig, key, digest
+    )
+    .into_bytes()
+}
+
+// weight of the signer if the preapproval is for the intent on chain and signed by a member,
+// only ed25519 signatures are supported
+pub async fn verify_preapproval(multisig: &mut Multisig, preapproval: &Preapproval) -> Result<u64> {
+    refetch_intent(multisig, &preapproval.key).await?;
+    check_preapproval(multisig, preapproval)
+}
+
+// against the cached intent, see verify_preapproval
+fn check_preapproval(multisig: &Multisig, preapproval: &Preapproval) -> Result<u64> {
+    let invalid = |reason: &str| -> anyhow::Error {
+        MultisigError::InvalidPreapproval {
+            key: preapproval.key.clone(),
+            signer: preapproval.signer,
+            reason: reason.to_string(),
+        }
+        .into()
+    };
+    if preapproval.multisig != multisig.id {
+        return Err(invalid("signed for another multisig"));
+    }
+    let intent = multisig
+        .intents
+        .as_ref()
+        .ok_or(anyhow!("Intents not loaded"))?
+        .get_intent(&preapproval.key)
+        .ok_or_else(|| invalid("intent not found"))?;
+    if intent_digest(multisig.id, intent)? != preapproval.digest {
+        return Err(invalid("the intent has different content"));
+    }
+
+    let signature = UserSignature::from_base64(&preapproval.signature)
+        .map_err(|_| invalid("malformed signature"))?;
+    let UserSignature::Simple(SimpleSignature::Ed25519 { public_key, .. }) = &signature else {
+        return Err(invalid("not an ed25519 signature"));
+    };
+    if public_key.derive_address() != preapproval.signer {
+        return Err(invalid("signed by another address"));
+    }
+    let message = preapproval_message(multisig.id, &preapproval.key, &preapproval.digest);
+    Ed25519Verifier::new()
+        .verify_personal_message(&PersonalMessage(message.into()), &signature)
+        .map_err(|_| invalid("wrong signature"))?;
+
+    multisig
+        .config
+        .members
+        .iter()
+        .find(|member| {
+            member
+                .address
+                .parse::<Address>()
+                .is_ok_and(|address| address == preapproval.signer)
+        })
+        .map(|member| member.weight)
+        .ok_or_else(|| invalid("not a member"))
+}
+
+// refetches the intent with its actions, an intent replaced under the same key replaces the
+// cached one and an executed or deleted one is removed
+pub async fn refetch_intent(multisig: &mut Multisig, key: &str) -> Result<()> {
+    let intents = multisig
+        .intents
+        .as_mut()
+        .ok_or(anyhow!("Intents not loaded"))?;
+    intents.refresh_intent(key).await?;
+    if let Some(intent) = intents.get_intent_mut(key) {
+        intent.get_actions_args().await?;
+    }
+    Ok(())
+}
+
+// blake2b256 digest of what is approved: the intent fields and the raw actions,
+// the approvals are excluded so that it doesn't change while they are collected.
+// errors if the actions aren't loaded, see refetch_intent
+pub fn intent_digest(multisig: Address, intent: &Intent) -> Result<String> {
+    if intent.actions_args.is_none() || intent.actions_types_bcs.is_empty() {
+        return Err(anyhow!("Actions of {} not loaded", intent.key));
+    }
+    let actions = intent
+        .actions_types_bcs
+        .iter()
+        .map(|(types, bcs)| {
+            (
+                types.iter().map(|type_| type_.to_string()).collect(),
+                bcs.clone(),
+            )
+        })
+        .collect::<Vec<(Vec<String>, Vec<u8>)>>();
+    let content = bcs::to_bytes(&(
+        multisig,
+        &intent.key,
+        &intent.type_,
+        &intent.description,
+        &intent.execution_times,
+        intent.expiration_time,
+        &intent.role,
+        actions,
+    ))?;
+    Ok(Hasher::digest(content).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proposals::actions::{ConfigMultisigFields, IntentActions};
+    use crate::test_utils;
+
+    // with its actions loaded
+    fn intent() -> Intent {
+        let mut intent = test_utils::intent("key", "");
+        intent.type_ = "ConfigMultisig".to_string();
+        intent.actions_types_bcs = vec![(vec![], vec![1, 2, 3])];
+        intent.actions_args = Some(IntentActions::ConfigMultisig(ConfigMultisigFields {
+            global: 0,
+            members: Vec::new(),
+            roles: Vec::new(),
+        }));
+        intent
+    }
+
+    #[test]
+    fn digest_ignores_approvals() {
+        let mut intent = intent();
+        let digest = intent_digest(Address::ZERO, &intent).unwrap();
+
+        intent.outcome.approved.push(Address::new([0xa; 32]));
+        intent.outcome.total_weight = 1;
+        assert_eq!(intent_digest(Address::ZERO, &intent).unwrap(), digest);
+
+        intent.actions_types_bcs[0].1.push(4);
+        assert_ne!(intent_digest(Address::ZERO, &intent).unwrap(), digest);
+        intent.actions_types_bcs[0].1.pop();
+        assert_ne!(
+            intent_digest(Address::new([0xa; 32]), &intent).unwrap(),
+            digest
+        );
+        // not loaded
+        intent.actions_args = None;
+        assert!(intent_digest(Address::ZERO, &intent).is_err());
+
+        let request = ApprovalRequest {
+            multisig: Address::ZERO,
+            key: intent.key.clone(),
+            intent_type: intent.type_.clone(),
+            description: String::new(),
+            digest,
+            requested: vec![Address::new([0xb; 32])],
+        };
+        assert_eq!(
+            ApprovalRequest::decode(&request.encode().unwrap()).unwrap(),
+            request
+        );
+        assert!(ApprovalRequest::decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn preapprovals_tally() {
+        let [alice, bob] = [1u8, 2].map(|seed| Ed25519PrivateKey::new([seed; 32]));
+        let members = [&alice, &bob].map(|pk| (pk.public_key().derive_address(), vec![]));
+        let mut multisig = test_utils::multisig(&members, 2);
+        multisig.intents = Some(test_utils::intents(vec![intent()]));
+
+        let intent = multisig
+            .intents
+            .as_ref()
+            .unwrap()
+            .get_intent("key")
+            .unwrap();
+        let request = ApprovalRequest::from_intent(&multisig, intent).unwrap();
+        let preapproval = request.preapprove(&alice).unwrap();
+        assert_eq!(
+            Preapproval::decode(&preapproval.encode().unwrap()).unwrap(),
+            preapproval
+        );
+        assert_eq!(check_preapproval(&multisig, &preapproval).unwrap(), 1);
+
+        // signed by bob on behalf of alice
+        let mut forged = request.preapprove(&bob).unwrap();
+        forged.signer = alice.public_key().derive_address();
+        assert!(check_preapproval(&multisig, &forged).is_err());
+
+        let tally = request
+            .tally_cached(&multisig, &[preapproval.clone(), preapproval.clone()])
+            .unwrap();
+        assert_eq!(tally.total_weight, 1);
+        assert!(!tally.threshold_reached);
+
+        let tally = request
+            .tally_cached(
+                &multisig,
+                &[preapproval, request.preapprove(&bob).unwrap(), forged],
+            )
+            .unwrap();
+        assert_eq!(tally.total_weight, 2);
+        assert!(tally.threshold_reached);
+        assert_eq!(tally.rejected.len(), 1);
+
+        // the intent changed since the members signed
+        let intents = multisig.intents.as_mut().unwrap();
+        intents.intents.get_mut("key").unwrap().description = "changed".to_string();
+        assert!(request.tally_cached(&multisig, &[]).is_err());
+    }
+}
@@ -1,15 +1,20 @@
-use anyhow::{Ok, Result};
+use anyhow::{anyhow, Ok, Result};
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_graphql_client::Client;
-use sui_sdk_types::{Address, TypeTag};
+use sui_sdk_types::{Address, ObjectId, TypeTag};
 
-use crate::proposals::actions::{IntentType, IntentActions};
 use crate::move_binding::account_multisig as am;
 use crate::move_binding::account_protocol as ap;
+use crate::multisig::{Config, Member};
+use crate::proposals::actions::{IntentActions, IntentType};
 use crate::utils;
 
+// account.tech web app
+pub const DEFAULT_FRONTEND: &str = "https://multisig.account.tech";
+
 pub struct Intents {
     pub sui_client: Arc<Client>,
     pub bag_id: Address,
@@ -40,6 +45,26 @@ pub struct Approvals {
     pub approved: Vec<Address>,
 }
 
+// most urgent first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Urgency {
+    Expired,
+    // within a day
+    ExpiresToday,
+    // within a week
+    ExpiresThisWeek,
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadlines {
+    // None if the next execution time has passed or if there is no execution left
+    pub until_executable: Option<Duration>,
+    // None once expired
+    pub until_expiry: Option<Duration>,
+    pub urgency: Urgency,
+}
+
 impl Intents {
     pub async fn from_bag_id(sui_client: Arc<Client>, bag_id: Address) -> Result<Self> {
         let mut intents = Self {
@@ -56,37 +81,93 @@ impl Intents {
 
         for df_output in df_outputs {
             if let Some(value) = &df_output.value {
-                let intent: ap::intents::Intent<am::multisig::Approvals> =
-                    bcs::from_bytes(&value.1)?;
-                self.intents.insert(
-                    intent.key.clone(),
-                    Intent {
-                        sui_client: self.sui_client.clone(),
-                        type_: intent.type_,
-                        key: intent.key,
-                        description: intent.description,
-                        account: intent.account,
-                        creator: intent.creator,
-                        creation_time: intent.creation_time,
-                        execution_times: intent.execution_times,
-                        expiration_time: intent.expiration_time,
-                        role: intent.role,
-                        actions_bag_id: intent.actions.id.into(),
-                        actions_types_bcs: Vec::new(),
-                        actions_args: None,
-                        outcome: Approvals {
-                            total_weight: intent.outcome.total_weight,
-                            role_weight: intent.outcome.role_weight,
-                            approved: intent.outcome.approved.contents,
-                        },
-                    },
-                );
+                let intent = self.intent_from_bcs(&value.0, &value.1)?;
+                self.intents.insert(intent.key.clone(), intent);
             }
         }
 
         Ok(())
     }
 
+    // refetches a single intent, the outcome and the execution times left of an already fetched
+    // intent are updated (its loaded actions are kept), an intent replaced under the same key
+    // (new actions bag) replaces it and the intent is removed if it has been executed or deleted
+    pub async fn refresh_intent(&mut self, key: &str) -> Result<()> {
+        match self.fetch_intent(key).await? {
+            Some(intent) => match self.intents.get_mut(key) {
+                Some(cached) if cached.actions_bag_id == intent.actions_bag_id => {
+                    cached.outcome = intent.outcome;
+                    cached.execution_times = intent.execution_times;
+                }
+                _ => {
+                    self.intents.insert(intent.key.clone(), intent);
+                }
+            }
+            None => {
+                self.intents.remove(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    // the intent as it is on chain without updating the cache, None if it has been executed or
+    // deleted, its actions aren't loaded
+    pub async fn fetch_intent(&self, key: &str) -> Result<Option<Intent>> {
+        let df_output = utils::get_dynamic_field(
+            &self.sui_client,
+            self.bag_id,
+            "0x1::string::String".parse()?,
+            bcs::to_bytes(key)?,
+        )
+        .await?;
+        df_output
+            .and_then(|df_output| df_output.value)
+            .map(|value| self.intent_from_bcs(&value.0, &value.1))
+            .transpose()
+    }
+
+    // intents are stored in the bag as dynamic fields keyed by a String
+    pub fn field_id(&self, key: &str) -> Result<Address> {
+        let field_id = ObjectId::from(self.bag_id)
+            .derive_dynamic_child_id(&"0x1::string::String".parse()?, &bcs::to_bytes(key)?);
+        Ok(field_id.into())
+    }
+
+    // intents the member can push towards a threshold: its weight counts towards the global
+    // one and towards the role one if it has the intent role, the most urgent first
+    // (next execution time, then expiration)
+    pub fn for_approver(&self, address: Address, config: &Config) -> Vec<&Intent> {
+        let Some(member) = config
+            .members
+            .iter()
+            .find(|member| member.address == address.to_string())
+        else {
+            return Vec::new();
+        };
+
+        let mut intents = self
+            .intents
+            .values()
+            .filter(|intent| {
+                let global = config.global.threshold > 0;
+                let role =
+                    member.roles.contains(&intent.role) && config.roles.contains_key(&intent.role);
+                member.weight > 0 && (global || role)
+            })
+            .collect::<Vec<_>>();
+        intents.sort_by(|a, b| {
+            let urgency = |intent: &Intent| {
+                (
+                    intent.execution_times.first().copied().unwrap_or(u64::MAX),
+                    intent.expiration_time,
+                )
+            };
+            urgency(a).cmp(&urgency(b)).then_with(|| a.key.cmp(&b.key))
+        });
+        intents
+    }
+
     pub async fn switch_multisig(&mut self, bag_id: Address) -> Result<()> {
         self.bag_id = bag_id;
         self.refresh().await?;
@@ -100,6 +181,31 @@ impl Intents {
     pub fn get_intent_mut(&mut self, key: &str) -> Option<&mut Intent> {
         self.intents.get_mut(key)
     }
+
+    fn intent_from_bcs(&self, type_: &TypeTag, bytes: &[u8]) -> Result<Intent> {
+        let intent: ap::intents::Intent<am::multisig::Approvals> =
+            utils::decode(bytes, Some(&type_.to_string()))?;
+        Ok(Intent {
+            sui_client: self.sui_client.clone(),
+            type_: intent.type_,
+            key: intent.key,
+            description: intent.description,
+            account: intent.account,
+            creator: intent.creator,
+            creation_time: intent.creation_time,
+            execution_times: intent.execution_times,
+            expiration_time: intent.expiration_time,
+            role: intent.role,
+            actions_bag_id: intent.actions.id.into(),
+            actions_types_bcs: Vec::new(),
+            actions_args: None,
+            outcome: Approvals {
+                total_weight: intent.outcome.total_weight,
+                role_weight: intent.outcome.role_weight,
+                approved: intent.outcome.approved.contents,
+            },
+        })
+    }
 }
 
 impl fmt::Display for Intents {
@@ -145,6 +251,56 @@ impl Intent {
         Ok(self.actions_args.as_ref().unwrap())
     }
 
+    // link to the proposal in the account.tech web app
+    pub fn share_link(&self, frontend_base: &str) -> String {
+        share_link(frontend_base, self.account, &self.key)
+    }
+
+    // members who haven't approved yet, every approval adds to the global weight and
+    // the members with the intent role also add to the role weight so they come first
+    pub fn pending_approvers(&self, config: &Config) -> Vec<Member> {
+        let mut pending = config
+            .members
+            .iter()
+            .filter(|member| member.weight > 0)
+            .filter(|member| {
+                !self
+                    .outcome
+                    .approved
+                    .iter()
+                    .any(|addr| addr.to_string() == member.address)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        pending.sort_by_key(|member| !member.roles.contains(&self.role));
+        pending
+    }
+
+    // clock is the current timestamp in ms, the intent expires after its expiration time
+    pub fn deadlines(&self, clock: u64) -> Deadlines {
+        let until_executable = self
+            .execution_times
+            .first()
+            .filter(|time| **time > clock)
+            .map(|time| Duration::from_millis(time - clock));
+        let until_expiry = (self.expiration_time >= clock)
+            .then(|| Duration::from_millis(self.expiration_time - clock));
+
+        const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+        let urgency = match until_expiry {
+            None => Urgency::Expired,
+            Some(left) if left < DAY => Urgency::ExpiresToday,
+            Some(left) if left < 7 * DAY => Urgency::ExpiresThisWeek,
+            Some(_) => Urgency::Normal,
+        };
+
+        Deadlines {
+            until_executable,
+            until_expiry,
+            urgency,
+        }
+    }
+
     pub async fn get_executions_count(&mut self) -> Result<usize> {
         let _ = self.get_actions_args().await?; // fetch actions args
         let intent_type = IntentType::try_from(self.type_.as_str())?;
@@ -152,6 +308,65 @@ impl Intent {
     }
 }
 
+// <frontend>/multisig/<multisig id>/proposals/<key>
+pub fn share_link(frontend_base: &str, multisig_id: Address, key: &str) -> String {
+    format!(
+        "{}/multisig/{}/proposals/{}",
+        frontend_base.trim_end_matches('/'),
+        multisig_id,
+        encode_path_segment(key)
+    )
+}
+
+// (multisig id, intent key) from a link generated by share_link, ignores the frontend base
+pub fn parse_share_link(link: &str) -> Result<(Address, String)> {
+    let path = link.split(['?', '#']).next().unwrap_or_default();
+    let (_, rest) = path
+        .split_once("/multisig/")
+        .ok_or(anyhow!("Invalid share link: {}", link))?;
+    let (id, key) = rest
+        .split_once("/proposals/")
+        .ok_or(anyhow!("Invalid share link: {}", link))?;
+
+    let key = decode_path_segment(key.trim_end_matches('/'))?;
+    if key.is_empty() {
+        return Err(anyhow!("Invalid share link: {}", link));
+    }
+    Ok((id.parse()?, key))
+}
+
+// percent-encodes everything but unreserved characters
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn decode_path_segment(segment: &str) -> Result<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segment
+                .get(i + 1..i + 3)
+                .ok_or(anyhow!("Invalid escape in {}", segment))?;
+            decoded.push(u8::from_str_radix(hex, 16)?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(String::from_utf8(decoded)?)
+}
+
 impl fmt::Display for Intent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Name: {}", self.key)?;
@@ -178,3 +393,76 @@ impl fmt::Debug for Intent {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn share_link_roundtrip() {
+        let id: Address = "0x7f4a7dd1c6ab1b3bd8a3ffb6ee8d9a3b5c4e2c1f2b6e1d9a8c7b6a5f4e3d2c1b"
+            .parse()
+            .unwrap();
+        let link = share_link("https://multisig.account.tech/", id, "pay team #3");
+        assert_eq!(
+            link,
+            format!(
+                "https://multisig.account.tech/multisig/{}/proposals/pay%20team%20%233",
+                id
+            )
+        );
+        assert_eq!(
+            parse_share_link(&link).unwrap(),
+            (id, "pay team #3".to_string())
+        );
+        assert!(parse_share_link("https://multisig.account.tech/multisig/").is_err());
+    }
+
+    fn test_intent(key: &str, execution_time: u64, expiration_time: u64) -> Intent {
+        let mut intent = test_utils::intent(key, "");
+        intent.execution_times = vec![execution_time];
+        intent.expiration_time = expiration_time;
+        intent
+    }
+
+    #[test]
+    fn intents_for_approver() {
+        let intents = test_utils::intents(vec![
+            test_intent("b", 10, 20),
+            test_intent("a", 10, 15),
+            test_intent("c", 5, 30),
+        ]);
+
+        let signer: Address = "0x0000000000000000000000000000000000000000000000000000000000000a11"
+            .parse()
+            .unwrap();
+        let config = test_utils::multisig(&[(signer, vec![])], 1).config;
+        let keys = intents
+            .for_approver(signer, &config)
+            .iter()
+            .map(|intent| intent.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["c", "a", "b"]);
+        assert!(intents.for_approver(Address::ZERO, &config).is_empty());
+    }
+
+    #[test]
+    fn intent_deadlines() {
+        const DAY: u64 = 24 * 60 * 60 * 1000;
+        let intent = test_intent("key", 1_000, 3 * DAY);
+        let deadlines = intent.deadlines(0);
+        assert_eq!(deadlines.until_executable, Some(Duration::from_secs(1)));
+        assert_eq!(deadlines.urgency, Urgency::ExpiresThisWeek);
+
+        let deadlines = intent.deadlines(3 * DAY - 1_000);
+        assert_eq!(deadlines.until_executable, None);
+        assert_eq!(deadlines.until_expiry, Some(Duration::from_secs(1)));
+        assert_eq!(deadlines.urgency, Urgency::ExpiresToday);
+        assert_eq!(intent.deadlines(3 * DAY + 1).urgency, Urgency::Expired);
+        assert_eq!(
+            test_intent("key", 0, 30 * DAY).deadlines(0).urgency,
+            Urgency::Normal
+        );
+    }
+}
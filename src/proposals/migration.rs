@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use sui_sdk_types::{Address, ObjectId};
+use sui_transaction_builder::TransactionBuilder;
+
+use crate::proposals::batch::{ChunkPolicy, ProposalBatch};
+use crate::proposals::params;
+use crate::MultisigClient;
+
+// moves the assets of a multisig to a new one, e.g. to rotate the members,
+// the intents still need to be approved and executed in the old multisig
+
+#[derive(Debug, Clone, Default)]
+pub struct MigrationPlan {
+    pub from: Address,
+    pub to: Address,
+    // owned objects withdrawn and transferred (locked objects are skipped)
+    pub objects: Vec<Address>,
+    // (vault name, coin type, amount) spent and transferred
+    pub vault_spends: Vec<(String, String, u64)>,
+    // assets stored as dynamic fields that cannot be moved by an intent
+    pub manual_steps: Vec<String>,
+    // keys of the proposed intents
+    pub keys: Vec<String>,
+}
+
+// computes what can be moved from the loaded multisig, without proposing anything
+pub fn plan_migration(client: &MultisigClient, new_multisig: Address) -> Result<MigrationPlan> {
+    let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+    if multisig.id == new_multisig {
+        return Err(anyhow!("Cannot migrate a multisig to itself"));
+    }
+    let owned_objects = client
+        .owned_objects()
+        .ok_or(anyhow!("Owned objects not loaded"))?;
+    let dynamic_fields = client
+        .dynamic_fields()
+        .ok_or(anyhow!("Dynamic fields not loaded"))?;
+
+    let mut objects = owned_objects
+        .coins
+        .iter()
+        .map(|coin| coin.id)
+        .chain(owned_objects.objects.iter().map(|object| object.id))
+        .filter(|id| !multisig.locked_objects.contains(id))
+        .collect::<Vec<_>>();
+    objects.sort();
+
+    let mut vault_spends = Vec::new();
+    for (vault_name, vault) in &dynamic_fields.vaults {
+        for (coin_type, amount) in &vault.coins {
+            if *amount > 0 {
+                vault_spends.push((vault_name.clone(), coin_type.clone(), *amount));
+            }
+        }
+    }
+    vault_spends.sort();
+
+    let mut manual_steps = Vec::new();
+    for cap in &dynamic_fields.caps {
+        manual_steps.push(format!(
+            "Cap {} is locked in the account, borrow it to grant the new multisig the same rights",
+            cap.type_
+        ));
+    }
+    for coin_type in dynamic_fields.currencies.keys() {
+        manual_steps.push(format!(
+            "TreasuryCap of {} is locked in the account, mint and burn from the old multisig",
+            coin_type
+        ));
+    }
+    for (name, package) in &dynamic_fields.packages {
+        manual_steps.push(format!(
+            "UpgradeCap of {} ({}) is locked in the account, upgrades stay with the old multisig",
+            name, package.package_id
+        ));
+    }
+    for name in dynamic_fields.kiosks.keys() {
+        manual_steps.push(format!(
+            "Kiosk {} is managed by the account, list or take its items from the old multisig",
+            name
+        ));
+    }
+    for field in &dynamic_fields.other {
+        manual_steps.push(format!(
+            "Dynamic field {} is not handled by the sdk, check it manually",
+            field.key_type
+        ));
+    }
+    manual_steps.sort();
+
+    Ok(MigrationPlan {
+        from: multisig.id,
+        to: new_multisig,
+        objects,
+        vault_spends,
+        manual_steps,
+        keys: Vec::new(),
+    })
+}
+
+// proposes, in one transaction, the intents moving the assets of old_multisig to new_multisig,
+// keys are migration-1, migration-2, ...
+pub async fn migrate_assets(
+    client: &mut MultisigClient,
+    builder: &mut TransactionBuilder,
+    old_multisig: Address,
+    new_multisig: Address,
+    policy: ChunkPolicy,
+) -> Result<MigrationPlan> {
+    if client.multisig().map(|multisig| multisig.id) != Some(old_multisig) {
+        client.load_multisig(old_multisig).await?;
+    }
+    let mut plan = plan_migration(client, new_multisig)?;
+    if plan.objects.is_empty() && plan.vault_spends.is_empty() {
+        return Err(anyhow!("Nothing to migrate from {}", old_multisig));
+    }
+
+    let mut batch = ProposalBatch::new(client, builder, "migration").await?;
+    for object_ids in plan.objects.chunks(policy.max_transfers_per_intent.max(1)) {
+        let actions_args = params::WithdrawAndTransferArgs::new(
+            batch.builder(),
+            object_ids.iter().copied().map(ObjectId::from).collect(),
+            vec![new_multisig; object_ids.len()],
+        );
+        batch
+            .request_withdraw_and_transfer("Migrate owned objects", actions_args)
+            .await?;
+    }
+    for (vault_name, coin_type, amount) in &plan.vault_spends {
+        let actions_args = params::SpendAndTransferArgs::new(
+            batch.builder(),
+            vault_name.clone(),
+            vec![*amount],
+            vec![new_multisig],
+        );
+        batch
+            .request_spend_and_transfer("Migrate vault", actions_args, coin_type)
+            .await?;
+    }
+    plan.keys = batch.finish();
+
+    Ok(plan)
+}
+
+impl fmt::Display for MigrationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Migration from {} to {}", self.from, self.to)?;
+        writeln!(f, "  {} owned objects", self.objects.len())?;
+        for (vault_name, coin_type, amount) in &self.vault_spends {
+            writeln!(f, "  {} {} from vault {}", amount, coin_type, vault_name)?;
+        }
+        for step in &self.manual_steps {
+            writeln!(f, "  manual: {}", step)?;
+        }
+        if !self.keys.is_empty() {
+            writeln!(f, "  intents: {}", self.keys.join(", "))?;
+        }
+        Ok(())
+    }
+}
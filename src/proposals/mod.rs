@@ -1,3 +1,11 @@
+pub mod batch;
+pub mod borrowed_cap;
+pub mod coordinator;
 pub mod intents;
+pub mod migration;
 pub mod params;
+pub mod receipt;
+pub mod recovery;
+pub mod scaffold;
+pub mod simulation;
 pub mod actions;
\ No newline at end of file
@@ -1,7 +1,13 @@
+use anyhow::{anyhow, Result};
 use move_types::{functions::Arg, ObjectId};
+use std::io::{BufRead, BufReader, Read};
 use sui_sdk_types::Address;
 use sui_transaction_builder::{Serialized, TransactionBuilder};
 
+use crate::coin_metadata::parse_amount;
+use crate::proposals::actions::IntentType;
+use crate::MultisigClient;
+
 macro_rules! define_args_struct {
     (
         $struct_name:ident {
@@ -25,6 +31,37 @@ macro_rules! define_args_struct {
         }
     };
 
+    // the values are also kept, for the checks of the request methods
+    (
+        $struct_name:ident, $values_name:ident {
+            $($field_name:ident: $field_type:ty),* $(,)?
+        }
+    ) => {
+        pub struct $values_name {
+            $(pub $field_name: $field_type,)*
+        }
+
+        pub struct $struct_name {
+            $(pub $field_name: Arg<$field_type>,)*
+            pub values: $values_name,
+        }
+
+        impl $struct_name {
+            #[allow(clippy::too_many_arguments)]
+            pub fn new(
+                builder: &mut TransactionBuilder,
+                $($field_name: $field_type,)*
+            ) -> Self {
+                Self {
+                    $($field_name: builder.input(Serialized(&$field_name)).into(),)*
+                    values: $values_name {
+                        $($field_name,)*
+                    },
+                }
+            }
+        }
+    };
+
     (
         $struct_name:ident {
             coin_type: $coin_type:expr,
@@ -90,17 +127,20 @@ define_args_struct!(UpdateMetadataArgs {
     icon_url: Option<String>,
 });
 
-define_args_struct!(MintAndTransferArgs {
+define_args_struct!(MintAndTransferArgs, MintAndTransferValues {
     amounts: Vec<u64>,
     recipients: Vec<Address>,
 });
 
-define_args_struct!(MintAndVestArgs {
-    total_amount: u64,
-    start_timestamp: u64,
-    end_timestamp: u64,
-    recipient: Address,
-});
+define_args_struct!(
+    MintAndVestArgs,
+    MintAndVestValues {
+        total_amount: u64,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        recipient: Address,
+    }
+);
 
 define_args_struct!(WithdrawAndBurnArgs {
     coin_id: ObjectId,
@@ -125,17 +165,20 @@ define_args_struct!(WithdrawAndTransferToVaultArgs {
     vault_name: String,
 });
 
-define_args_struct!(WithdrawAndTransferArgs {
+define_args_struct!(WithdrawAndTransferArgs, WithdrawAndTransferValues {
     object_ids: Vec<ObjectId>,
     recipients: Vec<Address>,
 });
 
-define_args_struct!(WithdrawAndVestArgs {
-    coin_id: ObjectId,
-    start_timestamp: u64,
-    end_timestamp: u64,
-    recipient: Address,
-});
+define_args_struct!(
+    WithdrawAndVestArgs,
+    WithdrawAndVestValues {
+        coin_id: ObjectId,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        recipient: Address,
+    }
+);
 
 define_args_struct!(UpgradePackageArgs {
     package_name: String,
@@ -147,16 +190,459 @@ define_args_struct!(RestrictPolicyArgs {
     policy: u8,
 });
 
-define_args_struct!(SpendAndTransferArgs {
+define_args_struct!(SpendAndTransferArgs, SpendAndTransferValues {
     vault_name: String,
     amounts: Vec<u64>,
     recipients: Vec<Address>,
 });
 
-define_args_struct!(SpendAndVestArgs {
-    vault_name: String,
-    coin_amount: u64,
-    start_timestamp: u64,
-    end_timestamp: u64,
-    recipient: Address,
-});
+define_args_struct!(
+    SpendAndVestArgs,
+    SpendAndVestValues {
+        vault_name: String,
+        coin_amount: u64,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        recipient: Address,
+    }
+);
+
+// === Intent params ===
+
+pub const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+// timing of the new intents relative to their creation, see MultisigClient::set_intent_defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntentDefaults {
+    // the intents can be executed once this delay has passed
+    pub review_window_ms: u64,
+    // and deleted this long after their last execution time
+    pub expires_after_ms: u64,
+}
+
+impl Default for IntentDefaults {
+    fn default() -> Self {
+        Self {
+            review_window_ms: 0,
+            expires_after_ms: 30 * DAY_MS,
+        }
+    }
+}
+
+// ParamsArgs with the client defaults, unless overridden
+#[derive(Debug, Clone)]
+pub struct IntentParamsBuilder {
+    key: String,
+    description: String,
+    execution_times: Option<Vec<u64>>,
+    expiration_time: Option<u64>,
+    defaults: IntentDefaults,
+    now: u64,
+}
+
+impl IntentParamsBuilder {
+    // now is the clock timestamp in ms
+    pub fn new(key: &str, defaults: IntentDefaults, now: u64) -> Self {
+        Self {
+            key: key.to_string(),
+            description: String::new(),
+            execution_times: None,
+            expiration_time: None,
+            defaults,
+            now,
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn execution_times(mut self, execution_times: Vec<u64>) -> Self {
+        self.execution_times = Some(execution_times);
+        self
+    }
+
+    pub fn expiration_time(mut self, expiration_time: u64) -> Self {
+        self.expiration_time = Some(expiration_time);
+        self
+    }
+
+    // executable as soon as created, ignores the review window
+    pub fn immediate(mut self) -> Self {
+        self.execution_times = Some(vec![0]);
+        self
+    }
+
+    // (execution_times, expiration_time)
+    pub fn resolve(&self) -> (Vec<u64>, u64) {
+        let execution_times = self
+            .execution_times
+            .clone()
+            .unwrap_or_else(|| vec![self.now.saturating_add(self.defaults.review_window_ms)]);
+        let last_execution = execution_times.iter().copied().max().unwrap_or(self.now);
+        let expiration_time = self.expiration_time.unwrap_or_else(|| {
+            last_execution
+                .max(self.now)
+                .saturating_add(self.defaults.expires_after_ms)
+        });
+        (execution_times, expiration_time)
+    }
+
+    // execution times strictly increasing and not in the past (0 is immediate),
+    // a single one unless the intent is recurring, and expiring after the last one
+    pub fn validate(&self, intent_type: &IntentType) -> Result<()> {
+        let (execution_times, expiration_time) = self.resolve();
+        if execution_times.is_empty() {
+            return Err(anyhow!("No execution time"));
+        }
+        if execution_times.len() > 1 && !intent_type.allows_recurring() {
+            return Err(anyhow!(
+                "{:?} intents are executed once, got {} execution times",
+                intent_type,
+                execution_times.len()
+            ));
+        }
+        if let Some(pair) = execution_times.windows(2).find(|pair| pair[0] >= pair[1]) {
+            return Err(anyhow!(
+                "Execution times must be strictly increasing, {} is followed by {}",
+                pair[0],
+                pair[1]
+            ));
+        }
+        if let Some(time) = execution_times
+            .iter()
+            .find(|time| **time != 0 && **time < self.now)
+        {
+            return Err(anyhow!(
+                "Execution time {} is in the past (now is {}), use 0 to execute immediately",
+                time,
+                self.now
+            ));
+        }
+        let last_execution = execution_times[execution_times.len() - 1];
+        if expiration_time < last_execution {
+            return Err(anyhow!(
+                "Intent expires at {}, before its last execution time {}",
+                expiration_time,
+                last_execution
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn build_checked(
+        self,
+        builder: &mut TransactionBuilder,
+        intent_type: &IntentType,
+    ) -> Result<ParamsArgs> {
+        self.validate(intent_type)?;
+        Ok(self.build(builder))
+    }
+
+    pub fn build(self, builder: &mut TransactionBuilder) -> ParamsArgs {
+        let (execution_times, expiration_time) = self.resolve();
+        ParamsArgs::new(
+            builder,
+            self.key,
+            self.description,
+            execution_times,
+            expiration_time,
+        )
+    }
+}
+
+// === SuiNS names ===
+
+// same as new with names (alice.sui, @alice) or addresses for the recipients and members,
+// resolved and cached by the client
+
+impl ConfigMultisigArgs {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        addresses: &[impl AsRef<str>],
+        weights: Vec<u64>,
+        roles: Vec<Vec<String>>,
+        global: u64,
+        role_names: Vec<String>,
+        role_thresholds: Vec<u64>,
+    ) -> Result<Self> {
+        let addresses = client.resolve_addresses(addresses).await?;
+        Ok(Self::new(
+            builder,
+            addresses,
+            weights,
+            roles,
+            global,
+            role_names,
+            role_thresholds,
+        ))
+    }
+}
+
+impl MintAndTransferArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        amounts: Vec<u64>,
+        recipients: &[impl AsRef<str>],
+    ) -> Result<Self> {
+        let recipients = client.resolve_addresses(recipients).await?;
+        Ok(Self::new(builder, amounts, recipients))
+    }
+}
+
+impl MintAndVestArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        total_amount: u64,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        recipient: &str,
+    ) -> Result<Self> {
+        let recipient = client.resolve_address(recipient).await?;
+        Ok(Self::new(
+            builder,
+            total_amount,
+            start_timestamp,
+            end_timestamp,
+            recipient,
+        ))
+    }
+}
+
+impl TakeNftsArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        kiosk_name: String,
+        nft_ids: Vec<Address>,
+        recipient: &str,
+    ) -> Result<Self> {
+        let recipient = client.resolve_address(recipient).await?;
+        Ok(Self::new(builder, kiosk_name, nft_ids, recipient))
+    }
+}
+
+impl WithdrawAndTransferArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        object_ids: Vec<ObjectId>,
+        recipients: &[impl AsRef<str>],
+    ) -> Result<Self> {
+        let recipients = client.resolve_addresses(recipients).await?;
+        Ok(Self::new(builder, object_ids, recipients))
+    }
+}
+
+impl WithdrawAndVestArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        coin_id: ObjectId,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        recipient: &str,
+    ) -> Result<Self> {
+        let recipient = client.resolve_address(recipient).await?;
+        Ok(Self::new(
+            builder,
+            coin_id,
+            start_timestamp,
+            end_timestamp,
+            recipient,
+        ))
+    }
+}
+
+impl SpendAndTransferArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        vault_name: String,
+        amounts: Vec<u64>,
+        recipients: &[impl AsRef<str>],
+    ) -> Result<Self> {
+        let recipients = client.resolve_addresses(recipients).await?;
+        Ok(Self::new(builder, vault_name, amounts, recipients))
+    }
+}
+
+impl SpendAndVestArgs {
+    pub async fn new_resolved(
+        client: &MultisigClient,
+        builder: &mut TransactionBuilder,
+        vault_name: String,
+        coin_amount: u64,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        recipient: &str,
+    ) -> Result<Self> {
+        let recipient = client.resolve_address(recipient).await?;
+        Ok(Self::new(
+            builder,
+            vault_name,
+            coin_amount,
+            start_timestamp,
+            end_timestamp,
+            recipient,
+        ))
+    }
+}
+
+// === CSV import ===
+
+impl MintAndTransferArgs {
+    // lines of "recipient,amount", amounts are in coin units (e.g. 1.5)
+    pub fn from_csv<R: Read>(
+        builder: &mut TransactionBuilder,
+        reader: R,
+        decimals: u8,
+    ) -> Result<Self> {
+        let (amounts, recipients) = parse_transfers_csv(reader, decimals)?;
+        Ok(Self::new(builder, amounts, recipients))
+    }
+}
+
+impl SpendAndTransferArgs {
+    // lines of "recipient,amount", amounts are in coin units (e.g. 1.5)
+    pub fn from_csv<R: Read>(
+        builder: &mut TransactionBuilder,
+        vault_name: String,
+        reader: R,
+        decimals: u8,
+    ) -> Result<Self> {
+        let (amounts, recipients) = parse_transfers_csv(reader, decimals)?;
+        Ok(Self::new(builder, vault_name, amounts, recipients))
+    }
+}
+
+// skips empty lines, # comments and a header line (recipient,amount or address,amount),
+// any other line with an invalid address is an error
+pub fn parse_transfers_csv<R: Read>(reader: R, decimals: u8) -> Result<(Vec<u64>, Vec<Address>)> {
+    let mut amounts = Vec::new();
+    let mut recipients = Vec::new();
+
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (recipient, amount) = line
+            .split_once(',')
+            .ok_or(anyhow!("Line {}: expected recipient,amount", index + 1))?;
+        let recipient = match recipient.trim().parse::<Address>() {
+            Ok(recipient) => recipient,
+            Err(_) if recipients.is_empty() && is_header(recipient) => continue,
+            Err(_) => return Err(anyhow!("Line {}: invalid address {}", index + 1, recipient)),
+        };
+        let amount =
+            parse_amount(amount, decimals).map_err(|e| anyhow!("Line {}: {}", index + 1, e))?;
+
+        recipients.push(recipient);
+        amounts.push(amount);
+    }
+
+    if recipients.is_empty() {
+        return Err(anyhow!("No transfers found in csv"));
+    }
+    Ok((amounts, recipients))
+}
+
+fn is_header(column: &str) -> bool {
+    ["recipient", "address"].contains(&column.trim().to_lowercase().as_str())
+}
+
+// total of the amounts of an intent, errors instead of overflowing
+pub fn total_amount(amounts: &[u64]) -> Result<u64> {
+    amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(anyhow!("Total amount overflows u64"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_amount_overflow() {
+        assert_eq!(total_amount(&[1, 2, 3]).unwrap(), 6);
+        assert!(total_amount(&[u64::MAX, 1]).is_err());
+    }
+
+    const ALICE: &str = "0x0000000000000000000000000000000000000000000000000000000000000a11";
+    const BOB: &str = "0x0000000000000000000000000000000000000000000000000000000000000b0b";
+
+    #[test]
+    fn parse_csv_with_header_and_comments() {
+        let csv = format!("recipient,amount\n# team\n{},1.5\n\n{}, 2\n", ALICE, BOB);
+        let (amounts, recipients) = parse_transfers_csv(csv.as_bytes(), 9).unwrap();
+        assert_eq!(amounts, vec![1_500_000_000, 2_000_000_000]);
+        assert_eq!(
+            recipients,
+            vec![
+                ALICE.parse::<Address>().unwrap(),
+                BOB.parse::<Address>().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn intent_params_defaults_and_overrides() {
+        let defaults = IntentDefaults {
+            review_window_ms: 1_000,
+            expires_after_ms: DAY_MS,
+        };
+        let params = IntentParamsBuilder::new("key", defaults, 5_000);
+        assert_eq!(params.resolve(), (vec![6_000], 6_000 + DAY_MS));
+        assert_eq!(
+            params.clone().execution_times(vec![0, 10_000]).resolve(),
+            (vec![0, 10_000], 10_000 + DAY_MS)
+        );
+        assert_eq!(
+            params.expiration_time(7_000).resolve(),
+            (vec![6_000], 7_000)
+        );
+    }
+
+    #[test]
+    fn validate_execution_times() {
+        let params = IntentParamsBuilder::new("key", IntentDefaults::default(), 5_000);
+        assert!(params.validate(&IntentType::ConfigMultisig).is_ok());
+        assert!(params
+            .clone()
+            .immediate()
+            .validate(&IntentType::ConfigMultisig)
+            .is_ok());
+
+        let tranches = params.clone().execution_times(vec![6_000, 7_000]);
+        assert!(tranches.validate(&IntentType::SpendAndTransfer).is_ok());
+        assert!(tranches.validate(&IntentType::ConfigMultisig).is_err());
+        let unordered = params.clone().execution_times(vec![7_000, 7_000]);
+        assert!(unordered.validate(&IntentType::SpendAndTransfer).is_err());
+        let past = params.clone().execution_times(vec![4_000]);
+        assert!(past.validate(&IntentType::SpendAndTransfer).is_err());
+        let expired = params.execution_times(vec![6_000]).expiration_time(5_500);
+        assert!(expired.validate(&IntentType::SpendAndTransfer).is_err());
+    }
+
+    #[test]
+    fn reject_invalid_csv_lines() {
+        let bad_address = format!("{},1\nnot_an_address,1\n", ALICE);
+        assert!(parse_transfers_csv(bad_address.as_bytes(), 0).is_err());
+        assert!(parse_transfers_csv(ALICE.as_bytes(), 0).is_err());
+        assert!(parse_transfers_csv("recipient,amount\n".as_bytes(), 0).is_err());
+        // a typo in the first row isn't taken for a header
+        let bad_first_row = format!("0xa1ice,1\n{},2\n", BOB);
+        assert!(parse_transfers_csv(bad_first_row.as_bytes(), 0).is_err());
+        let header = format!("Address, Amount\n{},2\n", BOB);
+        assert!(parse_transfers_csv(header.as_bytes(), 0).is_ok());
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use sui_graphql_client::Client;
+use sui_sdk_types::{
+    framework::Coin, Address, ObjectOut, Owner, TransactionDigest, TransactionEffects,
+};
+
+use crate::policy::Outflow;
+use crate::types::StructType;
+
+// what each transfer of an executed intent produced, to reconcile payouts with transactions,
+// built from the outflows of the executions run by the transaction (see policy::outflows)
+#[derive(Debug, Clone)]
+pub struct ExecutionReceipt {
+    pub key: String,
+    pub digest: TransactionDigest,
+    pub outcomes: Vec<ActionOutcome>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionOutcome {
+    pub outflow: Outflow,
+    // the coin or object received by the recipient, None if no written object matches
+    pub object: Option<WrittenObject>,
+}
+
+// object created or mutated by the transaction and owned by an address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrittenObject {
+    pub id: Address,
+    pub owner: Address,
+    pub coin_type: Option<String>,
+    pub amount: u64,
+}
+
+impl ExecutionReceipt {
+    // outflows are repeated for each execution run in the transaction
+    pub async fn from_effects(
+        sui_client: &Client,
+        key: &str,
+        outflows: &[Outflow],
+        digest: TransactionDigest,
+        effects: &TransactionEffects,
+    ) -> Result<Self> {
+        let written = written_objects(sui_client, effects).await?;
+        let matches = match_outflows(outflows, &written);
+        Ok(Self {
+            key: key.to_string(),
+            digest,
+            outcomes: outflows
+                .iter()
+                .zip(matches)
+                .map(|(outflow, index)| ActionOutcome {
+                    outflow: outflow.clone(),
+                    object: index.map(|index| written[index].clone()),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn unmatched(&self) -> impl Iterator<Item = &ActionOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.object.is_none())
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "key": self.key,
+            "digest": self.digest.to_string(),
+            "outcomes": self.outcomes.iter().map(|outcome| json!({
+                "recipient": outcome.outflow.recipient.to_string(),
+                "coin_type": outcome.outflow.coin_type,
+                "amount": outcome.outflow.amount,
+                "object": outcome.object.as_ref().map(|object| object.id.to_string()),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+async fn written_objects(
+    sui_client: &Client,
+    effects: &TransactionEffects,
+) -> Result<Vec<WrittenObject>> {
+    let TransactionEffects::V2(effects) = effects else {
+        return Err(anyhow!("Unsupported transaction effects version"));
+    };
+    let owned = effects
+        .changed_objects
+        .iter()
+        .filter_map(|changed| match &changed.output_state {
+            ObjectOut::ObjectWrite {
+                owner: Owner::Address(owner),
+                ..
+            } => Some((Address::from(changed.object_id), *owner)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // read at the version written by the transaction, the objects may have moved since
+    let mut written = Vec::new();
+    for (id, owner) in owned {
+        let object = sui_client
+            .object(id, Some(effects.lamport_version))
+            .await?
+            .ok_or(anyhow!(
+                "Object {} not found at version {}",
+                id,
+                effects.lamport_version
+            ))?;
+        let coin = Coin::try_from_object(&object);
+        written.push(WrittenObject {
+            id,
+            owner,
+            coin_type: coin.as_ref().map(|coin| coin.coin_type().to_string()),
+            amount: coin.map(|coin| coin.balance()).unwrap_or_default(),
+        });
+    }
+    Ok(written)
+}
+
+// index of the written object paid by each outflow, each object is matched once:
+// coins by recipient, coin type and amount, objects by recipient
+pub fn match_outflows(outflows: &[Outflow], written: &[WrittenObject]) -> Vec<Option<usize>> {
+    let mut used = vec![false; written.len()];
+    outflows
+        .iter()
+        .map(|outflow| {
+            let index = written.iter().zip(&used).position(|(object, used)| {
+                !used
+                    && object.owner == outflow.recipient
+                    && match &outflow.coin_type {
+                        Some(coin_type) => {
+                            object.amount == outflow.amount
+                                && object
+                                    .coin_type
+                                    .as_deref()
+                                    .is_some_and(|written| same_type(written, coin_type))
+                        }
+                        None => object.coin_type.is_none(),
+                    }
+            })?;
+            used[index] = true;
+            Some(index)
+        })
+        .collect()
+}
+
+fn same_type(a: &str, b: &str) -> bool {
+    match (a.parse::<StructType>(), b.parse::<StructType>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_outflows_once() {
+        let (alice, bob) = (Address::new([0xa; 32]), Address::new([0xb; 32]));
+        let sui = |amount, recipient| Outflow {
+            coin_type: Some("0x2::sui::SUI".to_string()),
+            amount,
+            recipient,
+        };
+        let coin = |id: u8, owner, amount| WrittenObject {
+            id: Address::new([id; 32]),
+            owner,
+            coin_type: Some(
+                "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI"
+                    .to_string(),
+            ),
+            amount,
+        };
+        let written = vec![coin(1, alice, 5), coin(2, bob, 7), coin(3, alice, 5)];
+        let outflows = vec![sui(5, alice), sui(5, alice), sui(5, alice), sui(7, bob)];
+        assert_eq!(
+            match_outflows(&outflows, &written),
+            vec![Some(0), Some(2), None, Some(1)]
+        );
+
+        let object = Outflow {
+            coin_type: None,
+            amount: 0,
+            recipient: bob,
+        };
+        assert_eq!(match_outflows(&[object], &written), vec![None]);
+    }
+}
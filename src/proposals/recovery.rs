@@ -0,0 +1,254 @@
+use anyhow::{anyhow, Result};
+use sui_crypto::SuiSigner;
+use sui_sdk_types::{Address, ObjectId};
+use sui_transaction_builder::TransactionBuilder;
+
+use crate::proposals::actions::IntentType;
+use crate::proposals::params::{ConfigMultisigArgs, ParamsArgs, WithdrawAndTransferArgs};
+use crate::utils;
+use crate::MultisigClient;
+
+// dead-man switch: a recovery intent that can only be executed after a long delay,
+// members approve it once and extend it periodically by proposing the next one.
+// intents can't be deleted before they expire, so extending disapproves the current recovery
+// and deletes the expired ones. keys are <name>-1, <name>-2, ...
+// only the approval of the caller is withdrawn: the previous recovery stays executable until it
+// expires if the approvals of the other members still reach the threshold, they have to
+// disapprove it too (or keep the delay longer than the extension period)
+
+#[derive(Debug, Clone)]
+pub enum RecoveryAction {
+    // the objects stay locked by the recovery, so it can only be extended once expired
+    WithdrawAndTransfer {
+        object_ids: Vec<ObjectId>,
+        recipients: Vec<Address>,
+    },
+    // gives the address enough weight to reach the global threshold alone
+    AddRecoveryKey(Address),
+}
+
+// index of the recovery intents with this name, sorted
+pub fn recovery_keys(client: &MultisigClient, name: &str) -> Result<Vec<(u64, String)>> {
+    let intents = client.intents().ok_or(anyhow!("Intents not loaded"))?;
+    let mut keys = intents
+        .intents
+        .keys()
+        .filter_map(|key| Some((recovery_index(name, key)?, key.clone())))
+        .collect::<Vec<_>>();
+    keys.sort();
+    Ok(keys)
+}
+
+// the recovery can be executed delay_ms from now, during grace_ms
+pub async fn schedule_recovery<S: SuiSigner>(
+    client: &mut MultisigClient,
+    signer: &S,
+    name: &str,
+    action: &RecoveryAction,
+    delay_ms: u64,
+    grace_ms: u64,
+) -> Result<String> {
+    if !recovery_keys(client, name)?.is_empty() {
+        return Err(anyhow!(
+            "Recovery {} already scheduled, extend it instead",
+            name
+        ));
+    }
+    let key = format!("{}-1", name);
+    request_recovery(client, signer, &key, action, delay_ms, grace_ms).await?;
+    Ok(key)
+}
+
+// proposes the next recovery, disapproves the current one and deletes the expired ones
+// the current one is only disapproved by the signer, see the module comment
+pub async fn extend_recovery<S: SuiSigner>(
+    client: &mut MultisigClient,
+    signer: &S,
+    name: &str,
+    action: &RecoveryAction,
+    delay_ms: u64,
+    grace_ms: u64,
+) -> Result<String> {
+    let keys = recovery_keys(client, name)?;
+    let (last_index, _) = keys
+        .last()
+        .ok_or(anyhow!("No recovery {} to extend", name))?;
+    let next_key = format!("{}-{}", name, last_index + 1);
+
+    let now = client.clock_timestamp().await?;
+    let mut expired = Vec::new();
+    let mut active = Vec::new();
+    for (_, key) in &keys {
+        if now > client.intent(key)?.expiration_time {
+            expired.push(key.clone());
+        } else {
+            active.push(key.clone());
+        }
+    }
+    if let (RecoveryAction::WithdrawAndTransfer { .. }, Some(key)) = (action, active.first()) {
+        return Err(anyhow!(
+            "Objects are locked by {} until it expires at {}",
+            key,
+            client.intent(key)?.expiration_time
+        ));
+    }
+
+    let sender = client.user().ok_or(anyhow!("User not loaded"))?.address;
+    for key in &expired {
+        let intent_type = IntentType::try_from(client.intent(key)?.type_.as_str())?;
+        let mut builder = client.init_tx(sender).await?;
+        match intent_type {
+            IntentType::WithdrawAndTransfer => {
+                client
+                    .delete_withdraw_and_transfer(&mut builder, key)
+                    .await?
+            }
+            IntentType::ConfigMultisig => client.delete_config_multisig(&mut builder, key).await?,
+            _ => return Err(anyhow!("Intent {} is not a recovery", key)),
+        }
+        submit(client, signer, builder).await?;
+    }
+    for key in &active {
+        if client.has_approved(key)? == Some(true) {
+            let mut builder = client.init_tx(sender).await?;
+            client.disapprove_intent(&mut builder, key, sender).await?;
+            submit(client, signer, builder).await?;
+        }
+    }
+
+    request_recovery(client, signer, &next_key, action, delay_ms, grace_ms).await?;
+    Ok(next_key)
+}
+
+// === Helpers ===
+
+async fn request_recovery<S: SuiSigner>(
+    client: &mut MultisigClient,
+    signer: &S,
+    key: &str,
+    action: &RecoveryAction,
+    delay_ms: u64,
+    grace_ms: u64,
+) -> Result<()> {
+    let sender = client.user().ok_or(anyhow!("User not loaded"))?.address;
+    let execution_time = client.clock_timestamp().await?.saturating_add(delay_ms);
+    let mut builder = client.init_tx(sender).await?;
+
+    match action {
+        RecoveryAction::WithdrawAndTransfer {
+            object_ids,
+            recipients,
+        } => {
+            let intent_args = params(&mut builder, key, execution_time, grace_ms);
+            let actions_args =
+                WithdrawAndTransferArgs::new(&mut builder, object_ids.clone(), recipients.clone());
+            client
+                .request_withdraw_and_transfer(&mut builder, intent_args, actions_args)
+                .await?;
+        }
+        RecoveryAction::AddRecoveryKey(recovery) => {
+            let actions_args = recovery_config(client, &mut builder, *recovery)?;
+            let intent_args = params(&mut builder, key, execution_time, grace_ms);
+            client
+                .request_config_multisig(&mut builder, intent_args, actions_args)
+                .await?;
+        }
+    }
+
+    submit(client, signer, builder).await
+}
+
+fn params(
+    builder: &mut TransactionBuilder,
+    key: &str,
+    execution_time: u64,
+    grace_ms: u64,
+) -> ParamsArgs {
+    ParamsArgs::new(
+        builder,
+        key.to_string(),
+        "Recovery".to_string(),
+        vec![execution_time],
+        execution_time.saturating_add(grace_ms),
+    )
+}
+
+// current config with the recovery address weighing the global threshold
+fn recovery_config(
+    client: &MultisigClient,
+    builder: &mut TransactionBuilder,
+    recovery: Address,
+) -> Result<ConfigMultisigArgs> {
+    let config = &client
+        .multisig()
+        .ok_or(anyhow!("Multisig not loaded"))?
+        .config;
+    let threshold = config.global.threshold;
+
+    let (mut addresses, mut weights, mut roles) = (Vec::new(), Vec::new(), Vec::new());
+    for member in &config.members {
+        let address = member
+            .address
+            .parse::<Address>()
+            .map_err(|_| anyhow!("Invalid member address: {}", member.address))?;
+        let weight = if address == recovery {
+            member.weight.max(threshold)
+        } else {
+            member.weight
+        };
+        addresses.push(address);
+        weights.push(weight);
+        roles.push(member.roles.clone());
+    }
+    if !addresses.contains(&recovery) {
+        addresses.push(recovery);
+        weights.push(threshold);
+        roles.push(vec![]);
+    }
+    let (role_names, role_thresholds) = config
+        .roles
+        .iter()
+        .map(|(name, role)| (name.clone(), role.threshold))
+        .unzip();
+
+    Ok(ConfigMultisigArgs::new(
+        builder,
+        addresses,
+        weights,
+        roles,
+        threshold,
+        role_names,
+        role_thresholds,
+    ))
+}
+
+async fn submit<S: SuiSigner>(
+    client: &mut MultisigClient,
+    signer: &S,
+    builder: TransactionBuilder,
+) -> Result<()> {
+    let tx = builder.finish()?;
+    let signature = signer.sign_transaction(&tx)?;
+    utils::submit_tx(client.sui(), &tx, vec![signature]).await?;
+    if let Some(multisig) = client.multisig_mut() {
+        multisig.refresh().await?;
+    }
+    Ok(())
+}
+
+fn recovery_index(name: &str, key: &str) -> Option<u64> {
+    key.strip_prefix(name)?.strip_prefix('-')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_recovery_keys() {
+        assert_eq!(recovery_index("recovery", "recovery-1"), Some(1));
+        assert_eq!(recovery_index("recovery", "recovery-12"), Some(12));
+        assert_eq!(recovery_index("recovery", "recovery-backup-1"), None);
+        assert_eq!(recovery_index("recovery", "other-1"), None);
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{anyhow, Result};
+use base64ct::{Base64, Encoding};
+use sui_sdk_types::{Address, Input, ObjectId, Transaction, TransactionKind};
+
+use crate::proposals::batch::ProposalBatch;
+use crate::proposals::params;
+use crate::MultisigClient;
+
+// intents needed to run an ad-hoc transaction (e.g. dry run by a developer) with the assets of the
+// multisig under governance: the owned objects it takes are withdrawn to the executor, who runs the
+// transaction once the intent is approved and executed.
+// the original transaction bytes are stale by then: the withdrawn objects have new versions and a
+// new owner, so the executor must rebuild the transaction with the same commands before running it.
+// caps held by the multisig are dynamic fields, they can't be inputs of the transaction and aren't
+// scaffolded, borrow them with MultisigClient::execute_with_borrowed_cap instead
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scaffold {
+    // owned by the multisig, withdrawn to the executor by one WithdrawAndTransfer intent
+    pub withdrawals: Vec<Address>,
+    // owned by the multisig but locked by a pending intent
+    pub locked: Vec<Address>,
+    // not held by the multisig, provided by the executor
+    pub external: Vec<Address>,
+}
+
+impl Scaffold {
+    // owned objects of the multisig must be loaded
+    pub fn analyze(client: &MultisigClient, tx: &Transaction) -> Result<Self> {
+        let multisig = client.multisig().ok_or(anyhow!("Multisig not loaded"))?;
+        let owned_objects = client
+            .owned_objects()
+            .ok_or(anyhow!("Owned objects not loaded"))?;
+        let inputs = object_inputs(tx)?;
+        let owned = owned_objects
+            .coins
+            .iter()
+            .map(|coin| coin.id)
+            .chain(owned_objects.objects.iter().map(|object| object.id))
+            .collect::<Vec<_>>();
+
+        Ok(Self::from_inputs(&inputs, &owned, &multisig.locked_objects))
+    }
+
+    // tx_bytes: base64 of the bcs encoded transaction, as serialized by the sui cli
+    pub fn analyze_base64(client: &MultisigClient, tx_bytes: &str) -> Result<Self> {
+        let bytes = Base64::decode_vec(tx_bytes).map_err(|e| anyhow!("Invalid tx bytes: {}", e))?;
+        Self::analyze(client, &bcs::from_bytes(&bytes)?)
+    }
+
+    pub fn from_inputs(inputs: &[Address], owned: &[Address], locked: &[Address]) -> Self {
+        let mut scaffold = Self::default();
+        for id in inputs {
+            if locked.contains(id) {
+                scaffold.locked.push(*id);
+            } else if owned.contains(id) {
+                scaffold.withdrawals.push(*id);
+            } else {
+                scaffold.external.push(*id);
+            }
+        }
+        scaffold
+    }
+
+    // requests the withdrawal in the batch, returns its key
+    pub async fn propose(
+        &self,
+        batch: &mut ProposalBatch<'_>,
+        description: &str,
+        executor: Address,
+    ) -> Result<String> {
+        if !self.locked.is_empty() {
+            return Err(anyhow!(
+                "Objects locked by pending intents: {:?}",
+                self.locked
+            ));
+        }
+        if self.withdrawals.is_empty() {
+            return Err(anyhow!("The transaction uses no object of the multisig"));
+        }
+
+        let actions_args = params::WithdrawAndTransferArgs::new(
+            batch.builder(),
+            self.withdrawals
+                .iter()
+                .copied()
+                .map(ObjectId::from)
+                .collect(),
+            vec![executor; self.withdrawals.len()],
+        );
+        batch
+            .request_withdraw_and_transfer(description, actions_args)
+            .await
+    }
+}
+
+// ids of the objects passed to the transaction, in order and deduplicated
+fn object_inputs(tx: &Transaction) -> Result<Vec<Address>> {
+    let TransactionKind::ProgrammableTransaction(ptb) = &tx.kind else {
+        return Err(anyhow!("Only programmable transactions can be scaffolded"));
+    };
+    let mut ids = Vec::new();
+    for input in &ptb.inputs {
+        let id = match input {
+            Input::ImmutableOrOwned(object) | Input::Receiving(object) => *object.object_id(),
+            Input::Shared { object_id, .. } => *object_id,
+            Input::Pure { .. } => continue,
+        };
+        if !ids.contains(&Address::from(id)) {
+            ids.push(Address::from(id));
+        }
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffold_from_inputs() {
+        let [owned, locked, external] = [1, 2, 3].map(|byte| Address::new([byte; 32]));
+        let scaffold = Scaffold::from_inputs(&[owned, locked, external], &[owned, locked], &[locked]);
+        assert_eq!(
+            scaffold,
+            Scaffold {
+                withdrawals: vec![owned],
+                locked: vec![locked],
+                external: vec![external],
+            }
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::fmt;
+use sui_sdk_types::Address;
+
+use crate::multisig::Multisig;
+use crate::proposals::actions::{ConfigMultisigFields, IntentType};
+
+// what-if analysis of a multisig config, to review ConfigMultisig proposals
+
+#[derive(Debug, Clone)]
+pub struct ConfigSimulation {
+    pub global: QuorumReport,
+    // roles defined in the config, sorted by name
+    pub roles: Vec<QuorumReport>,
+    // (role, achievable) for the roles of the intents, reached globally or with the role
+    pub intent_roles: Vec<(String, bool)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuorumReport {
+    pub name: String,
+    pub threshold: u64,
+    // sum of the weights of the members counted for this threshold
+    pub total_weight: u64,
+    pub achievable: bool,
+    // smallest set of members reaching the threshold, heaviest first
+    pub min_signers: Vec<Address>,
+    // members whose weight alone reaches the threshold
+    pub solo_signers: Vec<Address>,
+    // members without whom the threshold cannot be reached
+    pub required_signers: Vec<Address>,
+}
+
+pub fn simulate_config(config: &ConfigMultisigFields) -> ConfigSimulation {
+    let all = config
+        .members
+        .iter()
+        .map(|(addr, weight, _)| (*addr, *weight))
+        .collect::<Vec<_>>();
+    let global = quorum("global", config.global, &all);
+
+    let mut roles = config
+        .roles
+        .iter()
+        .map(|(name, threshold)| {
+            let members = config
+                .members
+                .iter()
+                .filter(|(_, _, roles)| roles.contains(name))
+                .map(|(addr, weight, _)| (*addr, *weight))
+                .collect::<Vec<_>>();
+            quorum(name, *threshold, &members)
+        })
+        .collect::<Vec<_>>();
+    roles.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut intent_roles: Vec<(String, bool)> = Vec::new();
+    for type_ in IntentType::ALL.iter() {
+        let role = type_.role();
+        if intent_roles
+            .iter()
+            .any(|(name, _)| *name == role.short_name())
+        {
+            continue;
+        }
+        let achievable = global.achievable
+            || roles
+                .iter()
+                .any(|report| report.name == role.to_string() && report.achievable);
+        intent_roles.push((role.short_name(), achievable));
+    }
+
+    ConfigSimulation {
+        global,
+        roles,
+        intent_roles,
+    }
+}
+
+impl ConfigSimulation {
+    // members who can execute some intents alone
+    pub fn single_points_of_failure(&self) -> Vec<Address> {
+        let mut members = Vec::new();
+        for report in std::iter::once(&self.global).chain(&self.roles) {
+            for addr in &report.solo_signers {
+                if !members.contains(addr) {
+                    members.push(*addr);
+                }
+            }
+        }
+        members
+    }
+
+    // intents of this role can be approved, globally or with the role
+    pub fn can_approve(&self, role: &str) -> bool {
+        self.global.achievable
+            || self
+                .roles
+                .iter()
+                .any(|report| report.name == role && report.achievable)
+    }
+}
+
+// what a new config breaks compared to the current one, to review before proposing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigWarning {
+    // reachable with the current config but not with the new one
+    ThresholdUnreachable {
+        name: String,
+        threshold: u64,
+        total_weight: u64,
+    },
+    // still collecting approvals and couldn't be approved anymore
+    IntentUnexecutable {
+        key: String,
+        role: String,
+    },
+}
+
+pub fn analyze_config_change(
+    multisig: &Multisig,
+    proposed: &ConfigMultisigFields,
+) -> Vec<ConfigWarning> {
+    let current = simulate_config(&multisig.config.to_fields());
+    let proposed = simulate_config(proposed);
+
+    let mut warnings = std::iter::once(&proposed.global)
+        .chain(&proposed.roles)
+        .filter(|report| !report.achievable)
+        .filter(|report| {
+            std::iter::once(&current.global)
+                .chain(&current.roles)
+                .any(|before| before.name == report.name && before.achievable)
+        })
+        .map(|report| ConfigWarning::ThresholdUnreachable {
+            name: report.name.clone(),
+            threshold: report.threshold,
+            total_weight: report.total_weight,
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(intents) = &multisig.intents {
+        let mut pending = intents
+            .intents
+            .values()
+            .filter(|intent| !multisig.is_approved(intent) && !proposed.can_approve(&intent.role))
+            .collect::<Vec<_>>();
+        pending.sort_by(|a, b| a.key.cmp(&b.key));
+        warnings.extend(
+            pending
+                .into_iter()
+                .map(|intent| ConfigWarning::IntentUnexecutable {
+                    key: intent.key.clone(),
+                    role: intent.role.clone(),
+                }),
+        );
+    }
+    warnings
+}
+
+fn quorum(name: &str, threshold: u64, members: &[(Address, u64)]) -> QuorumReport {
+    let total_weight = members.iter().map(|(_, weight)| weight).sum::<u64>();
+    let achievable = total_weight >= threshold;
+
+    // taking the heaviest members first minimizes the number of signers
+    let mut sorted = members.to_vec();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1));
+    let mut min_signers = Vec::new();
+    let mut weight = 0;
+    if achievable {
+        for (addr, member_weight) in sorted {
+            if weight >= threshold {
+                break;
+            }
+            weight += member_weight;
+            min_signers.push(addr);
+        }
+    }
+
+    let solo_signers = members
+        .iter()
+        .filter(|(_, weight)| threshold > 0 && *weight >= threshold)
+        .map(|(addr, _)| *addr)
+        .collect();
+    let required_signers = members
+        .iter()
+        .filter(|(_, weight)| achievable && *weight > 0 && total_weight - weight < threshold)
+        .map(|(addr, _)| *addr)
+        .collect();
+
+    QuorumReport {
+        name: name.to_string(),
+        threshold,
+        total_weight,
+        achievable,
+        min_signers,
+        solo_signers,
+        required_signers,
+    }
+}
+
+impl fmt::Display for QuorumReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: threshold {} / total weight {}{}",
+            self.name,
+            self.threshold,
+            self.total_weight,
+            if self.achievable {
+                ""
+            } else {
+                " (unreachable)"
+            }
+        )?;
+        if self.achievable {
+            writeln!(f, "  min signers: {}", join(&self.min_signers))?;
+        }
+        if !self.solo_signers.is_empty() {
+            writeln!(f, "  can approve alone: {}", join(&self.solo_signers))?;
+        }
+        if !self.required_signers.is_empty() {
+            writeln!(f, "  required: {}", join(&self.required_signers))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigSimulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.global)?;
+        for role in &self.roles {
+            write!(f, "{}", role)?;
+        }
+        for (role, achievable) in &self.intent_roles {
+            if !achievable {
+                writeln!(f, "{} intents cannot be executed", role)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigWarning::ThresholdUnreachable {
+                name,
+                threshold,
+                total_weight,
+            } => write!(
+                f,
+                "{} threshold {} cannot be reached anymore (total weight {})",
+                name, threshold, total_weight
+            ),
+            ConfigWarning::IntentUnexecutable { key, role } => write!(
+                f,
+                "pending proposal {} ({}) could not be approved anymore",
+                key, role
+            ),
+        }
+    }
+}
+
+fn join(addresses: &[Address]) -> String {
+    addresses
+        .iter()
+        .map(|addr| addr.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roles::{Package, Role};
+
+    #[test]
+    fn simulate_weights_and_roles() {
+        let role = Role::for_module(Package::Actions, "currency_intents").to_string();
+        let (alice, bob, carol) = (
+            Address::new([0xa; 32]),
+            Address::new([0xb; 32]),
+            Address::new([0xc; 32]),
+        );
+        let config = ConfigMultisigFields {
+            global: 4,
+            members: vec![
+                (alice, 3, vec![role.clone()]),
+                (bob, 1, vec![]),
+                (carol, 1, vec![role.clone()]),
+            ],
+            roles: vec![(role.clone(), 3)],
+        };
+
+        let simulation = simulate_config(&config);
+        assert!(simulation.global.achievable);
+        assert_eq!(simulation.global.min_signers, vec![alice, bob]);
+        assert!(simulation.global.solo_signers.is_empty());
+        assert_eq!(simulation.global.required_signers, vec![alice]);
+        assert_eq!(simulation.roles[0].solo_signers, vec![alice]);
+        assert_eq!(simulation.single_points_of_failure(), vec![alice]);
+
+        let unreachable = simulate_config(&ConfigMultisigFields {
+            global: 10,
+            members: vec![(alice, 3, vec![])],
+            roles: vec![],
+        });
+        assert!(!unreachable.global.achievable);
+        assert!(unreachable.intent_roles.iter().all(|(_, ok)| !ok));
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::anyhow;
+use pyo3::prelude::*;
+use serde_json::Value;
+use sui_sdk_types::{Address, UserSignature};
+use sui_transaction_builder::TransactionBuilder;
+use tokio::runtime::Runtime;
+
+use crate::json;
+use crate::native_multisig::SigningPayload;
+use crate::proposals::params::{MintAndTransferArgs, SpendAndTransferArgs};
+use crate::utils;
+use crate::MultisigClient;
+
+// python bindings, built with maturin: `maturin develop --features python`
+// read methods return dicts and lists, transaction methods return the base64 bcs bytes
+// of the unsigned transaction to be signed by the sender then passed to execute_signed
+
+#[pyclass(name = "MultisigClient")]
+pub struct PyMultisigClient {
+    runtime: Runtime,
+    inner: MultisigClient,
+}
+
+#[pymethods]
+impl PyMultisigClient {
+    // testnet if no graphql url is provided
+    #[new]
+    #[pyo3(signature = (url=None))]
+    fn new(url: Option<&str>) -> PyResult<Self> {
+        let inner = match url {
+            Some(url) => MultisigClient::new_with_url(url)?,
+            None => MultisigClient::new_testnet(),
+        };
+        Ok(Self {
+            runtime: Runtime::new()?,
+            inner,
+        })
+    }
+
+    #[staticmethod]
+    fn mainnet() -> PyResult<Self> {
+        Ok(Self {
+            runtime: Runtime::new()?,
+            inner: MultisigClient::new_mainnet(),
+        })
+    }
+
+    // === Loading ===
+
+    fn load_multisig(&mut self, id: &str) -> PyResult<()> {
+        let id = parse_address(id)?;
+        Ok(self.runtime.block_on(self.inner.load_multisig(id))?)
+    }
+
+    fn load_user(&mut self, address: &str) -> PyResult<()> {
+        let address = parse_address(address)?;
+        Ok(self.runtime.block_on(self.inner.load_user(address))?)
+    }
+
+    fn refresh(&mut self) -> PyResult<()> {
+        Ok(self.runtime.block_on(self.inner.refresh())?)
+    }
+
+    // === Reads ===
+
+    fn multisig(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let multisig = self
+            .inner
+            .multisig()
+            .ok_or(anyhow!("Multisig not loaded"))?;
+        to_py(py, json::multisig(multisig))
+    }
+
+    fn intents(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let intents = self.inner.intents().ok_or(anyhow!("Intents not loaded"))?;
+        to_py(py, json::intents(intents))
+    }
+
+    // with the deserialized actions
+    fn intent(&mut self, py: Python<'_>, key: &str) -> PyResult<PyObject> {
+        let intent = self.inner.intent_mut(key)?;
+        self.runtime.block_on(intent.get_actions_args())?;
+        to_py(py, json::intent(intent))
+    }
+
+    fn vaults(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let dynamic_fields = self
+            .inner
+            .dynamic_fields()
+            .ok_or(anyhow!("Dynamic fields not loaded"))?;
+        to_py(py, json::vaults(dynamic_fields))
+    }
+
+    fn owned(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let owned_objects = self
+            .inner
+            .owned_objects()
+            .ok_or(anyhow!("Owned objects not loaded"))?;
+        to_py(py, json::owned(owned_objects))
+    }
+
+    // === Transactions ===
+
+    fn approve_intent(&self, sender: &str, key: &str) -> PyResult<String> {
+        Ok(self.runtime.block_on(async {
+            let sender = parse_address(sender)?;
+            let mut builder = self.inner.init_tx(sender).await?;
+            self.inner.approve_intent(&mut builder, key, sender).await?;
+            tx_bytes(builder)
+        })?)
+    }
+
+    fn disapprove_intent(&self, sender: &str, key: &str) -> PyResult<String> {
+        Ok(self.runtime.block_on(async {
+            let sender = parse_address(sender)?;
+            let mut builder = self.inner.init_tx(sender).await?;
+            self.inner
+                .disapprove_intent(&mut builder, key, sender)
+                .await?;
+            tx_bytes(builder)
+        })?)
+    }
+
+    #[pyo3(signature = (sender, key, coin_type, amounts, recipients, description=String::new()))]
+    fn propose_mint_and_transfer(
+        &self,
+        sender: &str,
+        key: String,
+        coin_type: &str,
+        amounts: Vec<u64>,
+        recipients: Vec<String>,
+        description: String,
+    ) -> PyResult<String> {
+        let recipients = parse_addresses(&recipients)?;
+        Ok(self.runtime.block_on(async {
+            let mut builder = self.inner.init_tx(parse_address(sender)?).await?;
+            let intent_args = self
+                .inner
+                .intent_params(&key)
+                .await?
+                .description(&description)
+                .build(&mut builder);
+            let actions_args = MintAndTransferArgs::new(&mut builder, amounts, recipients);
+            self.inner
+                .request_mint_and_transfer(&mut builder, intent_args, actions_args, coin_type)
+                .await?;
+            tx_bytes(builder)
+        })?)
+    }
+
+    #[pyo3(signature = (sender, key, vault_name, coin_type, amounts, recipients, description=String::new()))]
+    #[allow(clippy::too_many_arguments)]
+    fn propose_spend_and_transfer(
+        &self,
+        sender: &str,
+        key: String,
+        vault_name: String,
+        coin_type: &str,
+        amounts: Vec<u64>,
+        recipients: Vec<String>,
+        description: String,
+    ) -> PyResult<String> {
+        let recipients = parse_addresses(&recipients)?;
+        Ok(self.runtime.block_on(async {
+            let mut builder = self.inner.init_tx(parse_address(sender)?).await?;
+            let intent_args = self
+                .inner
+                .intent_params(&key)
+                .await?
+                .description(&description)
+                .build(&mut builder);
+            let actions_args =
+                SpendAndTransferArgs::new(&mut builder, vault_name, amounts, recipients);
+            self.inner
+                .request_spend_and_transfer(&mut builder, intent_args, actions_args, coin_type)
+                .await?;
+            tx_bytes(builder)
+        })?)
+    }
+
+    // submits a transaction returned by the methods above with its base64 signatures,
+    // returns the digest
+    fn execute_signed(&self, tx_bytes: String, signatures: Vec<String>) -> PyResult<String> {
+        let tx = SigningPayload::from_tx_bytes(tx_bytes)?.transaction()?;
+        let signatures = signatures
+            .iter()
+            .map(|sig| {
+                UserSignature::from_base64(sig).map_err(|e| anyhow!("Invalid signature: {}", e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.runtime
+            .block_on(utils::submit_tx(self.inner.sui(), &tx, signatures))?;
+        Ok(tx.digest().to_string())
+    }
+}
+
+// called by the extension module of the python/ crate
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMultisigClient>()?;
+    Ok(())
+}
+
+// === Helpers ===
+
+fn parse_address(address: &str) -> anyhow::Result<Address> {
+    address
+        .parse()
+        .map_err(|_| anyhow!("Invalid address: {}", address))
+}
+
+fn parse_addresses(addresses: &[String]) -> anyhow::Result<Vec<Address>> {
+    addresses.iter().map(|addr| parse_address(addr)).collect()
+}
+
+fn tx_bytes(builder: TransactionBuilder) -> anyhow::Result<String> {
+    Ok(SigningPayload::new(&builder.finish()?)?.tx_bytes)
+}
+
+// through the json module to get native dicts and lists
+fn to_py(py: Python<'_>, value: Value) -> PyResult<PyObject> {
+    let loads = py.import_bound("json")?.getattr("loads")?;
+    Ok(loads.call1((value.to_string(),))?.unbind())
+}
@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ACCOUNT_ACTIONS_PACKAGE, ACCOUNT_MULTISIG_PACKAGE, ACCOUNT_PROTOCOL_PACKAGE};
+
+// packages defining intents, a role is the package address + the module of the intent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Package {
+    Protocol,
+    Actions,
+    Multisig,
+}
+
+impl Package {
+    // address as it appears in role names (no 0x prefix)
+    pub fn address(&self) -> &'static str {
+        let addr = match self {
+            Package::Protocol => ACCOUNT_PROTOCOL_PACKAGE,
+            Package::Actions => ACCOUNT_ACTIONS_PACKAGE,
+            Package::Multisig => ACCOUNT_MULTISIG_PACKAGE,
+        };
+        addr.trim_start_matches("0x")
+    }
+
+    pub fn alias(&self) -> &'static str {
+        match self {
+            Package::Protocol => "protocol",
+            Package::Actions => "actions",
+            Package::Multisig => "multisig",
+        }
+    }
+
+    // modules containing intents that can be used as roles
+    pub fn intent_modules(&self) -> &'static [&'static str] {
+        match self {
+            Package::Protocol => &["config"],
+            Package::Actions => &[
+                "access_control_intents",
+                "currency_intents",
+                "kiosk_intents",
+                "owned_intents",
+                "package_upgrade_intents",
+                "vault_intents",
+            ],
+            Package::Multisig => &["config"],
+        }
+    }
+
+    pub fn from_alias(alias: &str) -> Option<Self> {
+        match alias {
+            "protocol" => Some(Package::Protocol),
+            "actions" => Some(Package::Actions),
+            "multisig" => Some(Package::Multisig),
+            _ => None,
+        }
+    }
+
+    pub fn from_address(address: &str) -> Option<Self> {
+        [Package::Protocol, Package::Actions, Package::Multisig]
+            .into_iter()
+            .find(|package| package.address() == address)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Role {
+    // 64 hex chars, no 0x prefix
+    pub address: String,
+    pub module: String,
+}
+
+impl Role {
+    pub fn for_module(package: Package, module: &str) -> Self {
+        Self {
+            address: package.address().to_string(),
+            module: module.to_string(),
+        }
+    }
+
+    pub fn new(address: &str, module: &str) -> Result<Self> {
+        Ok(Self {
+            address: normalize_address(address)?,
+            module: validate_module(module)?,
+        })
+    }
+
+    pub fn package(&self) -> Option<Package> {
+        Package::from_address(&self.address)
+    }
+
+    // whether the role can be attached to an intent from the known packages
+    pub fn is_known(&self) -> bool {
+        self.package()
+            .is_some_and(|package| package.intent_modules().contains(&self.module.as_str()))
+    }
+
+    // "actions::currency_intents" for known packages, full role name otherwise
+    pub fn short_name(&self) -> String {
+        match self.package() {
+            Some(package) => format!("{}::{}", package.alias(), self.module),
+            None => self.to_string(),
+        }
+    }
+}
+
+// accepts "<address>::<module>" with or without 0x prefix or "<protocol|actions|multisig>::<module>"
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (package, module) = s
+            .split_once("::")
+            .ok_or(anyhow!("Invalid role {}: expected <package>::<module>", s))?;
+
+        match Package::from_alias(package) {
+            Some(package) => Ok(Self::for_module(package, &validate_module(module)?)),
+            None => Self::new(package, module),
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.address, self.module)
+    }
+}
+
+// role names are free-form in the multisig config, names of package roles are normalized
+// ("actions::currency_intents" to the full name) and any other non-empty name is kept as is
+pub fn normalize_role_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Empty role name"));
+    }
+    Ok(name
+        .parse::<Role>()
+        .map(|role| role.to_string())
+        .unwrap_or(name.to_string()))
+}
+
+#[derive(Debug)]
+pub struct RoleOverview {
+    pub name: String,
+    pub threshold: u64,
+    pub total_weight: u64,
+    // (address, weight) of the members having the role
+    pub members: Vec<(String, u64)>,
+}
+
+// === Helpers ===
+
+fn normalize_address(address: &str) -> Result<String> {
+    let hex = address.trim_start_matches("0x").to_lowercase();
+    if hex.is_empty() || hex.len() > 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("Invalid role address: {}", address));
+    }
+    Ok(format!("{:0>64}", hex))
+}
+
+fn validate_module(module: &str) -> Result<String> {
+    let mut chars = module.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(anyhow!("Invalid role module: {}", module));
+    }
+    Ok(module.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_alias_and_address() {
+        let role: Role = "actions::currency_intents".parse().unwrap();
+        assert_eq!(role, Role::for_module(Package::Actions, "currency_intents"));
+        assert!(role.is_known());
+        assert_eq!(role.short_name(), "actions::currency_intents");
+
+        let full: Role = format!("{}::currency_intents", ACCOUNT_ACTIONS_PACKAGE)
+            .parse()
+            .unwrap();
+        assert_eq!(full, role);
+
+        let padded: Role = "0x2::coin".parse().unwrap();
+        assert_eq!(padded.to_string(), format!("{:0>64}::coin", "2"));
+        assert!(!padded.is_known());
+    }
+
+    #[test]
+    fn reject_invalid_roles() {
+        assert!("admin".parse::<Role>().is_err());
+        assert!("0xzz::module".parse::<Role>().is_err());
+        assert!("actions::1module".parse::<Role>().is_err());
+        assert!("actions::".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn normalize_free_form_names() {
+        assert_eq!(
+            normalize_role_name("actions::currency_intents").unwrap(),
+            format!("{}::currency_intents", Package::Actions.address())
+        );
+        assert_eq!(normalize_role_name("admin").unwrap(), "admin");
+        assert_eq!(normalize_role_name(" treasury ops ").unwrap(), "treasury ops");
+        assert!(normalize_role_name(" ").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use sui_graphql_client::Client;
+use sui_sdk_types::Address;
+
+use crate::json;
+use crate::multisig::Multisig;
+
+// read-only HTTP API over the multisig accounts, each request fetches a fresh snapshot:
+// GET /multisig/:id
+// GET /multisig/:id/intents
+// GET /multisig/:id/intents/:key
+// GET /multisig/:id/vaults
+// GET /multisig/:id/owned
+pub fn router(sui_client: Arc<Client>) -> Router {
+    Router::new()
+        .route("/multisig/:id", get(get_multisig))
+        .route("/multisig/:id/intents", get(get_intents))
+        .route("/multisig/:id/intents/:key", get(get_intent))
+        .route("/multisig/:id/vaults", get(get_vaults))
+        .route("/multisig/:id/owned", get(get_owned))
+        .with_state(sui_client)
+}
+
+pub async fn serve(sui_client: Arc<Client>, addr: SocketAddr) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(sui_client)).await?;
+    Ok(())
+}
+
+// === Handlers ===
+
+async fn get_multisig(
+    State(sui_client): State<Arc<Client>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let multisig = load(sui_client, &id).await?;
+    Ok(Json(json::multisig(&multisig)))
+}
+
+async fn get_intents(
+    State(sui_client): State<Arc<Client>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let multisig = load(sui_client, &id).await?;
+    let intents = multisig
+        .intents
+        .as_ref()
+        .ok_or(anyhow!("Intents not loaded"))?;
+    Ok(Json(json::intents(intents)))
+}
+
+async fn get_intent(
+    State(sui_client): State<Arc<Client>>,
+    Path((id, key)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    let mut multisig = load(sui_client, &id).await?;
+    let intent = multisig
+        .intents
+        .as_mut()
+        .and_then(|intents| intents.get_intent_mut(&key))
+        .ok_or(ApiError::NotFound(format!("Intent {} not found", key)))?;
+    intent.get_actions_args().await?;
+    Ok(Json(json::intent(intent)))
+}
+
+async fn get_vaults(
+    State(sui_client): State<Arc<Client>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let multisig = load(sui_client, &id).await?;
+    let dynamic_fields = multisig
+        .dynamic_fields
+        .as_ref()
+        .ok_or(anyhow!("Dynamic fields not loaded"))?;
+    Ok(Json(json::vaults(dynamic_fields)))
+}
+
+async fn get_owned(
+    State(sui_client): State<Arc<Client>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let multisig = load(sui_client, &id).await?;
+    let owned_objects = multisig
+        .owned_objects
+        .as_ref()
+        .ok_or(anyhow!("Owned objects not loaded"))?;
+    Ok(Json(json::owned(owned_objects)))
+}
+
+// === Helpers ===
+
+async fn load(sui_client: Arc<Client>, id: &str) -> Result<Multisig, ApiError> {
+    let id = id
+        .parse::<Address>()
+        .map_err(|_| ApiError::BadRequest(format!("Invalid multisig id {}", id)))?;
+    Ok(Multisig::from_id(sui_client, id).await?)
+}
+
+enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError::Internal(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            ApiError::Internal(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
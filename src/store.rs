@@ -0,0 +1,289 @@
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sui_sdk_types::Address;
+
+use crate::json;
+use crate::multisig::Multisig;
+use crate::notify::IntentEvent;
+
+// local copy of the fetched multisigs for offline browsing
+
+const V1: &str = "
+    CREATE TABLE multisigs (
+        id TEXT PRIMARY KEY,
+        version INTEGER NOT NULL,
+        snapshot TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE intents (
+        multisig_id TEXT NOT NULL,
+        key TEXT NOT NULL,
+        snapshot TEXT NOT NULL,
+        PRIMARY KEY (multisig_id, key)
+    );
+    CREATE TABLE activity (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        multisig_id TEXT NOT NULL,
+        key TEXT NOT NULL,
+        event TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX activity_multisig ON activity (multisig_id, created_at);
+";
+
+// applied in order, the number of applied migrations is stored as user_version
+const MIGRATIONS: &[&str] = &[V1];
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub id: Address,
+    // version of the Account object when the snapshot was taken
+    pub version: u64,
+    pub multisig: Value,
+    pub intents: Vec<Value>,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Activity {
+    pub key: String,
+    pub event: String,
+    pub payload: Value,
+    pub created_at: u64,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let mut store = Self {
+            conn: Connection::open(path)?,
+        };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        let mut store = Self {
+            conn: Connection::open_in_memory()?,
+        };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    // replaces the previous snapshot of the multisig and its intents,
+    // the differences with the previous snapshot are recorded as activity
+    pub fn save_snapshot(&mut self, multisig: &Multisig) -> Result<()> {
+        let id = multisig.id.to_string();
+        let intents = multisig
+            .intents
+            .as_ref()
+            .ok_or(anyhow!("Intents not loaded"))?;
+        let previous = self.intent_snapshots(&id)?;
+        let now = now()? as i64;
+
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO multisigs (id, version, snapshot, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![id, multisig.version as i64, json::multisig(multisig).to_string(), now],
+        )?;
+        tx.execute("DELETE FROM intents WHERE multisig_id = ?1", params![id])?;
+
+        for (key, intent) in &intents.intents {
+            let snapshot = json::intent(intent);
+            let event = match previous.get(key) {
+                None => Some("proposal_created"),
+                Some(old) if old["outcome"]["approved"] != snapshot["outcome"]["approved"] => {
+                    Some("proposal_approvals_changed")
+                }
+                _ => None,
+            };
+            if let Some(event) = event {
+                insert_activity(&tx, &id, key, event, &snapshot, now)?;
+            }
+            tx.execute(
+                "INSERT INTO intents (multisig_id, key, snapshot) VALUES (?1, ?2, ?3)",
+                params![id, key, snapshot.to_string()],
+            )?;
+        }
+        // executed or deleted
+        for (key, old) in &previous {
+            if !intents.intents.contains_key(key) {
+                insert_activity(&tx, &id, key, "proposal_removed", old, now)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_snapshot(&self, id: Address) -> Result<Option<Snapshot>> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT version, snapshot, updated_at FROM multisigs WHERE id = ?1",
+                params![id.to_string()],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((version, multisig, updated_at)) = row else {
+            return Ok(None);
+        };
+
+        let mut statement = self
+            .conn
+            .prepare("SELECT snapshot FROM intents WHERE multisig_id = ?1")?;
+        let mut intents = statement
+            .query_map(params![id.to_string()], |row| row.get::<_, String>(0))?
+            .map(|snapshot| Ok(serde_json::from_str::<Value>(&snapshot?)?))
+            .collect::<Result<Vec<_>>>()?;
+        intents.sort_by_key(|intent| intent["creation_time"].as_u64());
+
+        Ok(Some(Snapshot {
+            id,
+            version: version as u64,
+            multisig: serde_json::from_str(&multisig)?,
+            intents,
+            updated_at: updated_at as u64,
+        }))
+    }
+
+    // id of the multisig saved last, to browse offline when none is loaded
+    pub fn last_multisig(&self) -> Result<Option<Address>> {
+        let id = self
+            .conn
+            .query_row(
+                "SELECT id FROM multisigs ORDER BY updated_at DESC LIMIT 1",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        id.map(|id| id.parse().map_err(|_| anyhow!("Invalid stored id {}", id)))
+            .transpose()
+    }
+
+    // events from notify::IntentWatcher
+    pub fn record_activity(&mut self, event: &IntentEvent) -> Result<()> {
+        insert_activity(
+            &self.conn,
+            &event.multisig.to_string(),
+            &event.key,
+            event.kind.as_str(),
+            &event.to_json(),
+            now()? as i64,
+        )
+    }
+
+    // most recent first
+    pub fn activity(&self, id: Address, limit: usize) -> Result<Vec<Activity>> {
+        let mut statement = self.conn.prepare(
+            "SELECT key, event, payload, created_at FROM activity
+            WHERE multisig_id = ?1 ORDER BY created_at DESC, id DESC LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![id.to_string(), limit as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (key, event, payload, created_at) = row?;
+            Ok(Activity {
+                key,
+                event,
+                payload: serde_json::from_str(&payload)?,
+                created_at: created_at as u64,
+            })
+        })
+        .collect()
+    }
+
+    // === Helpers ===
+
+    fn intent_snapshots(&self, id: &str) -> Result<HashMap<String, Value>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT key, snapshot FROM intents WHERE multisig_id = ?1")?;
+        let rows = statement.query_map(params![id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (key, snapshot) = row?;
+            Ok((key, serde_json::from_str(&snapshot)?))
+        })
+        .collect()
+    }
+
+    fn migrate(&mut self) -> Result<()> {
+        let applied: usize = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))?
+            as usize;
+        if applied > MIGRATIONS.len() {
+            return Err(anyhow!(
+                "Database schema version {} is newer than supported ({})",
+                applied,
+                MIGRATIONS.len()
+            ));
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+}
+
+fn insert_activity(
+    conn: &Connection,
+    id: &str,
+    key: &str,
+    event: &str,
+    payload: &Value,
+    created_at: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO activity (multisig_id, key, event, payload, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, key, event, payload.to_string(), created_at],
+    )?;
+    Ok(())
+}
+
+fn now() -> Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_idempotent() {
+        let mut store = Store::open_in_memory().unwrap();
+        store.migrate().unwrap();
+        let version: i64 = store
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version as usize, MIGRATIONS.len());
+        assert!(store.last_multisig().unwrap().is_none());
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use sui_graphql_client::Client;
+use sui_sdk_types::Address;
+
+use crate::errors::MultisigError;
+
+// SuiNS names accepted wherever a recipient or a member address is expected:
+// alice.sui, sub.alice.sui or @alice, resolved once per name
+#[derive(Debug, Default)]
+pub struct NameCache {
+    inner: RwLock<HashMap<String, Address>>,
+}
+
+impl NameCache {
+    // addresses are returned as is
+    pub async fn resolve(&self, sui_client: &Client, name_or_address: &str) -> Result<Address> {
+        if let Ok(address) = name_or_address.trim().parse::<Address>() {
+            return Ok(address);
+        }
+        let name = normalize_name(name_or_address).ok_or(anyhow!(
+            "{} is neither an address nor a SuiNS name",
+            name_or_address
+        ))?;
+        if let Some(address) = self
+            .inner
+            .read()
+            .ok()
+            .and_then(|inner| inner.get(&name).copied())
+        {
+            return Ok(address);
+        }
+
+        let address = sui_client
+            .resolve_suins_to_address(&name)
+            .await?
+            .ok_or(MultisigError::UnresolvedName { name: name.clone() })?;
+        self.inner
+            .write()
+            .map_err(|_| anyhow!("Name cache poisoned"))?
+            .insert(name, address);
+        Ok(address)
+    }
+
+    pub async fn resolve_all(
+        &self,
+        sui_client: &Client,
+        names_or_addresses: &[impl AsRef<str>],
+    ) -> Result<Vec<Address>> {
+        let mut addresses = Vec::with_capacity(names_or_addresses.len());
+        for name_or_address in names_or_addresses {
+            addresses.push(self.resolve(sui_client, name_or_address.as_ref()).await?);
+        }
+        Ok(addresses)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.clear();
+        }
+    }
+}
+
+// lowercase name ending with .sui, None if it can't be a SuiNS name
+pub fn normalize_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let name = match name.strip_prefix('@') {
+        Some(label) => format!("{}.sui", label),
+        None => name,
+    };
+    let labels = name.strip_suffix(".sui")?.split('.').collect::<Vec<_>>();
+    labels
+        .iter()
+        .all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+        .then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_names() {
+        assert_eq!(normalize_name("Alice.sui"), Some("alice.sui".to_string()));
+        assert_eq!(normalize_name("@alice"), Some("alice.sui".to_string()));
+        assert_eq!(
+            normalize_name("pay.alice.sui"),
+            Some("pay.alice.sui".to_string())
+        );
+        assert_eq!(normalize_name("alice"), None);
+        assert_eq!(normalize_name("alice..sui"), None);
+        assert_eq!(normalize_name("0x1"), None);
+    }
+}
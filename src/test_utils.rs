@@ -0,0 +1,165 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_graphql_client::Client;
+use sui_sdk_types::{Address, Argument, ObjectDigest};
+use sui_transaction_builder::{unresolved::Input, TransactionBuilder};
+
+use crate::multisig::{Config, LoadOptions, Member, Multisig};
+use crate::proposals::intents::{Approvals, Intent, Intents};
+use crate::proposals::{batch::RequestInputs, params::ParamsArgs};
+use crate::{utils, CLOCK_OBJECT_ID};
+
+// multisig with the members (weight 1) and the global threshold, nothing else loaded
+pub fn multisig(members: &[(Address, Vec<&str>)], global_threshold: u64) -> Multisig {
+    let mut config = Config::default();
+    config.global.threshold = global_threshold;
+    for (address, roles) in members {
+        config.members.push(Member {
+            address: address.to_string(),
+            weight: 1,
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            ..Default::default()
+        });
+    }
+    Multisig {
+        sui_client: Arc::new(Client::new_testnet()),
+        id: Address::ZERO,
+        version: 0,
+        metadata: HashMap::new(),
+        deps: Vec::new(),
+        unverified_deps_allowed: false,
+        intents_bag_id: Address::ZERO,
+        locked_objects: Vec::new(),
+        config,
+        intents: None,
+        owned_objects: None,
+        dynamic_fields: None,
+        consistent_reads: None,
+        load_options: LoadOptions::default(),
+    }
+}
+
+// executable once at 0 and expiring at 1_000, without approvals nor actions
+pub fn intent(key: &str, role: &str) -> Intent {
+    Intent {
+        sui_client: Arc::new(Client::new_testnet()),
+        type_: String::new(),
+        key: key.to_string(),
+        description: String::new(),
+        account: Address::ZERO,
+        creator: Address::ZERO,
+        creation_time: 0,
+        execution_times: vec![0],
+        expiration_time: 1_000,
+        role: role.to_string(),
+        actions_bag_id: Address::ZERO,
+        actions_types_bcs: Vec::new(),
+        actions_args: None,
+        outcome: Approvals {
+            total_weight: 0,
+            role_weight: 0,
+            approved: Vec::new(),
+        },
+    }
+}
+
+pub fn intents(intents: Vec<Intent>) -> Intents {
+    Intents {
+        sui_client: Arc::new(Client::new_testnet()),
+        bag_id: Address::ZERO,
+        intents: intents
+            .into_iter()
+            .map(|intent| (intent.key.clone(), intent))
+            .collect(),
+    }
+}
+
+// snapshots of the PTBs built by the request_*/execute_* methods, compared without a network:
+// objects are given as fixed inputs and the transaction is never executed
+
+// fixed sender and gas object
+pub fn offline_builder() -> TransactionBuilder {
+    let mut builder = TransactionBuilder::new();
+    builder.add_gas_objects(vec![Input::owned(
+        Address::new([0xff; 32]),
+        1,
+        ObjectDigest::new([0; 32]),
+    )]);
+    builder.set_gas_budget(utils::DEFAULT_GAS_BUDGET);
+    builder.set_gas_price(1000);
+    builder.set_sender(Address::ZERO);
+    builder
+}
+
+pub fn shared_input(builder: &mut TransactionBuilder, id: Address, mutable: bool) -> Argument {
+    builder.input(Input::shared(id, 1, mutable))
+}
+
+// multisig and clock of the request_*_with methods
+pub fn request_inputs(builder: &mut TransactionBuilder) -> RequestInputs {
+    let multisig = shared_input(builder, Address::new([0xaa; 32]), true);
+    let clock = shared_input(builder, *CLOCK_OBJECT_ID, false);
+    RequestInputs {
+        multisig: multisig.into(),
+        clock: clock.into(),
+    }
+}
+
+// executed once right away
+pub fn params(builder: &mut TransactionBuilder, key: &str) -> ParamsArgs {
+    ParamsArgs::new(builder, key.to_string(), "".to_string(), vec![0], 1)
+}
+
+// inputs and commands of the transaction, with sorted keys
+pub fn ptb_json(builder: TransactionBuilder) -> Value {
+    let tx = builder.finish().expect("Transaction should be complete");
+    canonical(serde_json::to_value(&tx.kind).expect("Transaction should serialize"))
+}
+
+// compares with tests/snapshots/<name>.json, a missing snapshot fails the test,
+// both are (re)written when UPDATE_SNAPSHOTS is set (review the diff before committing it)
+pub fn assert_ptb_snapshot(name: &str, builder: TransactionBuilder) {
+    let actual = serde_json::to_string_pretty(&ptb_json(builder)).unwrap() + "\n";
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.json", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let Ok(expected) = std::fs::read_to_string(&path) else {
+        panic!(
+            "PTB snapshot {} is missing, run with UPDATE_SNAPSHOTS=1 to write it\n{}",
+            path.display(),
+            actual
+        );
+    };
+    assert!(
+        expected == actual,
+        "PTB snapshot {} changed, run with UPDATE_SNAPSHOTS=1 to accept it\n--- expected\n{}\n--- actual\n{}",
+        path.display(),
+        expected,
+        actual
+    );
+}
+
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonical).collect()),
+        other => other,
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+use sui_sdk_types::{Address, StructTag, TypeTag};
+
+// a Move struct type such as 0x2::sui::SUI or 0x2::coin::Coin<0x2::sui::SUI>,
+// validated when created instead of when building the move calls
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructType(StructTag);
+
+// T in 0x2::coin::Coin<T>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CoinType(StructType);
+
+// accepted by the methods taking a type argument: &str, String, TypeTag, StructType, CoinType...
+pub trait IntoStructType {
+    fn into_struct_type(self) -> Result<StructType>;
+}
+
+impl<T> IntoStructType for T
+where
+    T: TryInto<StructType>,
+    T::Error: Into<anyhow::Error>,
+{
+    fn into_struct_type(self) -> Result<StructType> {
+        self.try_into().map_err(Into::into)
+    }
+}
+
+// === StructType ===
+
+impl StructType {
+    pub fn tag(&self) -> &StructTag {
+        &self.0
+    }
+
+    // a filter without type params matches all instantiations:
+    // 0x2::coin::Coin matches 0x2::coin::Coin<0x2::sui::SUI>
+    pub fn matches(&self, filter: &StructType) -> bool {
+        let (tag, filter) = (&self.0, &filter.0);
+        tag.address == filter.address
+            && tag.module == filter.module
+            && tag.name == filter.name
+            && (filter.type_params.is_empty() || tag.type_params == filter.type_params)
+    }
+}
+
+impl FromStr for StructType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().parse::<TypeTag>() {
+            Ok(TypeTag::Struct(tag)) => Ok(Self(*tag)),
+            _ => Err(anyhow!("Invalid struct type: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<&str> for StructType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&String> for StructType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for StructType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<TypeTag> for StructType {
+    type Error = anyhow::Error;
+
+    fn try_from(type_tag: TypeTag) -> Result<Self> {
+        match type_tag {
+            TypeTag::Struct(tag) => Ok(Self(*tag)),
+            other => Err(anyhow!("Not a struct type: {}", other)),
+        }
+    }
+}
+
+impl From<StructTag> for StructType {
+    fn from(tag: StructTag) -> Self {
+        Self(tag)
+    }
+}
+
+impl From<StructType> for TypeTag {
+    fn from(struct_type: StructType) -> Self {
+        TypeTag::Struct(Box::new(struct_type.0))
+    }
+}
+
+impl fmt::Display for StructType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// === CoinType ===
+
+impl CoinType {
+    pub fn struct_type(&self) -> &StructType {
+        &self.0
+    }
+
+    // 0x2::coin::Coin<T>
+    pub fn coin(&self) -> Result<StructType> {
+        format!("0x2::coin::Coin<{}>", self).parse()
+    }
+}
+
+impl FromStr for CoinType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let struct_type: StructType = s.parse()?;
+        let tag = struct_type.tag();
+        if tag.address == Address::TWO
+            && tag.module.as_str() == "coin"
+            && tag.name.as_str() == "Coin"
+        {
+            return Err(anyhow!("Expected the coin type T, not {}", s));
+        }
+        Ok(Self(struct_type))
+    }
+}
+
+impl TryFrom<&str> for CoinType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&String> for CoinType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for CoinType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<CoinType> for StructType {
+    fn from(coin_type: CoinType) -> Self {
+        coin_type.0
+    }
+}
+
+impl fmt::Display for CoinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_struct_types() {
+        let sui: StructType = "0x2::sui::SUI".parse().unwrap();
+        assert_eq!(sui.tag().name.as_str(), "SUI");
+        assert!("0x2::coin::Coin<0x2::sui::SUI>"
+            .parse::<StructType>()
+            .is_ok());
+        assert!("u64".parse::<StructType>().is_err());
+        assert!("not a type".parse::<StructType>().is_err());
+        assert!("0x2::sui::SUI".into_struct_type().is_ok());
+        assert!(sui.clone().into_struct_type().is_ok());
+    }
+
+    #[test]
+    fn match_struct_types() {
+        let coin: StructType = "0x2::coin::Coin<0x2::sui::SUI>".parse().unwrap();
+        assert!(coin.matches(&"0x2::coin::Coin".parse().unwrap()));
+        assert!(coin.matches(&"0x2::coin::Coin<0x2::sui::SUI>".parse().unwrap()));
+        assert!(!coin.matches(&"0x2::coin::Coin<0x3::sui::SUI>".parse().unwrap()));
+        assert!(!coin.matches(&"0x2::balance::Balance".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_coin_types() {
+        let sui: CoinType = "0x2::sui::SUI".parse().unwrap();
+        assert_eq!(
+            sui.coin().unwrap(),
+            "0x2::coin::Coin<0x2::sui::SUI>".parse().unwrap()
+        );
+        assert!("0x2::coin::Coin<0x2::sui::SUI>"
+            .parse::<CoinType>()
+            .is_err());
+    }
+}
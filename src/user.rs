@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use move_types::functions::Arg;
 use std::collections::HashMap;
 use std::fmt;
@@ -7,9 +7,12 @@ use sui_graphql_client::query_types::ObjectFilter;
 use sui_graphql_client::{Client, PaginationFilter};
 use sui_sdk_types::{Address, ObjectData, ObjectId};
 use sui_transaction_builder::{Serialized, TransactionBuilder};
+use tokio::task::JoinSet;
 
 use crate::move_binding::{account_multisig as am, account_protocol as ap};
-use crate::utils;
+use crate::multisig::{LoadOptions, Multisig, OwnedObjectsLoad};
+use crate::proposals::intents::Deadlines;
+use crate::utils::{self, InputKind};
 
 pub struct User {
     pub sui_client: Arc<Client>,
@@ -17,6 +20,7 @@ pub struct User {
     pub id: Option<ObjectId>,
     pub profile: Profile,
     pub multisigs: Vec<MultisigPreview>,
+    pub accounts: Vec<AccountPreview>, // all account types, including multisigs
     pub invites: Vec<Invite>,
 }
 
@@ -32,6 +36,43 @@ pub struct MultisigPreview {
     pub name: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountKind {
+    Multisig,
+    // config type of other account.tech accounts
+    Other(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountPreview {
+    pub id: ObjectId,
+    pub kind: AccountKind,
+    pub name: Option<String>, // only decoded for multisigs
+}
+
+// state of a multisig for a wallet home screen, see User::aggregate_overview
+#[derive(Debug, Clone)]
+pub struct MultisigOverview {
+    pub id: Address,
+    pub name: String,
+    pub members: usize,
+    // intents waiting for the approval of the user
+    pub awaiting_approval: usize,
+    // SUI owned by the account in MIST, vaults excluded
+    pub sui_balance: u128,
+}
+
+// intent waiting for the approval of the user, see User::pending_approvals
+#[derive(Debug, Clone)]
+pub struct PendingApproval {
+    pub multisig_id: Address,
+    pub multisig_name: String,
+    pub key: String,
+    pub intent_type: String,
+    pub description: String,
+    pub deadlines: Deadlines,
+}
+
 #[derive(Debug, Clone)]
 pub struct Invite {
     pub id: ObjectId,
@@ -52,6 +93,7 @@ impl User {
                 avatar: "".to_string(),
             },
             multisigs: Vec::new(),
+            accounts: Vec::new(),
             invites: Vec::new(),
         };
         user.refresh().await?;
@@ -62,7 +104,16 @@ impl User {
         let user = self.fetch_user_object().await?;
         if let Some(user) = user {
             self.id = Some(user.id);
-            self.multisigs = self.fetch_previews(&user).await?;
+            self.accounts = self.fetch_accounts(&user).await?;
+            self.multisigs = self
+                .accounts
+                .iter()
+                .filter(|account| account.kind == AccountKind::Multisig)
+                .map(|account| MultisigPreview {
+                    id: account.id,
+                    name: account.name.clone().unwrap_or_default(),
+                })
+                .collect();
         }
 
         self.profile = self.fetch_profile().await?;
@@ -88,7 +139,10 @@ impl User {
 
         if let Some(object) = page.data().first() {
             if let ObjectData::Struct(move_struct) = object.data() {
-                let user: ap::user::User = bcs::from_bytes(move_struct.contents())?;
+                let user: ap::user::User = utils::decode(
+                    move_struct.contents(),
+                    Some(&move_struct.object_type().to_string()),
+                )?;
                 Ok(Some(user))
             } else {
                 Ok(None)
@@ -111,12 +165,15 @@ impl User {
             .map(|entry| entry.value.clone())
             .unwrap_or_default();
 
-        let objects = utils::get_objects(&self.sui_client, ids).await?;
+        // deleted multisigs are still referenced by the user object, they are skipped
+        let objects = utils::get_objects(&self.sui_client, ids).await?.found;
         let mut previews = Vec::new();
         for object in objects {
             if let ObjectData::Struct(move_struct) = object.data() {
-                let account: ap::account::Account<am::multisig::Multisig> =
-                    bcs::from_bytes(move_struct.contents())?;
+                let account: ap::account::Account<am::multisig::Multisig> = utils::decode(
+                    move_struct.contents(),
+                    Some(&move_struct.object_type().to_string()),
+                )?;
                 previews.push(MultisigPreview {
                     id: account.id,
                     name: account
@@ -133,6 +190,34 @@ impl User {
         Ok(previews)
     }
 
+    // enumerates every account type registered in the user object
+    pub async fn fetch_accounts(&self, user: &ap::user::User) -> Result<Vec<AccountPreview>> {
+        let multisig_type = format!(
+            "{}::multisig::Multisig",
+            &crate::ACCOUNT_MULTISIG_PACKAGE[2..]
+        );
+
+        let mut accounts = Vec::new();
+        for entry in user.accounts.contents.iter() {
+            if entry.key == multisig_type {
+                let previews = self.fetch_previews(user).await?;
+                accounts.extend(previews.into_iter().map(|preview| AccountPreview {
+                    id: preview.id,
+                    kind: AccountKind::Multisig,
+                    name: Some(preview.name),
+                }));
+            } else {
+                // config type unknown to the sdk, can't decode the account
+                accounts.extend(entry.value.iter().map(|id| AccountPreview {
+                    id: (*id).into(),
+                    kind: AccountKind::Other(entry.key.to_string()),
+                    name: None,
+                }));
+            }
+        }
+        Ok(accounts)
+    }
+
     pub async fn fetch_profile(&self) -> Result<Profile> {
         let username = self.sui_client.default_suins_name(self.address).await?;
         Ok(Profile {
@@ -152,7 +237,10 @@ impl User {
         let mut multisig_to_invite = HashMap::new();
         for object in invite_objects {
             if let ObjectData::Struct(move_struct) = object.data() {
-                let invite: ap::user::Invite = bcs::from_bytes(move_struct.contents())?;
+                let invite: ap::user::Invite = utils::decode(
+                    move_struct.contents(),
+                    Some(&move_struct.object_type().to_string()),
+                )?;
                 if invite.account_type
                     == format!(
                         "{}::multisig::Multisig",
@@ -164,17 +252,20 @@ impl User {
             }
         }
 
-        // get multisig objects
+        // get multisig objects, the invites to deleted multisigs are skipped
         let multisig_objects = utils::get_objects(
             &self.sui_client,
             multisig_to_invite.keys().cloned().collect(),
         )
-        .await?;
+        .await?
+        .found;
         let mut invites = Vec::new();
         for object in multisig_objects {
             if let ObjectData::Struct(move_struct) = object.data() {
-                let account: ap::account::Account<am::multisig::Multisig> =
-                    bcs::from_bytes(move_struct.contents())?;
+                let account: ap::account::Account<am::multisig::Multisig> = utils::decode(
+                    move_struct.contents(),
+                    Some(&move_struct.object_type().to_string()),
+                )?;
                 invites.push(Invite {
                     id: *multisig_to_invite.get(account.id.as_address()).unwrap(),
                     multisig_id: account.id,
@@ -193,6 +284,75 @@ impl User {
         Ok(invites)
     }
 
+    // loads the multisigs of the user concurrently without their owned objects,
+    // in the order of user.multisigs, a failure doesn't prevent loading the others
+    pub async fn load_multisigs(&self) -> Vec<Result<Multisig>> {
+        let options = LoadOptions {
+            owned_objects: OwnedObjectsLoad::Skip,
+        };
+        let mut tasks = JoinSet::new();
+        for (index, preview) in self.multisigs.iter().enumerate() {
+            let sui_client = self.sui_client.clone();
+            let id = *preview.id.as_address();
+            tasks.spawn(async move {
+                (
+                    index,
+                    Multisig::from_id_with_options(sui_client, id, options).await,
+                )
+            });
+        }
+
+        // stays an error if the task panicked
+        let mut multisigs = (0..self.multisigs.len())
+            .map(|_| Err(anyhow!("Multisig not loaded")))
+            .collect::<Vec<_>>();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((index, result)) = joined {
+                multisigs[index] = result;
+            }
+        }
+        multisigs
+    }
+
+    pub async fn aggregate_overview(&self) -> Vec<Result<MultisigOverview>> {
+        let mut overviews = Vec::new();
+        for multisig in self.load_multisigs().await {
+            overviews.push(match multisig {
+                Ok(multisig) => self.overview(&multisig).await,
+                Err(e) => Err(e),
+            });
+        }
+        overviews
+    }
+
+    // intents that the user can approve and hasn't, per multisig in the order of
+    // self.multisigs and the most urgent first, see sort_by_urgency to merge them
+    pub async fn pending_approvals(&self) -> Result<Vec<Result<Vec<PendingApproval>>>> {
+        let now = utils::clock_timestamp(&self.sui_client).await?;
+        Ok(self
+            .load_multisigs()
+            .await
+            .into_iter()
+            .map(|multisig| {
+                let multisig = multisig?;
+                let mut pending = multisig
+                    .awaiting_approval(self.address)
+                    .into_iter()
+                    .map(|intent| PendingApproval {
+                        multisig_id: multisig.id,
+                        multisig_name: multisig.name().to_string(),
+                        key: intent.key.clone(),
+                        intent_type: intent.type_.clone(),
+                        description: intent.description.clone(),
+                        deadlines: intent.deadlines(now),
+                    })
+                    .collect::<Vec<_>>();
+                sort_by_urgency(&mut pending);
+                Ok(pending)
+            })
+            .collect())
+    }
+
     pub async fn create_user(
         &self,
         builder: &mut TransactionBuilder,
@@ -269,29 +429,105 @@ impl User {
         Ok(())
     }
 
-    pub async fn join_multisig(&self, builder: &mut TransactionBuilder, multisig_id: Address) -> Result<()> {
-        let mut user = self.user_arg(builder, *self.id.unwrap().as_address()).await?;
+    pub async fn join_multisig(
+        &self,
+        builder: &mut TransactionBuilder,
+        multisig_id: Address,
+    ) -> Result<()> {
+        let mut user = if self.id.is_none() {
+            self.create_user(builder).await?
+        } else {
+            self.user_arg(builder, *self.id.unwrap().as_address())
+                .await?
+        };
         let multisig = self.multisig_arg(builder, multisig_id).await?;
+
         am::multisig::join(builder, user.borrow_mut(), multisig.borrow());
+
+        if self.id.is_none() {
+            self.transfer_user(builder, user).await?;
+        }
         Ok(())
     }
 
-    pub async fn leave_multisig(&self, builder: &mut TransactionBuilder, multisig_id: Address) -> Result<()> {
-        let mut user = self.user_arg(builder, *self.id.unwrap().as_address()).await?;
+    pub async fn leave_multisig(
+        &self,
+        builder: &mut TransactionBuilder,
+        multisig_id: Address,
+    ) -> Result<()> {
+        let user_id = self.id.ok_or(anyhow::anyhow!("User not found"))?;
+        if !self
+            .multisigs
+            .iter()
+            .any(|m| *m.id.as_address() == multisig_id)
+        {
+            return Err(anyhow::anyhow!(
+                "Multisig {} not in user object",
+                multisig_id
+            ));
+        }
+        let mut user = self.user_arg(builder, *user_id.as_address()).await?;
         let multisig = self.multisig_arg(builder, multisig_id).await?;
+
         am::multisig::leave(builder, user.borrow_mut(), multisig.borrow());
         Ok(())
     }
 
+    // ids must contain exactly the multisigs already in the user object
+    pub async fn reorder_multisigs(
+        &self,
+        builder: &mut TransactionBuilder,
+        multisig_ids: Vec<Address>,
+    ) -> Result<()> {
+        let user_id = self.id.ok_or(anyhow::anyhow!("User not found"))?;
+
+        let mut current = self
+            .multisigs
+            .iter()
+            .map(|m| *m.id.as_address())
+            .collect::<Vec<_>>();
+        let mut requested = multisig_ids.clone();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Err(anyhow::anyhow!(
+                "Reordered ids must match the multisigs of the user"
+            ));
+        }
+
+        let mut user = self.user_arg(builder, *user_id.as_address()).await?;
+        let ids_arg = builder.input(Serialized(&multisig_ids));
+        ap::user::reorder_accounts::<am::multisig::Multisig>(
+            builder,
+            user.borrow_mut(),
+            ids_arg.into(),
+        );
+        Ok(())
+    }
+
     // === Helpers ===
 
+    async fn overview(&self, multisig: &Multisig) -> Result<MultisigOverview> {
+        Ok(MultisigOverview {
+            id: multisig.id,
+            name: multisig.name().to_string(),
+            members: multisig.config.members.len(),
+            awaiting_approval: multisig.awaiting_approval(self.address).len(),
+            sui_balance: utils::get_balance(&self.sui_client, multisig.id, "0x2::sui::SUI").await?,
+        })
+    }
+
     pub async fn registry_arg(
         &self,
         builder: &mut TransactionBuilder,
     ) -> Result<Arg<ap::user::Registry>> {
-        let registry_input =
-            utils::get_object_as_input(&self.sui_client, Self::REGISTRY.parse()?).await?;
-        let registry_arg = builder.input(registry_input.by_mut()).into();
+        let registry_input = utils::get_object_as_input(
+            &self.sui_client,
+            Self::REGISTRY.parse()?,
+            InputKind::Shared { mutable: true },
+        )
+        .await?;
+        let registry_arg = builder.input(registry_input).into();
         Ok(registry_arg)
     }
 
@@ -300,7 +536,8 @@ impl User {
         builder: &mut TransactionBuilder,
         user_id: Address,
     ) -> Result<Arg<ap::user::User>> {
-        let user_input = utils::get_object_as_input(&self.sui_client, user_id).await?;
+        let user_input =
+            utils::get_object_as_input(&self.sui_client, user_id, InputKind::Owned).await?;
         let user_arg = builder.input(user_input).into();
         Ok(user_arg)
     }
@@ -310,8 +547,13 @@ impl User {
         builder: &mut TransactionBuilder,
         multisig_id: Address,
     ) -> Result<Arg<ap::account::Account<am::multisig::Multisig>>> {
-        let multisig_input = utils::get_object_as_input(&self.sui_client, multisig_id).await?;
-        let multisig_arg = builder.input(multisig_input.by_ref()).into();
+        let multisig_input = utils::get_object_as_input(
+            &self.sui_client,
+            multisig_id,
+            InputKind::Shared { mutable: false },
+        )
+        .await?;
+        let multisig_arg = builder.input(multisig_input).into();
         Ok(multisig_arg)
     }
 
@@ -320,7 +562,8 @@ impl User {
         builder: &mut TransactionBuilder,
         invite_id: Address,
     ) -> Result<Arg<ap::user::Invite>> {
-        let invite_input = utils::get_object_as_input(&self.sui_client, invite_id).await?;
+        let invite_input =
+            utils::get_object_as_input(&self.sui_client, invite_id, InputKind::Owned).await?;
         let invite_arg = builder.input(invite_input).into();
         Ok(invite_arg)
     }
@@ -333,7 +576,16 @@ impl std::fmt::Debug for User {
             .field("id", &self.id)
             .field("profile", &self.profile)
             .field("multisigs", &self.multisigs)
+            .field("accounts", &self.accounts)
             .field("invites", &self.invites)
             .finish()
     }
 }
+
+// the most urgent first, then the closest to expire
+pub fn sort_by_urgency(pending: &mut [PendingApproval]) {
+    pending.sort_by(|a, b| {
+        (a.deadlines.urgency, a.deadlines.until_expiry)
+            .cmp(&(b.deadlines.urgency, b.deadlines.until_expiry))
+    });
+}
@@ -1,11 +1,21 @@
 use anyhow::{anyhow, Result};
 use cynic::QueryBuilder;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use sui_crypto::SuiSigner;
 use sui_graphql_client::{
-    query_types::{MoveValue, ObjectFilter, ObjectsQuery, ObjectsQueryArgs},
-    Client, Direction, DynamicFieldOutput, PaginationFilter,
+    query_types::{MoveValue, ObjectFilter, ObjectsQuery, ObjectsQueryArgs, TransactionsFilter},
+    BcsName, Client, Direction, DynamicFieldOutput, PaginationFilter,
 };
-use sui_sdk_types::{Address, Object, Owner, framework::Coin};
-use sui_transaction_builder::unresolved::Input;
+use sui_sdk_types::{
+    framework::Coin, Address, Command, ExecutionStatus, Object, ObjectData, Owner, Transaction,
+    TransactionDigest, TransactionEffects, TransactionKind, TypeTag, UserSignature,
+};
+use sui_transaction_builder::{unresolved::Input, TransactionBuilder};
+
+use crate::coin_registry::Network;
+use crate::errors;
+use crate::move_binding::{account_extensions as ae, sui};
 
 pub async fn get_object(sui_client: &Client, id: Address) -> Result<Object> {
     sui_client
@@ -14,17 +24,55 @@ pub async fn get_object(sui_client: &Client, id: Address) -> Result<Object> {
         .ok_or(anyhow!("Object not found {}", id))
 }
 
-pub async fn get_object_as_input(sui_client: &Client, id: Address) -> Result<Input> {
-    let object = get_object(sui_client, id).await?;
-    let mut input = Input::from(&object);
+// how the transaction uses an object, checked against the owner of the object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    // owned by an address, shared (mutably) or immutable, from the owner
+    Infer,
+    Owned,
+    // sent to the address of an object, received by it in the transaction
+    Receiving,
+    Shared { mutable: bool },
+    // e.g. packages and frozen objects
+    Immutable,
+}
 
-    input = match object.owner() {
-        Owner::Address(_) => input.with_owned_kind(),
-        Owner::Object(_) => input.with_owned_kind(),
-        _ => input,
-    };
+impl InputKind {
+    // the kind to use for an object with this owner, never Infer
+    pub fn resolve(self, owner: &Owner) -> Result<Self> {
+        match (self, owner) {
+            (InputKind::Infer, Owner::Address(_)) => Ok(InputKind::Owned),
+            (InputKind::Infer, Owner::Shared(_)) => Ok(InputKind::Shared { mutable: true }),
+            (InputKind::Infer, Owner::Immutable) => Ok(InputKind::Immutable),
+            (InputKind::Owned | InputKind::Receiving, Owner::Address(_))
+            | (InputKind::Shared { .. }, Owner::Shared(_))
+            | (InputKind::Immutable, Owner::Immutable) => Ok(self),
+            (kind, owner) => Err(anyhow!("{:?} input but the owner is {:?}", kind, owner)),
+        }
+    }
+}
+
+pub async fn get_object_as_input(
+    sui_client: &Client,
+    id: Address,
+    kind: InputKind,
+) -> Result<Input> {
+    object_as_input(&get_object(sui_client, id).await?, kind)
+}
+
+pub fn object_as_input(object: &Object, kind: InputKind) -> Result<Input> {
+    let kind = kind
+        .resolve(object.owner())
+        .map_err(|e| anyhow!("Object {}: {}", object.object_id(), e))?;
 
-    Ok(input)
+    let input = Input::from(object);
+    Ok(match kind {
+        InputKind::Owned => input.with_owned_kind(),
+        InputKind::Receiving => input.with_receiving_kind(),
+        InputKind::Shared { mutable: true } => input.with_shared_kind().by_mut(),
+        InputKind::Shared { mutable: false } => input.with_shared_kind().by_ref(),
+        InputKind::Immutable | InputKind::Infer => input.with_immutable_kind(),
+    })
 }
 
 pub async fn get_owned_objects(
@@ -50,7 +98,7 @@ pub async fn get_owned_objects(
                     type_,
                     object_ids: None,
                 }),
-                filter
+                filter,
             )
             .await?;
         objects.extend(resp.data().iter().cloned());
@@ -62,12 +110,9 @@ pub async fn get_owned_objects(
     Ok(objects)
 }
 
-pub async fn get_owned_coins(
-    sui_client: &Client,
-    owner: Address,
-    type_: Option<&str>,
-) -> Result<Vec<Coin<'static>>> {
-    let mut coins = Vec::new();
+// objects of a type regardless of their owner
+pub async fn get_objects_by_type(sui_client: &Client, type_: &str) -> Result<Vec<Object>> {
+    let mut objects = Vec::new();
     let mut cursor = None;
     let mut has_next_page = true;
 
@@ -79,57 +124,204 @@ pub async fn get_owned_coins(
         };
 
         let resp = sui_client
-            .coins(
-                owner,
-                type_,
-                filter
+            .objects(
+                Some(ObjectFilter {
+                    owner: None,
+                    type_: Some(type_),
+                    object_ids: None,
+                }),
+                filter,
             )
             .await?;
-        coins.extend(resp.data().iter().cloned());
+        objects.extend(resp.data().iter().cloned());
 
         cursor = resp.page_info().end_cursor.clone();
         has_next_page = resp.page_info().has_next_page;
     }
 
-    Ok(coins)
+    Ok(objects)
 }
 
-pub async fn get_objects(
+// coins of any owner (address or object), all types if None
+pub async fn get_owned_coins(
     sui_client: &Client,
-    mut ids: Vec<Address>,
-) -> Result<Vec<Object>> {
-    let mut objects = Vec::new();
-    let mut cursor = None;
-    let mut has_next_page = true;
+    owner: Address,
+    type_: Option<&str>,
+) -> Result<Vec<Coin<'static>>> {
+    let mut pages = CoinPages::new(sui_client, owner, type_);
+    let mut coins = Vec::new();
+    while let Some(page) = pages.next_page().await? {
+        coins.extend(page);
+    }
+    Ok(coins)
+}
 
-    while has_next_page {
+// pages of 50 coins fetched on demand, to stop once enough coins are found
+pub struct CoinPages<'a> {
+    sui_client: &'a Client,
+    owner: Address,
+    type_: Option<String>,
+    cursor: Option<String>,
+    has_next_page: bool,
+}
+
+impl<'a> CoinPages<'a> {
+    pub fn new(sui_client: &'a Client, owner: Address, type_: Option<&str>) -> Self {
+        Self {
+            sui_client,
+            owner,
+            type_: type_.map(str::to_string),
+            cursor: None,
+            has_next_page: true,
+        }
+    }
+
+    // None once all the pages have been returned
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Coin<'static>>>> {
+        if !self.has_next_page {
+            return Ok(None);
+        }
         let filter = PaginationFilter {
             direction: Direction::Forward,
-            cursor: cursor.clone(),
+            cursor: self.cursor.clone(),
             limit: Some(50),
         };
 
-        let mut object_ids = Some(ids.clone());
-        if ids.len() > 50 {
-            object_ids = Some(ids.split_off(50));
+        let resp = self
+            .sui_client
+            .coins(self.owner, self.type_.as_deref(), filter)
+            .await?;
+        self.cursor = resp.page_info().end_cursor.clone();
+        self.has_next_page = resp.page_info().has_next_page;
+        Ok(Some(resp.data().to_vec()))
+    }
+}
+
+// coins of the type until their balance covers amount, fetching only the pages needed
+pub async fn get_coins_for_amount(
+    sui_client: &Client,
+    owner: Address,
+    type_: &str,
+    amount: u64,
+) -> Result<Vec<Coin<'static>>> {
+    let mut pages = CoinPages::new(sui_client, owner, Some(type_));
+    let mut coins = Vec::new();
+    let mut total = 0u64;
+    while let Some(page) = pages.next_page().await? {
+        for coin in page {
+            total = total.saturating_add(coin.balance());
+            coins.push(coin);
+            if total >= amount {
+                return Ok(coins);
+            }
         }
+    }
+    Err(anyhow!(
+        "Insufficient {} balance: {} < {}",
+        type_,
+        total,
+        amount
+    ))
+}
 
-        let resp = sui_client
-            .objects(
-                Some(ObjectFilter {
-                    object_ids,
-                    ..Default::default()
-                }),
-                filter
-            )
-            .await?;
-        objects.extend(resp.data().iter().cloned());
+// on-chain time in ms
+pub async fn clock_timestamp(sui_client: &Client) -> Result<u64> {
+    let clock_object = get_object(sui_client, *crate::CLOCK_OBJECT_ID).await?;
+    if let ObjectData::Struct(obj) = clock_object.data() {
+        let clock: sui::clock::Clock = bcs::from_bytes(obj.contents())
+            .map_err(|e| anyhow!("Failed to parse clock object: {}", e))?;
+        Ok(clock.timestamp_ms)
+    } else {
+        Err(anyhow!("Clock object data is missing"))
+    }
+}
 
-        cursor = resp.page_info().end_cursor.clone();
-        has_next_page = resp.page_info().has_next_page;
+// (name, address, version) of the packages verified by account.tech, from the Extensions object
+pub async fn verified_extensions(sui_client: &Client) -> Result<Vec<(String, Address, u64)>> {
+    let extensions_object = get_object(sui_client, *crate::EXTENSIONS_OBJECT_ID).await?;
+    if let ObjectData::Struct(obj) = extensions_object.data() {
+        let extensions: ae::extensions::Extensions = bcs::from_bytes(obj.contents())
+            .map_err(|e| anyhow!("Failed to parse extensions object: {}", e))?;
+        Ok(extensions
+            .inner
+            .iter()
+            .flat_map(|extension| {
+                extension
+                    .history
+                    .iter()
+                    .map(|history| (extension.name.to_string(), history.addr, history.version))
+            })
+            .collect())
+    } else {
+        Err(anyhow!("Extensions object data is missing"))
     }
+}
 
-    Ok(objects)
+// total balance from the endpoint, without fetching the coins
+pub async fn get_balance(sui_client: &Client, owner: Address, type_: &str) -> Result<u128> {
+    Ok(sui_client
+        .balance(owner, Some(type_))
+        .await?
+        .unwrap_or_default())
+}
+
+// objects found by get_objects in the order of the ids, and the ids that weren't returned
+// (deleted, wrapped or not indexed yet) to tell them apart from endpoint errors
+#[derive(Debug, Clone, Default)]
+pub struct FetchedObjects {
+    pub found: Vec<Object>,
+    pub missing: Vec<Address>,
+}
+
+impl FetchedObjects {
+    // fails with MissingObjects if some ids weren't returned
+    pub fn require_all(self) -> Result<Vec<Object>> {
+        if !self.missing.is_empty() {
+            return Err(errors::MultisigError::MissingObjects { ids: self.missing }.into());
+        }
+        Ok(self.found)
+    }
+}
+
+pub async fn get_objects(sui_client: &Client, ids: Vec<Address>) -> Result<FetchedObjects> {
+    let mut objects = HashMap::new();
+    for chunk in ids.chunks(50) {
+        let mut cursor = None;
+        let mut has_next_page = true;
+
+        while has_next_page {
+            let filter = PaginationFilter {
+                direction: Direction::Forward,
+                cursor: cursor.clone(),
+                limit: Some(50),
+            };
+
+            let resp = sui_client
+                .objects(
+                    Some(ObjectFilter {
+                        object_ids: Some(chunk.to_vec()),
+                        ..Default::default()
+                    }),
+                    filter,
+                )
+                .await?;
+            for object in resp.data() {
+                objects.insert(*object.object_id().as_address(), object.clone());
+            }
+
+            cursor = resp.page_info().end_cursor.clone();
+            has_next_page = resp.page_info().has_next_page;
+        }
+    }
+
+    let mut fetched = FetchedObjects::default();
+    for id in ids {
+        match objects.remove(&id) {
+            Some(object) => fetched.found.push(object),
+            None => fetched.missing.push(id),
+        }
+    }
+    Ok(fetched)
 }
 
 // gets `MoveValue`s from sui-graphql-client (to get the fields json)
@@ -196,4 +388,317 @@ pub async fn get_dynamic_fields(
     }
 
     Ok(objects)
-}
\ No newline at end of file
+}
+
+pub async fn get_dynamic_field(
+    sui_client: &Client,
+    id: Address,
+    name_type: TypeTag,
+    name_bcs: Vec<u8>,
+) -> Result<Option<DynamicFieldOutput>> {
+    Ok(sui_client
+        .dynamic_field(id, name_type, BcsName(name_bcs))
+        .await?)
+}
+
+// (digest, sender) of the transactions that created, mutated or deleted the object, oldest first
+pub async fn get_transactions_changing(
+    sui_client: &Client,
+    id: Address,
+) -> Result<Vec<(TransactionDigest, Address)>> {
+    let mut transactions = Vec::new();
+    let mut cursor = None;
+    let mut has_next_page = true;
+
+    while has_next_page {
+        let filter = PaginationFilter {
+            direction: Direction::Forward,
+            cursor: cursor.clone(),
+            limit: Some(50),
+        };
+        let tx_filter = TransactionsFilter {
+            changed_object: Some(id),
+            ..Default::default()
+        };
+
+        let resp = sui_client.transactions(Some(tx_filter), filter).await?;
+        transactions.extend(
+            resp.data()
+                .iter()
+                .map(|signed| (signed.transaction.digest(), signed.transaction.sender)),
+        );
+
+        cursor = resp.page_info().end_cursor.clone();
+        has_next_page = resp.page_info().has_next_page;
+    }
+
+    Ok(transactions)
+}
+
+// whether the last transaction changing the object confirmed an intent execution, tells an
+// executed intent from a deleted one once its dynamic field is removed
+pub async fn last_change_executed(sui_client: &Client, id: Address) -> Result<bool> {
+    let filter = PaginationFilter {
+        direction: Direction::Backward,
+        cursor: None,
+        limit: Some(1),
+    };
+    let tx_filter = TransactionsFilter {
+        changed_object: Some(id),
+        ..Default::default()
+    };
+
+    let resp = sui_client.transactions(Some(tx_filter), filter).await?;
+    let signed = resp
+        .data()
+        .last()
+        .ok_or(anyhow!("No transaction changed {}", id))?;
+    let TransactionKind::ProgrammableTransaction(ptb) = &signed.transaction.kind else {
+        return Ok(false);
+    };
+    Ok(ptb.commands.iter().any(|command| {
+        matches!(command, Command::MoveCall(call)
+            if call.module.as_str() == "account" && call.function.as_str() == "confirm_execution")
+    }))
+}
+
+pub const DEFAULT_GAS_BUDGET: u64 = 100_000_000;
+pub const MAINNET_GAS_BUDGET: u64 = 50_000_000;
+// max number of gas objects in a transaction
+const MAX_GAS_OBJECTS: usize = 256;
+
+// budget used when none is given, lower on mainnet where the gas is paid in real SUI
+pub fn default_gas_budget(network: Network) -> u64 {
+    match network {
+        Network::Mainnet => MAINNET_GAS_BUDGET,
+        Network::Testnet | Network::Other => DEFAULT_GAS_BUDGET,
+    }
+}
+
+// gas price of the current epoch, transactions with a lower price are rejected
+pub async fn reference_gas_price(sui_client: &Client) -> Result<u64> {
+    sui_client
+        .reference_gas_price(None)
+        .await?
+        .ok_or(anyhow!("Reference gas price not available"))
+}
+
+// builder with enough SUI coins of the sender as gas for DEFAULT_GAS_BUDGET, the network isn't
+// known here, see MultisigClient::init_tx for the default budget of the network of the client
+pub async fn init_tx(sui_client: &Client, sender: Address) -> Result<TransactionBuilder> {
+    init_tx_with_budget(sui_client, sender, DEFAULT_GAS_BUDGET).await
+}
+
+pub async fn init_tx_with_budget(
+    sui_client: &Client,
+    sender: Address,
+    budget: u64,
+) -> Result<TransactionBuilder> {
+    let mut builder = TransactionBuilder::new();
+    let gas = prepare_gas(sui_client, sender, budget, &[]).await?;
+
+    builder.add_gas_objects(gas);
+    builder.set_gas_budget(budget);
+    builder.set_gas_price(reference_gas_price(sui_client).await?);
+    builder.set_sender(sender);
+
+    Ok(builder)
+}
+
+// selects the largest SUI coins of the address until the budget is covered,
+// the coins are merged into the first one when the transaction is executed
+// coins used as inputs elsewhere in the PTB must be excluded
+pub async fn prepare_gas(
+    sui_client: &Client,
+    address: Address,
+    budget: u64,
+    exclude: &[Address],
+) -> Result<Vec<Input>> {
+    let mut coins = get_owned_coins(sui_client, address, Some("0x2::coin::Coin<0x2::sui::SUI>"))
+        .await?
+        .into_iter()
+        .filter(|coin| !exclude.contains(&(*coin.id()).into()))
+        .collect::<Vec<_>>();
+    coins.sort_by(|a, b| b.balance().cmp(&a.balance()));
+
+    let mut selected = Vec::new();
+    let mut total = 0;
+    for coin in coins.into_iter().take(MAX_GAS_OBJECTS) {
+        if total >= budget && !selected.is_empty() {
+            break;
+        }
+        total += coin.balance();
+        selected.push((*coin.id()).into());
+    }
+    if total < budget {
+        return Err(anyhow!(
+            "Insufficient SUI for gas: {} available, {} needed",
+            total,
+            budget
+        ));
+    }
+
+    let mut gas = Vec::new();
+    for id in selected {
+        gas.push(Input::from(&get_object(sui_client, id).await?).with_owned_kind());
+    }
+    Ok(gas)
+}
+
+// signs and executes the transaction then waits for it to be finalized
+pub async fn execute_tx<S: SuiSigner>(
+    sui_client: &Client,
+    builder: TransactionBuilder,
+    signer: &S,
+) -> Result<TransactionEffects> {
+    let tx = builder.finish()?;
+    let sig = signer.sign_transaction(&tx)?;
+    submit_tx(sui_client, &tx, vec![sig]).await
+}
+
+// for transactions signed out of band (e.g. native multisig partial signatures)
+pub async fn submit_tx(
+    sui_client: &Client,
+    tx: &Transaction,
+    signatures: Vec<UserSignature>,
+) -> Result<TransactionEffects> {
+    let effects = sui_client
+        .execute_tx(signatures, tx)
+        .await?
+        .ok_or(anyhow!("No effects returned for {}", tx.digest()))?;
+    while sui_client.transaction(tx.digest()).await?.is_none() {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+
+    check_status(sui_client, &effects).await?;
+    Ok(effects)
+}
+
+// runs the transaction without executing it, aborts are decoded the same way
+pub async fn dry_run_tx(sui_client: &Client, tx: &Transaction) -> Result<TransactionEffects> {
+    let result = sui_client.dry_run_tx(tx, None).await?;
+    if let Some(error) = result.error {
+        return Err(anyhow!("Dry run failed: {}", error));
+    }
+    let effects = result
+        .effects
+        .ok_or(anyhow!("No effects returned for {}", tx.digest()))?;
+    check_status(sui_client, &effects).await?;
+    Ok(effects)
+}
+
+async fn check_status(sui_client: &Client, effects: &TransactionEffects) -> Result<()> {
+    if let ExecutionStatus::Failure { error, command } = effects.status() {
+        if let Some(abort) = errors::decode_abort(sui_client, error).await {
+            return Err(abort.into());
+        }
+        return Err(anyhow!(
+            "Transaction failed: {:?} (command {:?})",
+            error,
+            command
+        ));
+    }
+    Ok(())
+}
+
+// decodes a move value with the bindings, declared_type is the type reported by the endpoint.
+// the error only reports what is known: the struct names differ (rust paths can't be compared
+// to move paths, so only the name is checked) or the bytes don't fit the binding layout,
+// which field drifted can't be told from the bytes
+pub fn decode<T: DeserializeOwned>(bytes: &[u8], declared_type: Option<&str>) -> Result<T> {
+    let expected = std::any::type_name::<T>();
+    let mismatch = |reason: String| errors::MultisigError::SchemaMismatch {
+        type_: declared_type.unwrap_or(expected).to_string(),
+        expected: expected.to_string(),
+        reason,
+    };
+
+    if let Some(declared_type) = declared_type {
+        if struct_name(declared_type) != struct_name(expected) {
+            return Err(mismatch(format!(
+                "struct {} is not the bound struct {}",
+                struct_name(declared_type),
+                struct_name(expected)
+            ))
+            .into());
+        }
+    }
+
+    bcs::from_bytes(bytes).map_err(|e| {
+        let reason = match e {
+            bcs::Error::Eof => "the bytes end before the binding layout".to_string(),
+            bcs::Error::RemainingInput => "bytes left after the binding layout".to_string(),
+            e => e.to_string(),
+        };
+        mismatch(reason).into()
+    })
+}
+
+// name of 0x2::coin::Coin<0x2::sui::SUI> or of a rust path
+fn struct_name(type_: &str) -> &str {
+    let path = type_.split('<').next().unwrap_or(type_);
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MultisigError;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Fees {
+        amount: u64,
+    }
+
+    #[test]
+    fn decode_reports_schema_mismatch() {
+        let bytes = bcs::to_bytes(&7u64).unwrap();
+        let fees: Fees = decode(&bytes, Some("0x1::fees::Fees")).unwrap();
+        assert_eq!(fees.amount, 7);
+
+        let schema_error = |result: Result<Fees>| {
+            let error = result.unwrap_err();
+            let Some(MultisigError::SchemaMismatch { type_, reason, .. }) = error.downcast_ref()
+            else {
+                panic!("unexpected error {}", error);
+            };
+            (type_.clone(), reason.clone())
+        };
+        let (type_, reason) = schema_error(decode(&bytes[..4], Some("0x1::fees::Fees")));
+        assert_eq!(type_, "0x1::fees::Fees");
+        assert!(reason.contains("end before"));
+        let (_, reason) = schema_error(decode(&[0; 9], None));
+        assert!(reason.contains("bytes left"));
+        let (_, reason) = schema_error(decode(&bytes, Some("0x1::fees::Config<u64>")));
+        assert!(reason.contains("Config is not the bound struct Fees"));
+    }
+
+    #[test]
+    fn resolve_input_kinds() {
+        let address = Owner::Address(Address::ZERO);
+        assert_eq!(
+            InputKind::Infer.resolve(&address).unwrap(),
+            InputKind::Owned
+        );
+        assert_eq!(
+            InputKind::Infer.resolve(&Owner::Shared(1)).unwrap(),
+            InputKind::Shared { mutable: true }
+        );
+        assert_eq!(
+            InputKind::Infer.resolve(&Owner::Immutable).unwrap(),
+            InputKind::Immutable
+        );
+        assert_eq!(
+            InputKind::Receiving.resolve(&address).unwrap(),
+            InputKind::Receiving
+        );
+        assert!(InputKind::Shared { mutable: false }
+            .resolve(&address)
+            .is_err());
+        assert!(InputKind::Owned.resolve(&Owner::Immutable).is_err());
+        assert!(InputKind::Infer
+            .resolve(&Owner::Object(Address::ZERO.into()))
+            .is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use sui_sdk_types::Address;
+
+use crate::multisig::Multisig;
+use crate::utils;
+
+// tracks a proposal from its request to its execution or cleanup,
+// the state is saved between runs so automation tools can resume where they stopped
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkflowStage {
+    // collecting approvals
+    Pending,
+    // approved, waiting for the next execution time
+    Approved,
+    Executable,
+    // can only be deleted
+    Expired,
+    Executed,
+    // deleted before being executed
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NextAction {
+    // members to ping, see Intent::pending_approvers
+    Approve { pending_approvers: Vec<String> },
+    WaitUntil(u64),
+    Execute,
+    Delete,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalWorkflow {
+    pub multisig: Address,
+    pub key: String,
+    pub intent_type: String,
+    pub stage: WorkflowStage,
+    pub approved: Vec<Address>,
+    pub pending_approvers: Vec<String>,
+    // first remaining execution time
+    pub next_execution: Option<u64>,
+    pub expiration_time: Option<u64>,
+    // executions seen since the workflow was created
+    pub executions: usize,
+}
+
+// what the workflow needs from the intent, None if it isn't in the multisig anymore
+#[derive(Debug, Clone)]
+struct IntentState {
+    approved: bool,
+    execution_times: Vec<u64>,
+    expiration_time: u64,
+}
+
+impl ProposalWorkflow {
+    // to be created once the request transaction is executed
+    pub fn new(multisig: Address, key: &str, intent_type: &str) -> Self {
+        Self {
+            multisig,
+            key: key.to_string(),
+            intent_type: intent_type.to_string(),
+            stage: WorkflowStage::Pending,
+            approved: vec![],
+            pending_approvers: vec![],
+            next_execution: None,
+            expiration_time: None,
+            executions: 0,
+        }
+    }
+
+    // updates the stage from a freshly fetched multisig, now is in ms
+    pub async fn sync(&mut self, multisig: &Multisig, now: u64) -> Result<WorkflowStage> {
+        let intents = multisig
+            .intents
+            .as_ref()
+            .ok_or(anyhow!("Intents not loaded"))?;
+        let intent = intents.get_intent(&self.key);
+
+        let state = intent.map(|intent| {
+            self.approved = intent.outcome.approved.clone();
+            self.pending_approvers = intent
+                .pending_approvers(&multisig.config)
+                .into_iter()
+                .map(|member| member.address)
+                .collect();
+            IntentState {
+                approved: multisig.is_approved(intent),
+                execution_times: intent.execution_times.clone(),
+                expiration_time: intent.expiration_time,
+            }
+        });
+        // removed since the last sync, executed or deleted depending on the removing transaction
+        let executed = match self.stage {
+            WorkflowStage::Executed | WorkflowStage::Removed => false,
+            _ if state.is_none() => {
+                utils::last_change_executed(&multisig.sui_client, intents.field_id(&self.key)?)
+                    .await?
+            }
+            _ => false,
+        };
+        self.apply(state, executed, now);
+        Ok(self.stage)
+    }
+
+    pub fn next_action(&self) -> NextAction {
+        match self.stage {
+            WorkflowStage::Pending => NextAction::Approve {
+                pending_approvers: self.pending_approvers.clone(),
+            },
+            WorkflowStage::Approved => match self.next_execution {
+                Some(time) => NextAction::WaitUntil(time),
+                None => NextAction::Done,
+            },
+            WorkflowStage::Executable => NextAction::Execute,
+            WorkflowStage::Expired => NextAction::Delete,
+            WorkflowStage::Executed | WorkflowStage::Removed => NextAction::Done,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next_action() == NextAction::Done
+    }
+
+    // === Persistence ===
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    // === Helpers ===
+
+    // executed is only read when the intent has been removed
+    fn apply(&mut self, state: Option<IntentState>, executed: bool, now: u64) {
+        let Some(state) = state else {
+            self.stage = if executed || self.stage == WorkflowStage::Executed {
+                WorkflowStage::Executed
+            } else {
+                WorkflowStage::Removed
+            };
+            self.next_execution = None;
+            return;
+        };
+
+        let next_execution = state.execution_times.first().copied();
+        if self.next_execution.is_some() && next_execution != self.next_execution {
+            self.executions += 1;
+        }
+        self.next_execution = next_execution;
+        self.expiration_time = Some(state.expiration_time);
+
+        self.stage = if now >= state.expiration_time {
+            WorkflowStage::Expired
+        } else if !state.approved {
+            WorkflowStage::Pending
+        } else if next_execution.is_some_and(|time| time <= now) {
+            WorkflowStage::Executable
+        } else {
+            WorkflowStage::Approved
+        };
+    }
+}
+
+impl fmt::Display for NextAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NextAction::Approve { pending_approvers } => {
+                write!(f, "approve (pending: {})", pending_approvers.join(", "))
+            }
+            NextAction::WaitUntil(time) => write!(f, "wait until {}", time),
+            NextAction::Execute => write!(f, "execute"),
+            NextAction::Delete => write!(f, "delete"),
+            NextAction::Done => write!(f, "done"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(approved: bool, execution_times: Vec<u64>) -> Option<IntentState> {
+        Some(IntentState {
+            approved,
+            execution_times,
+            expiration_time: 1_000,
+        })
+    }
+
+    #[test]
+    fn stages_follow_the_intent() {
+        let mut workflow = ProposalWorkflow::new(Address::ZERO, "key", "MintAndTransfer");
+
+        workflow.apply(state(false, vec![100, 200]), false, 50);
+        assert_eq!(workflow.stage, WorkflowStage::Pending);
+        workflow.apply(state(true, vec![100, 200]), false, 50);
+        assert_eq!(workflow.next_action(), NextAction::WaitUntil(100));
+        workflow.apply(state(true, vec![100, 200]), false, 150);
+        assert_eq!(workflow.next_action(), NextAction::Execute);
+        workflow.apply(state(true, vec![200]), false, 250);
+        assert_eq!(workflow.executions, 1);
+        assert_eq!(workflow.stage, WorkflowStage::Executable);
+        workflow.apply(None, true, 300);
+        assert_eq!(workflow.stage, WorkflowStage::Executed);
+        assert!(workflow.is_done());
+
+        let mut expired = ProposalWorkflow::new(Address::ZERO, "key", "MintAndTransfer");
+        expired.apply(state(false, vec![100]), false, 1_000);
+        assert_eq!(expired.next_action(), NextAction::Delete);
+        expired.apply(None, false, 1_100);
+        assert_eq!(expired.stage, WorkflowStage::Removed);
+
+        // approved and executed between two syncs
+        let mut executed = ProposalWorkflow::new(Address::ZERO, "key", "MintAndTransfer");
+        executed.apply(state(true, vec![100]), false, 50);
+        assert_eq!(executed.stage, WorkflowStage::Approved);
+        executed.apply(None, true, 150);
+        assert_eq!(executed.stage, WorkflowStage::Executed);
+    }
+
+    #[test]
+    fn roundtrip_json() {
+        let workflow = ProposalWorkflow::new(Address::ZERO, "key", "MintAndTransfer");
+        let json = serde_json::to_string(&workflow).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ProposalWorkflow>(&json).unwrap(),
+            workflow
+        );
+    }
+}
@@ -67,7 +67,10 @@ async fn test_config_multisig_intent() {
     {
         let (pk, mut builder) = init_tx(client.sui()).await;
         let address = pk.public_key().derive_address();
-        client.approve_intent(&mut builder, "config_multisig").await.unwrap();
+        client
+            .approve_intent(&mut builder, "config_multisig", address)
+            .await
+            .unwrap();
         execute_tx(client.sui(), pk, builder).await;
         // check results
         client.refresh().await.unwrap();
@@ -1,9 +1,11 @@
 use base64ct::{Base64, Encoding};
 use sui_crypto::ed25519::Ed25519PrivateKey;
 use sui_crypto::SuiSigner;
-use sui_graphql_client::{Client, PaginationFilter};
+use sui_graphql_client::Client;
 use sui_sdk_types::{Address, ExecutionStatus, ObjectIn, ObjectOut, TransactionEffects};
-use sui_transaction_builder::{unresolved::Input, TransactionBuilder};
+use sui_transaction_builder::TransactionBuilder;
+
+use account_multisig_sdk::utils;
 
 /// Helper function to setup a transaction builder with a gas object and a sender address.
 pub async fn init_tx(sui_client: &Client) -> (Ed25519PrivateKey, TransactionBuilder) {
@@ -14,31 +16,7 @@ pub async fn init_tx(sui_client: &Client) -> (Ed25519PrivateKey, TransactionBuil
     );
     let address = pk.public_key().derive_address();
 
-    let mut builder = TransactionBuilder::new();
-
-    let gas_coin = sui_client
-        .coins(
-            address,
-            Some("0x2::coin::Coin<0x2::sui::SUI>"),
-            PaginationFilter::default(),
-        )
-        .await
-        .unwrap()
-        .data()
-        .first()
-        .unwrap()
-        .to_owned();
-    let gas_input: Input = (&sui_client
-        .object(gas_coin.id().to_owned().into(), None)
-        .await
-        .unwrap()
-        .unwrap())
-        .into();
-
-    builder.add_gas_objects(vec![gas_input.with_owned_kind()]);
-    builder.set_gas_budget(100000000);
-    builder.set_gas_price(1000);
-    builder.set_sender(address);
+    let builder = utils::init_tx(sui_client, address).await.unwrap();
 
     (pk, builder)
 }